@@ -10,6 +10,17 @@ pub const CONFIG_FILE_NAME: &str = "conf.json";
 pub const CONFIG_VERSION: u64 = 7;
 pub const CONFIG_MIN_VERSION: u64 = 3;
 
+/// 缺省语言：`title` 与 `statement.md` 所用的语言
+pub const DEFAULT_LANG: &str = "zh-cn";
+
+/// 取指定语言的标题，未设置时回落到缺省标题
+fn localized<'a>(titles: &'a IndexMap<String, String>, title: &'a str, lang: &str) -> &'a str {
+    if lang == DEFAULT_LANG {
+        return title;
+    }
+    titles.get(lang).map_or(title, String::as_str)
+}
+
 pub mod contest;
 pub mod contestday;
 pub mod lang;
@@ -126,8 +137,11 @@ pub fn load_config(ctx: &mut LoadContext, path: &Path) -> Result<Option<Config>>
             // TODO：总觉得不对劲
             problemconfig.use_pretest = dayconfig.use_pretest.or(config.use_pretest);
             problemconfig.noi_style = dayconfig.noi_style.or(config.noi_style);
-            problemconfig.file_io = if problemconfig.problem_type == ProblemType::Interactive {
-                // 交互强制使用 Stdio
+            problemconfig.file_io = if matches!(
+                problemconfig.problem_type,
+                ProblemType::Interactive | ProblemType::Communication
+            ) {
+                // 交互、通信强制使用 Stdio
                 Some(false)
             } else {
                 None
@@ -14,6 +14,9 @@ pub struct ContestConfig {
     pub name: String,
     pub subdir: Vec<String>,
     pub title: String,
+    /// 其他语言的标题，键为语言（如 `en`）
+    #[serde(file(default, skip_serializing_if = "IndexMap::is_empty"))]
+    pub titles: IndexMap<String, String>,
     #[serde(file(rename = "short title"), full(rename = "short title"))]
     pub short_title: String,
     #[serde(file(default, skip_serializing_if = "Option::is_none"))]
@@ -44,7 +47,7 @@ impl ContestConfig {
 
         if version < CONFIG_MIN_VERSION {
             bail!(
-                "配置文件版本过低，可能是 Tuack 的配置文件。请迁移到 Tuack-NG 配置文件格式再使用。"
+                "配置文件版本过低，可能是 Tuack 的配置文件。请使用 `tuack-ng import tuack` 导入后再使用。"
             );
         }
 
@@ -87,6 +90,11 @@ impl ContestConfig {
         Ok(config)
     }
 
+    /// 指定语言的标题
+    pub fn localized_title(&self, lang: &str) -> &str {
+        super::localized(&self.titles, &self.title, lang)
+    }
+
     pub fn save(&self) -> Result<String> {
         Ok(serde_json::to_string_pretty(&AsSerde::<
             ContestConfig,
@@ -14,6 +14,9 @@ pub struct ContestDayConfig {
     pub name: String,
     pub subdir: Vec<String>,
     pub title: String,
+    /// 其他语言的标题，键为语言（如 `en`）
+    #[serde(file(default, skip_serializing_if = "IndexMap::is_empty"))]
+    pub titles: IndexMap<String, String>,
     pub compile: IndexMap<String, String>,
     #[serde(file(rename = "start time"), full(rename = "start time"))]
     pub start_time: Option<[u32; 6]>,
@@ -47,7 +50,7 @@ impl ContestDayConfig {
         // 检查版本
         if version < CONFIG_MIN_VERSION {
             bail!(
-                "配置文件版本过低，可能是 Tuack 的配置文件。请迁移到 Tuack-NG 配置文件格式再使用。"
+                "配置文件版本过低，可能是 Tuack 的配置文件。请使用 `tuack-ng import tuack` 导入后再使用。"
             );
         }
 
@@ -100,6 +103,11 @@ impl ContestDayConfig {
         Ok(config)
     }
 
+    /// 指定语言的标题
+    pub fn localized_title(&self, lang: &str) -> &str {
+        super::localized(&self.titles, &self.title, lang)
+    }
+
     pub fn save(&self) -> Result<String> {
         Ok(serde_json::to_string_pretty(&AsSerde::<
             ContestDayConfig,
@@ -8,6 +8,28 @@ pub struct Language {
     pub compiler: Option<Compiler>,
     /// 运行器 (None 直接运行)
     pub runner: Option<Runner>,
+    /// 时间限制倍率，评测该语言时题目时间限制乘以此值
+    #[serde(
+        rename = "time multiplier",
+        default = "default_multiplier",
+        skip_serializing_if = "is_default_multiplier"
+    )]
+    pub time_multiplier: f64,
+    /// 空间限制倍率
+    #[serde(
+        rename = "memory multiplier",
+        default = "default_multiplier",
+        skip_serializing_if = "is_default_multiplier"
+    )]
+    pub memory_multiplier: f64,
+}
+
+fn default_multiplier() -> f64 {
+    1.0
+}
+
+fn is_default_multiplier(value: &f64) -> bool {
+    *value == 1.0
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
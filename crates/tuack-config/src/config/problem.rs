@@ -1,3 +1,4 @@
+use tuack_lib::utils::compiler::TimeMode;
 use tuack_lib::utils::testlib::Arg;
 use crate::{
     config::{CONFIG_MIN_VERSION, CONFIG_VERSION, migrate::base::MIGRATERS, msgs::LoadContext},
@@ -18,6 +19,12 @@ pub struct ProblemRuntime {
     pub data: Vec<ExpandedDataItem>,
     /// Subtask 配置（展开后）
     pub subtasks: BTreeMap<u32, SubtaskItem>,
+    /// 预测试数据（继承全局参数后，尚未展开）
+    pub inherited_pretest: Vec<DataItem>,
+    /// 预测试数据（展开后）
+    pub pretest: Vec<ExpandedDataItem>,
+    /// 预测试 subtask（展开后，只含有 pretest 的 subtask）
+    pub pretest_subtasks: BTreeMap<u32, SubtaskItem>,
 }
 
 #[derive(Debug, Clone, DeserializeMany, SerializeMany)]
@@ -36,9 +43,26 @@ pub struct ProblemConfig {
     pub name: String,
     /// 题目标题
     pub title: String,
+    /// 其他语言的标题，键为语言（如 `en`）
+    #[serde(file(default, skip_serializing_if = "IndexMap::is_empty"))]
+    pub titles: IndexMap<String, String>,
     /// 时间限制
     #[serde(file(rename = "time limit"), full(rename = "time limit"))]
     pub time_limit: f64,
+    /// 时间限制所依据的计时方式（`wall` 或 `cpu`），缺省为 `wall`；沙箱中总按 CPU 时间判定
+    #[serde(file(rename = "time mode", default), full(rename = "time mode"))]
+    pub time_mode: TimeMode,
+    /// 墙钟时间上限，缺省为时间限制的两倍再加一秒；
+    /// 测试点、subtask 或语言放宽时间限制时按同一比例放宽
+    #[serde(
+        file(
+            rename = "wall time limit",
+            default,
+            skip_serializing_if = "Option::is_none"
+        ),
+        full(rename = "wall time limit")
+    )]
+    pub wall_time_limit: Option<f64>,
     /// 空间限制
     #[serde(file(rename = "memory limit"), full(rename = "memory limit"))]
     pub memory_limit: ByteSize,
@@ -50,6 +74,9 @@ pub struct ProblemConfig {
     /// 交互
     #[serde(file(default, skip_serializing_if = "Option::is_none"))]
     pub interactive: Option<InteractiveConfig>,
+    /// 通信
+    #[serde(file(default, skip_serializing_if = "Option::is_none"))]
+    pub communication: Option<CommunicationConfig>,
     /// 生成器配置
     #[serde(file(default))]
     pub generator: Option<GeneratorConfigPair>,
@@ -62,7 +89,13 @@ pub struct ProblemConfig {
     pub data: Vec<DataItem>,
     /// Subtask 配置 (原始)
     #[serde(file(default), full(rename = "orig_subtasks"))]
-    pub subtasks: BTreeMap<u32, ScorePolicy>,
+    pub subtasks: BTreeMap<u32, SubtaskConfig>,
+    /// 预测试数据 (原始)，存放于 `pre/`，格式与 `data` 相同，subtask 共用 `subtasks` 的评分策略
+    #[serde(
+        file(default, skip_serializing_if = "Vec::is_empty"),
+        full(rename = "orig_pretest")
+    )]
+    pub pretest: Vec<DataItem>,
     /// 测试用例
     #[serde(file(default, skip_serializing_if = "IndexMap::is_empty"))]
     pub tests: IndexMap<String, TestCase>,
@@ -73,7 +106,7 @@ pub struct ProblemConfig {
     #[serde(file(default))]
     pub validator: Option<ValidatorConfigPair>,
 
-    /// 是否有 pretest，目前没有用途
+    /// 渲染时是否介绍 pretest，继承自比赛日与比赛配置
     #[serde(file(skip))]
     pub use_pretest: Option<bool>,
     /// 是否是 NOI 风格
@@ -91,6 +124,201 @@ pub struct ProblemConfig {
     pub runtime: ProblemRuntime,
 }
 
+/// 数据点继承全局参数（尚未展开）
+fn inherit_args(args: &IndexMap<String, Arg>, items: &[DataItem]) -> Vec<DataItem> {
+    items
+        .iter()
+        .map(|data| {
+            let mut merged = args.clone();
+            let mut item = data.clone();
+            match &mut item {
+                DataItem::Single(item) => {
+                    merged.extend(item.orig_args.clone());
+                    item.orig_args = merged;
+                }
+                DataItem::Bundle(item) => {
+                    merged.extend(item.orig_args.clone());
+                    item.orig_args = merged;
+                }
+            }
+            item
+        })
+        .collect()
+}
+
+/// 测试点的实际限制：测试点自身 > 所属 subtask > 题目
+fn resolve_limits(
+    config: &ProblemConfig,
+    subtask: u32,
+    time_limit: Option<f64>,
+    memory_limit: Option<ByteSize>,
+) -> (f64, ByteSize) {
+    let subtask = config.subtasks.get(&subtask);
+    (
+        time_limit
+            .or(subtask.and_then(|s| s.time_limit))
+            .unwrap_or(config.time_limit),
+        memory_limit
+            .or(subtask.and_then(|s| s.memory_limit))
+            .unwrap_or(config.memory_limit),
+    )
+}
+
+/// 展开数据点（基于继承后的数据）
+fn expand_data_items(inherited: &[DataItem], config: &ProblemConfig) -> Vec<ExpandedDataItem> {
+    let dmk = config.dmk;
+    let mut expanded = vec![];
+    for data in inherited {
+        match data {
+            DataItem::Single(item) => {
+                let (time_limit, memory_limit) =
+                    resolve_limits(config, item.subtask, item.time_limit, item.memory_limit);
+                expanded.push(ExpandedDataItem {
+                    id: item.id,
+                    score: item.score,
+                    subtask: item.subtask,
+                    input: item
+                        .input
+                        .clone()
+                        .unwrap_or_else(|| format!("{}.in", item.id)),
+                    output: item
+                        .output
+                        .clone()
+                        .unwrap_or_else(|| format!("{}.ans", item.id)),
+                    orig_args: item.orig_args.clone(),
+                    args: item.orig_args.clone(),
+                    dmk: item.dmk.unwrap_or(dmk),
+                    command: item.command.clone(),
+                    time_limit,
+                    memory_limit,
+                });
+            }
+            DataItem::Bundle(item) => {
+                let (time_limit, memory_limit) =
+                    resolve_limits(config, item.subtask, item.time_limit, item.memory_limit);
+                for id in &item.id {
+                    expanded.push(ExpandedDataItem {
+                        id: *id as u32,
+                        score: item.score,
+                        subtask: item.subtask,
+                        input: format!("{}.in", id),
+                        output: format!("{}.ans", id),
+                        orig_args: item.orig_args.clone(),
+                        args: item.orig_args.clone(),
+                        dmk: item.dmk.unwrap_or(dmk),
+                        command: item.command.clone(),
+                        time_limit,
+                        memory_limit,
+                    });
+                }
+            }
+        }
+    }
+    expanded
+}
+
+/// 展开 subtask 并计算每个 subtask 的最大分值；`kind` 用于警告中称呼数据点
+fn expand_subtasks(
+    ctx: &mut LoadContext,
+    subtasks: &BTreeMap<u32, SubtaskConfig>,
+    data: &[ExpandedDataItem],
+    kind: &str,
+) -> BTreeMap<u32, SubtaskItem> {
+    let mut expanded: BTreeMap<u32, SubtaskItem> = subtasks
+        .iter()
+        .map(|(&id, subtask)| {
+            (
+                id,
+                SubtaskItem {
+                    items: vec![],
+                    max_score: 0,
+                    policy: subtask.policy,
+                    dependencies: subtask.dependencies.clone(),
+                },
+            )
+        })
+        .collect();
+
+    for (idx, item) in data.iter().enumerate() {
+        if let Some(subtask) = expanded.get_mut(&item.subtask) {
+            subtask.items.push(idx);
+        } else {
+            ctx.emit_warn(format!(
+                "{} {} 中发现了无效的 Subtask ID {}",
+                kind,
+                item.id.to_string().cyan(),
+                item.subtask.to_string().cyan()
+            ));
+        }
+    }
+
+    for subtask in expanded.values_mut() {
+        subtask.max_score = match subtask.policy {
+            ScorePolicy::Max => subtask
+                .items
+                .iter()
+                .map(|&i| data[i].score)
+                .max()
+                .unwrap_or(0),
+            ScorePolicy::Min | ScorePolicy::Product => subtask
+                .items
+                .iter()
+                .map(|&i| data[i].score)
+                .min()
+                .unwrap_or(0),
+            ScorePolicy::Sum => subtask.items.iter().map(|&i| data[i].score).sum(),
+        };
+    }
+    expanded
+}
+
+/// 检查 subtask 依赖：依赖的 subtask 必须存在，且依赖关系不能成环
+fn check_dependencies(subtasks: &BTreeMap<u32, SubtaskConfig>) -> Result<()> {
+    for (id, subtask) in subtasks {
+        for dep in &subtask.dependencies {
+            if !subtasks.contains_key(dep) {
+                bail!("Subtask {} 依赖的 Subtask {} 不存在", id, dep);
+            }
+        }
+    }
+
+    // 0：未访问，1：访问中，2：已完成
+    fn visit(
+        id: u32,
+        subtasks: &BTreeMap<u32, SubtaskConfig>,
+        state: &mut BTreeMap<u32, u8>,
+        path: &mut Vec<u32>,
+    ) -> Result<()> {
+        match state.get(&id).copied().unwrap_or(0) {
+            1 => {
+                let start = path.iter().position(|&p| p == id).unwrap_or(0);
+                let cycle: Vec<String> = path[start..]
+                    .iter()
+                    .chain([&id])
+                    .map(|p| p.to_string())
+                    .collect();
+                bail!("Subtask 依赖关系成环：{}", cycle.join(" -> "));
+            }
+            2 => return Ok(()),
+            _ => {}
+        }
+        state.insert(id, 1);
+        path.push(id);
+        for &dep in &subtasks[&id].dependencies {
+            visit(dep, subtasks, state, path)?;
+        }
+        path.pop();
+        state.insert(id, 2);
+        Ok(())
+    }
+
+    let mut state = BTreeMap::new();
+    for &id in subtasks.keys() {
+        visit(id, subtasks, &mut state, &mut Vec::new())?;
+    }
+    Ok(())
+}
+
 impl ProblemConfig {
     pub fn load(ctx: &mut LoadContext, config_path: &Path) -> Result<Self> {
         // 读取并验证问题配置文件
@@ -105,7 +333,7 @@ impl ProblemConfig {
         // 检查版本
         if version < CONFIG_MIN_VERSION {
             bail!(
-                "配置文件版本过低，可能是 Tuack 的配置文件。请迁移到 Tuack-NG 配置文件格式再使用。"
+                "配置文件版本过低，可能是 Tuack 的配置文件。请使用 `tuack-ng import tuack` 导入后再使用。"
             );
         }
 
@@ -164,112 +392,41 @@ impl ProblemConfig {
             });
         }
 
-        // 继承全局参数（尚未展开）
-        let mut inherited_data: Vec<DataItem> = vec![];
-        for data in &config.data {
-            let mut merged = config.args.clone();
-            let mut item = data.clone();
-            match &mut item {
-                DataItem::Single(item) => {
-                    merged.extend(item.orig_args.clone());
-                    item.orig_args = merged;
-                }
-                DataItem::Bundle(item) => {
-                    merged.extend(item.orig_args.clone());
-                    item.orig_args = merged;
-                }
-            }
-            inherited_data.push(item);
-        }
-
-        // 展开数据点（基于继承后的数据）
-        let mut expand_data: Vec<ExpandedDataItem> = vec![];
-        for data in &inherited_data {
-            match data {
-                DataItem::Single(item) => {
-                    expand_data.push(ExpandedDataItem {
-                        id: item.id,
-                        score: item.score,
-                        subtask: item.subtask,
-                        input: item
-                            .input
-                            .clone()
-                            .unwrap_or_else(|| format!("{}.in", item.id)),
-                        output: item
-                            .output
-                            .clone()
-                            .unwrap_or_else(|| format!("{}.ans", item.id)),
-                        orig_args: item.orig_args.clone(),
-                        args: item.orig_args.clone(),
-                        dmk: item.dmk.unwrap_or(config.dmk),
-                    });
-                }
-                DataItem::Bundle(item) => {
-                    for id in &item.id {
-                        expand_data.push(ExpandedDataItem {
-                            id: *id as u32,
-                            score: item.score,
-                            subtask: item.subtask,
-                            input: format!("{}.in", id),
-                            output: format!("{}.ans", id),
-                            orig_args: item.orig_args.clone(),
-                            args: item.orig_args.clone(),
-                            dmk: item.dmk.unwrap_or(config.dmk),
-                        });
+        // 继承全局参数（尚未展开），再展开数据点与 subtask
+        let inherited_data = inherit_args(&config.args, &config.data);
+        let expand_data = expand_data_items(&inherited_data, &config);
+        let expanded_subtasks = expand_subtasks(ctx, &config.subtasks, &expand_data, "数据点");
+
+        check_dependencies(&config.subtasks)?;
+
+        // pretest 与正式数据同构，只保留实际包含 pretest 的 subtask
+        let inherited_pretest = inherit_args(&config.args, &config.pretest);
+        let expand_pretest = expand_data_items(&inherited_pretest, &config);
+        let mut expand_pretest_subtasks =
+            expand_subtasks(ctx, &config.subtasks, &expand_pretest, "pretest");
+        expand_pretest_subtasks.retain(|_, subtask| !subtask.items.is_empty());
+
+        if config.problem_type == ProblemType::Interactive {
+            match &config.interactive {
+                None => bail!("交互题目需要配置交互 (interactive)"),
+                Some(interactive) => match interactive.mode {
+                    InteractiveMode::Grader if interactive.grader.is_empty() => {
+                        bail!("grader 交互方式需要配置交互库 (grader)")
                     }
-                }
-            }
-        }
-
-        // 展开 subtask
-        let mut expand_subtasks: BTreeMap<u32, SubtaskItem> = config
-            .subtasks
-            .iter()
-            .map(|(&id, &policy)| {
-                (
-                    id,
-                    SubtaskItem {
-                        items: vec![],
-                        max_score: 0,
-                        policy,
-                    },
-                )
-            })
-            .collect();
-
-        for (idx, data) in expand_data.iter().enumerate() {
-            if let Some(subtask) = expand_subtasks.get_mut(&data.subtask) {
-                subtask.items.push(idx);
-            } else {
-                ctx.emit_warn(format!(
-                    "数据点 {} 中发现了无效的 Subtask ID {}",
-                    data.id.to_string().cyan(),
-                    data.subtask.to_string().cyan()
-                ));
+                    InteractiveMode::Interactor if interactive.interactor.is_none() => {
+                        bail!("interactor 交互方式需要配置交互器 (interactor)")
+                    }
+                    _ => {}
+                },
             }
         }
-
-        // 计算每个 subtask 的最大分值
-        for subtask in expand_subtasks.values_mut() {
-            subtask.max_score = match subtask.policy {
-                ScorePolicy::Max => subtask
-                    .items
-                    .iter()
-                    .map(|&i| expand_data[i].score)
-                    .max()
-                    .unwrap_or(0),
-                ScorePolicy::Min => subtask
-                    .items
-                    .iter()
-                    .map(|&i| expand_data[i].score)
-                    .min()
-                    .unwrap_or(0),
-                ScorePolicy::Sum => subtask.items.iter().map(|&i| expand_data[i].score).sum(),
-            };
-        }
-
-        if config.problem_type == ProblemType::Interactive && config.interactive.is_none() {
-            bail!("交互题目需要配置交互 (interactive)");
+        if config.problem_type == ProblemType::Communication
+            && config
+                .communication
+                .as_ref()
+                .is_none_or(|c| c.stages.is_empty())
+        {
+            bail!("通信题目需要配置 manager 与至少一个阶段 (communication)");
         }
 
         config.path = config_path
@@ -279,11 +436,48 @@ impl ProblemConfig {
         config.runtime.samples = expand_samples;
         config.runtime.inherited_data = inherited_data;
         config.runtime.data = expand_data;
-        config.runtime.subtasks = expand_subtasks;
+        config.runtime.subtasks = expanded_subtasks;
+        config.runtime.inherited_pretest = inherited_pretest;
+        config.runtime.pretest = expand_pretest;
+        config.runtime.pretest_subtasks = expand_pretest_subtasks;
 
         Ok(config)
     }
 
+    /// 指定语言的标题
+    pub fn localized_title(&self, lang: &str) -> &str {
+        super::localized(&self.titles, &self.title, lang)
+    }
+
+    /// 指定语言的题面文件：缺省语言为 `statement.md`，其他语言为 `statement.<lang>.md`
+    pub fn statement_path(&self, lang: &str) -> PathBuf {
+        if lang == super::DEFAULT_LANG {
+            self.path.join("statement.md")
+        } else {
+            self.path.join(format!("statement.{}.md", lang))
+        }
+    }
+
+    /// 题目目录下已有题面的语言，缺省语言在前
+    pub fn statement_langs(&self) -> Vec<String> {
+        let mut langs = Vec::new();
+        if self.statement_path(super::DEFAULT_LANG).exists() {
+            langs.push(super::DEFAULT_LANG.to_string());
+        }
+        let mut others: Vec<String> = fs::read_dir(&self.path)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name().into_string().ok()?;
+                let lang = name.strip_prefix("statement.")?.strip_suffix(".md")?;
+                (!lang.is_empty() && lang != super::DEFAULT_LANG).then(|| lang.to_string())
+            })
+            .collect();
+        others.sort();
+        langs.extend(others);
+        langs
+    }
+
     pub fn save(&self) -> Result<String> {
         Ok(serde_json::to_string_pretty(&AsSerde::<
             ProblemConfig,
@@ -305,6 +499,9 @@ pub struct GeneratorConfig {
     /// 生成输入后是否进行校验
     #[serde(default)]
     pub validate: bool,
+    /// 生成脚本路径（相对于题目目录），`dmk <目标> script` 按脚本展开数据点
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
 }
 
 /// 生成器配置对（data / sample）
@@ -327,6 +524,20 @@ pub struct CheckerConfig {
     /// 依赖文件列表（相对于题目目录）
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub deps: Vec<String>,
+    /// Checker 时间限制（秒），缺省为 10 秒
+    #[serde(
+        rename = "time limit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub time_limit: Option<f64>,
+    /// Checker 空间限制，缺省为 1 GiB
+    #[serde(
+        rename = "memory limit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub memory_limit: Option<ByteSize>,
 }
 
 /// Checker 配置对（data / sample）
@@ -440,6 +651,23 @@ pub struct SingleDataItem {
     /// 数据生成行为
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dmk: Option<DmkConfig>,
+    /// 生成命令（由生成脚本写入，如 `gen_tree --n=100000`），设置时代替 `generator` 与 `args`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// 时间限制（秒），缺省取所属 subtask 或题目的时间限制
+    #[serde(
+        rename = "time limit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub time_limit: Option<f64>,
+    /// 空间限制，缺省取所属 subtask 或题目的空间限制
+    #[serde(
+        rename = "memory limit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub memory_limit: Option<ByteSize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -458,6 +686,23 @@ pub struct BundleDataItem {
     /// 数据生成行为
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dmk: Option<DmkConfig>,
+    /// 生成命令（由生成脚本写入，如 `gen_tree --n=100000`），设置时代替 `generator` 与 `args`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// 时间限制（秒），缺省取所属 subtask 或题目的时间限制
+    #[serde(
+        rename = "time limit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub time_limit: Option<f64>,
+    /// 空间限制，缺省取所属 subtask 或题目的空间限制
+    #[serde(
+        rename = "memory limit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub memory_limit: Option<ByteSize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -478,6 +723,102 @@ pub struct ExpandedDataItem {
     pub args: IndexMap<String, Arg>,
     /// 数据生成行为
     pub dmk: DmkConfig,
+    /// 生成命令，首个词为生成器名
+    pub command: Option<String>,
+    /// 时间限制（秒），已按测试点、subtask、题目的顺序取定
+    pub time_limit: f64,
+    /// 空间限制，取定方式同时间限制
+    pub memory_limit: ByteSize,
+}
+
+/// Subtask 配置：只需评分策略时可直接写作策略（如 `"sum"`），需覆盖限制时写作对象
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "SubtaskConfigRepr", into = "SubtaskConfigRepr")]
+pub struct SubtaskConfig {
+    /// 评分策略
+    pub policy: ScorePolicy,
+    /// 该 subtask 测试点的时间限制（秒），缺省为题目的时间限制
+    pub time_limit: Option<f64>,
+    /// 该 subtask 测试点的空间限制，缺省为题目的空间限制
+    pub memory_limit: Option<ByteSize>,
+    /// 依赖的 subtask，其中任一未满分时该 subtask 记 0 分
+    pub dependencies: Vec<u32>,
+}
+
+impl From<ScorePolicy> for SubtaskConfig {
+    fn from(policy: ScorePolicy) -> Self {
+        SubtaskConfig {
+            policy,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SubtaskConfigRepr {
+    Policy(ScorePolicy),
+    Full {
+        #[serde(default)]
+        policy: ScorePolicy,
+        #[serde(
+            rename = "time limit",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        time_limit: Option<f64>,
+        #[serde(
+            rename = "memory limit",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        memory_limit: Option<ByteSize>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        dependencies: Vec<u32>,
+    },
+}
+
+impl From<SubtaskConfigRepr> for SubtaskConfig {
+    fn from(repr: SubtaskConfigRepr) -> Self {
+        match repr {
+            SubtaskConfigRepr::Policy(policy) => policy.into(),
+            SubtaskConfigRepr::Full {
+                policy,
+                time_limit,
+                memory_limit,
+                dependencies,
+            } => SubtaskConfig {
+                policy,
+                time_limit,
+                memory_limit,
+                dependencies,
+            },
+        }
+    }
+}
+
+impl From<SubtaskConfig> for SubtaskConfigRepr {
+    fn from(config: SubtaskConfig) -> Self {
+        match config {
+            SubtaskConfig {
+                policy,
+                time_limit: None,
+                memory_limit: None,
+                dependencies,
+            } if dependencies.is_empty() => SubtaskConfigRepr::Policy(policy),
+            SubtaskConfig {
+                policy,
+                time_limit,
+                memory_limit,
+                dependencies,
+            } => SubtaskConfigRepr::Full {
+                policy,
+                time_limit,
+                memory_limit,
+                dependencies,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -488,6 +829,9 @@ pub struct SubtaskItem {
     pub max_score: u32,
     /// 评分策略
     pub policy: ScorePolicy,
+    /// 依赖的 subtask
+    #[serde(default)]
+    pub dependencies: Vec<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -507,15 +851,18 @@ pub enum ExpectedScore {
     Multiple(Vec<String>),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum ScorePolicy {
     /// 求和（默认）
+    #[default]
     Sum,
     /// 求最大值
     Max,
     /// 求最小值
     Min,
+    /// 求积：各测试点得分比例相乘后乘以 subtask 分值
+    Product,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -527,18 +874,77 @@ pub enum ProblemType {
     Output,
     /// 交互型
     Interactive,
+    /// 通信型：选手程序按阶段运行多次，由 manager 在阶段间传递数据
+    Communication,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InteractiveConfig {
-    /// 交互库路径
+    /// 交互方式，缺省为 grader
+    #[serde(default)]
+    pub mode: InteractiveMode,
+    /// 交互库路径（grader 方式）
+    #[serde(default)]
     pub grader: String,
-    /// 交互库头文件路径
+    /// 交互库头文件路径（grader 方式）
+    #[serde(default)]
     pub header: String,
     /// 样例交互库路径
     pub sample_grader: Option<String>,
     /// Dmk 交互库路径
     pub dmk_grader: Option<String>,
+    /// 交互器源文件路径（interactor 方式），使用 testlib 编写
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interactor: Option<String>,
+    /// 交互器依赖文件列表（相对于题目目录）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interactor_deps: Vec<String>,
+}
+
+/// 通信题配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CommunicationConfig {
+    /// manager 源文件路径（相对于题目目录），使用 testlib 编写
+    pub manager: String,
+    /// manager 依赖文件列表（相对于题目目录）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deps: Vec<String>,
+    /// 各阶段，按运行顺序排列
+    pub stages: Vec<CommunicationStage>,
+}
+
+/// 通信题的一个阶段，选手程序在每个阶段各运行一次
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CommunicationStage {
+    /// 阶段名称（如 `encoder`），传给 manager
+    pub name: String,
+    /// 时间限制（秒），缺省为题目的时间限制
+    #[serde(
+        rename = "time limit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub time_limit: Option<f64>,
+    /// 空间限制，缺省为题目的空间限制
+    #[serde(
+        rename = "memory limit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub memory_limit: Option<ByteSize>,
+}
+
+/// 交互方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum InteractiveMode {
+    /// 交互库与选手程序一同编译，仅支持 C++
+    #[default]
+    Grader,
+    /// 交互器作为独立进程运行，通过管道与选手程序通信，支持所有语言
+    Interactor,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, Eq, PartialEq)]
@@ -553,3 +959,46 @@ pub enum DmkConfig {
     /// 启用
     On,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subtasks(deps: &[(u32, &[u32])]) -> BTreeMap<u32, SubtaskConfig> {
+        deps.iter()
+            .map(|(id, deps)| {
+                (
+                    *id,
+                    SubtaskConfig {
+                        dependencies: deps.to_vec(),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn acyclic_dependencies() {
+        check_dependencies(&subtasks(&[(1, &[]), (2, &[1]), (3, &[1, 2])])).unwrap();
+    }
+
+    #[test]
+    fn missing_dependency() {
+        let err = check_dependencies(&subtasks(&[(1, &[]), (2, &[3])])).unwrap_err();
+        assert!(err.to_string().contains("不存在"));
+    }
+
+    #[test]
+    fn dependency_cycle() {
+        let err = check_dependencies(&subtasks(&[(1, &[3]), (2, &[1]), (3, &[2]), (4, &[])]))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Subtask 依赖关系成环：1 -> 3 -> 2 -> 1");
+    }
+
+    #[test]
+    fn self_dependency() {
+        let err = check_dependencies(&subtasks(&[(1, &[1])])).unwrap_err();
+        assert_eq!(err.to_string(), "Subtask 依赖关系成环：1 -> 1");
+    }
+}
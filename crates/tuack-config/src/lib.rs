@@ -3,7 +3,7 @@ pub mod current_location;
 pub mod prelude;
 
 pub use config::{
-    Config, CONFIG_FILE_NAME, CONFIG_MIN_VERSION, CONFIG_VERSION, FileView, FullView, load_config,
+    Config, CONFIG_FILE_NAME, CONFIG_MIN_VERSION, CONFIG_VERSION, DEFAULT_LANG, FileView, FullView, load_config,
     save_config,
 };
 pub use config::{ContestConfig, ContestDayConfig};
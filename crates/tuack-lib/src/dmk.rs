@@ -16,19 +16,21 @@ pub struct DmkParams {
 pub struct DmkSession<'a> {
     /// 已 prepare 的标程运行器。
     runner: &'a mut dyn Runner,
-    /// 已 prepare 的数据生成器。
-    generator: &'a mut dyn Generator,
+    /// 已 prepare 的数据生成器，可由多个会话共用。
+    generator: &'a dyn Generator,
     /// 已 prepare 的输入校验器（可为空）。
     validator: Option<&'a dyn Validator>,
     /// 运行参数。
     params: DmkParams,
+    /// 生成脚本给出的生成器参数，设置时代替数据点的 `args`。
+    argv: Option<Vec<String>>,
 }
 
 impl<'a> DmkSession<'a> {
     /// 创建会话。需保证 `runner` / `generator` / `validator` 已完成 prepare。
     pub fn new(
         runner: &'a mut dyn Runner,
-        generator: &'a mut dyn Generator,
+        generator: &'a dyn Generator,
         validator: Option<&'a dyn Validator>,
         params: DmkParams,
     ) -> Self {
@@ -37,12 +39,22 @@ impl<'a> DmkSession<'a> {
             generator,
             validator,
             params,
+            argv: None,
         }
     }
 
+    /// 使用生成脚本给出的命令行参数运行生成器
+    pub fn with_argv(mut self, argv: Vec<String>) -> Self {
+        self.argv = Some(argv);
+        self
+    }
+
     /// 生成单点输入
     pub async fn gen_input(&self, item: &dyn DmkData, seed: u64) -> Result<()> {
-        let stream = self.generator.run(item.args().clone(), seed).await?;
+        let stream = match &self.argv {
+            Some(argv) => self.generator.run_argv(argv.clone(), seed).await?,
+            None => self.generator.run(item.args().clone(), seed).await?,
+        };
         item.write_input(stream).await?;
 
         let Some(validator) = self.validator else {
@@ -9,6 +9,7 @@ use bytesize::ByteSize;
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::time::Duration;
+use tuack_ng_parser::ast::Document;
 
 use crate::prelude::*;
 use crate::ren::ProblemType;
@@ -21,6 +22,8 @@ pub enum ScorePolicy {
     Sum,
     Min,
     Max,
+    /// 各测试点得分比例之积
+    Product,
 }
 
 /// day 级导出配置
@@ -43,6 +46,10 @@ pub struct DumpCase {
     pub input: PathBuf,
     /// 输出相对路径（如 `data/1.ans`）
     pub output: PathBuf,
+    /// 该测试点的时间限制（已考虑测试点与 subtask 的单独设置）
+    pub time_limit: Duration,
+    /// 该测试点的空间限制
+    pub memory_limit: ByteSize,
 }
 
 /// Subtask
@@ -52,6 +59,8 @@ pub struct DumpSubtask {
     pub items: Vec<usize>,
     pub max_score: u32,
     pub policy: ScorePolicy,
+    /// 依赖的 subtask 编号
+    pub dependencies: Vec<u32>,
 }
 
 /// 样例（相对路径，如 `sample/a.in`）
@@ -67,6 +76,13 @@ pub struct DumpFile {
     pub path: PathBuf,
 }
 
+/// 交互库（grader 形式交互题），相对题目根的逻辑路径
+#[derive(Debug, Clone)]
+pub struct DumpInteractive {
+    pub grader: PathBuf,
+    pub header: PathBuf,
+}
+
 /// 单题导出数据
 #[derive(Debug, Clone)]
 pub struct DumpProblem {
@@ -79,11 +95,40 @@ pub struct DumpProblem {
     pub memory_limit: ByteSize,
     pub data: Vec<DumpCase>,
     pub subtasks: BTreeMap<u32, DumpSubtask>,
+    /// 预测试数据（相对路径如 `pre/1.in`）
+    pub pretest: Vec<DumpCase>,
+    /// 预测试 subtask，`items` 为数据点在 pretest 中的下标
+    pub pretest_subtasks: BTreeMap<u32, DumpSubtask>,
     pub samples: Vec<DumpSample>,
     /// down/ 下非样例的附加文件
     pub extra_down: Vec<DumpFile>,
     /// checker 源文件逻辑路径
     pub checker: Option<PathBuf>,
+    /// validator 源文件逻辑路径
+    pub validator: Option<PathBuf>,
+    /// 数据生成器源文件逻辑路径
+    pub generator: Option<PathBuf>,
+    /// 交互库，仅 grader 方式的交互题存在
+    pub interactive: Option<DumpInteractive>,
+    /// 交互器源文件逻辑路径，仅交互器方式的交互题存在
+    pub interactor: Option<PathBuf>,
+    /// 题面 AST（已按 Markdown 目标展开模板）；仅导出目标需要题面时由前端填充
+    pub statement: Option<Document>,
+}
+
+impl DumpProblem {
+    /// 是否有测试点的限制与题目的限制不同
+    pub fn has_case_limits(&self) -> bool {
+        self.data
+            .iter()
+            .chain(&self.pretest)
+            .any(|c| c.time_limit != self.time_limit || c.memory_limit != self.memory_limit)
+    }
+
+    /// 是否有 subtask 声明了依赖
+    pub fn has_dependencies(&self) -> bool {
+        self.subtasks.values().any(|s| !s.dependencies.is_empty())
+    }
 }
 
 /// 导出文档：dumper 的唯一输入（day 级）。
@@ -11,6 +11,7 @@ pub mod document;
 use crate::utils::output::OutputFile;
 pub use document::{
     DateInfo, Problem, ProblemMeta, ProblemType, RenConfig, RenderDocument, SupportLanguage,
+    Translation,
 };
 
 use crate::prelude::*;
@@ -10,6 +10,7 @@ pub enum ProblemType {
     Program,
     Output,
     Interactive,
+    Communication,
 }
 
 /// 题目渲染元信息
@@ -21,7 +22,13 @@ pub struct ProblemMeta {
     pub problem_type: ProblemType,
     pub time_limit: Duration,
     pub memory_limit: ByteSize,
+    /// 各测试点中最大的时间限制，没有单独设置时与 `time_limit` 相同
+    pub max_time_limit: Duration,
+    /// 各测试点中最大的空间限制
+    pub max_memory_limit: ByteSize,
     pub testcase: usize,
+    /// 预测试点数目
+    pub pretestcase: usize,
     /// 各测试点分数是否相等
     pub point_equal: bool,
     /// 可提交的文件名（如 ["a.cpp", "a.py"]）
@@ -33,6 +40,10 @@ pub struct ProblemMeta {
 pub struct SupportLanguage {
     pub name: String,
     pub compile_options: String,
+    /// 时间限制倍率
+    pub time_multiplier: f64,
+    /// 空间限制倍率
+    pub memory_multiplier: f64,
 }
 
 /// 比赛日起止时间
@@ -45,6 +56,8 @@ pub struct DateInfo {
 /// 渲染配置，包含渲染所需的全部信息。
 #[derive(Debug, Clone)]
 pub struct RenConfig {
+    /// 主语言（如 `zh-cn`）
+    pub lang: String,
     pub title: String,
     pub short_title: String,
     pub day_key: String,
@@ -63,6 +76,16 @@ pub struct Problem {
     pub idx: u64,
     pub meta: ProblemMeta,
     pub ast: Document,
+    /// 其他语言的译文，按请求顺序排列（用于双语题面）
+    pub translations: Vec<Translation>,
+}
+
+/// 题目的一种译文
+#[derive(Debug)]
+pub struct Translation {
+    pub lang: String,
+    pub title: String,
+    pub ast: Document,
 }
 
 /// 渲染文档，渲染器的输入。
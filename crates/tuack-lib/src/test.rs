@@ -3,12 +3,13 @@ use std::time::Duration;
 use bytesize::ByteSize;
 
 use crate::prelude::*;
-use crate::data::Data;
-use crate::utils::compiler::{IoMode, ResourceLimits, RunStatus, Runner};
-use crate::utils::testlib::{Checker, JudgeResult};
+use crate::data::{AsyncReader, Data};
+use crate::utils::compiler::{IoMode, ResourceLimits, RunStatus, Runner, TimeMode};
+use crate::utils::testlib::{Checker, JudgeResult, Manager};
+use tokio::io::AsyncReadExt;
 
 /// 测试点评测状态。
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum TestCaseStatus {
     AC,
@@ -24,14 +25,51 @@ pub enum TestCaseStatus {
 }
 
 /// 单个测试点评测结果。
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TestCaseResult {
     pub status: TestCaseStatus,
     /// 归一化得分，`AC=1.0`、`PC=p/100`、其余 `0.0`
     pub score: f64,
-    pub time: Option<Duration>,
+    /// CPU 时间（user + sys）
+    pub cpu_time: Option<Duration>,
+    /// 墙钟时间
+    pub wall_time: Option<Duration>,
     pub memory: Option<ByteSize>,
     pub message: Option<String>,
+    /// 通信题各阶段的运行结果，其他题目为空
+    pub stages: Vec<StageResult>,
+}
+
+/// 通信题单个阶段的运行结果。
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StageResult {
+    pub name: String,
+    /// 该阶段程序的运行状态，正常结束为 `AC`
+    pub status: TestCaseStatus,
+    pub cpu_time: Option<Duration>,
+    pub wall_time: Option<Duration>,
+    pub memory: Option<ByteSize>,
+    pub time_limit: Duration,
+    pub memory_limit: ByteSize,
+}
+
+/// 通信题单个阶段的资源限制。
+#[derive(Debug, Clone)]
+pub struct StageParams {
+    pub name: String,
+    pub time_limit: Duration,
+    pub memory_limit: ByteSize,
+    pub wall_time_limit: Duration,
+}
+
+impl TestCaseResult {
+    /// 按计时方式取用时；CPU 时间不可用时退化为墙钟时间。
+    pub fn time(&self, time_mode: TimeMode) -> Option<Duration> {
+        match time_mode {
+            TimeMode::Cpu => self.cpu_time.or(self.wall_time),
+            TimeMode::Wall => self.wall_time,
+        }
+    }
 }
 
 /// 运行参数。
@@ -40,7 +78,31 @@ pub struct TaskParams {
     pub problem_name: String,
     pub time_limit: Duration,
     pub memory_limit: ByteSize,
+    /// 时间限制所依据的计时方式
+    pub time_mode: TimeMode,
+    /// 墙钟时间上限
+    pub wall_time_limit: Duration,
     pub file_io: bool,
+    /// 交互器模式：答案交给运行器，结果取自交互器而非 Checker
+    pub interactor: bool,
+    /// 通信题各阶段，按运行顺序排列；非空时按阶段运行并由 manager 给出结果
+    pub stages: Vec<StageParams>,
+}
+
+impl TaskParams {
+    /// 按倍率放宽限制，墙钟时间与通信题各阶段的限制一同放宽
+    pub fn scaled(mut self, time_multiplier: f64, memory_multiplier: f64) -> Self {
+        let memory = |m: ByteSize| ByteSize((m.as_u64() as f64 * memory_multiplier) as u64);
+        self.time_limit = self.time_limit.mul_f64(time_multiplier);
+        self.wall_time_limit = self.wall_time_limit.mul_f64(time_multiplier);
+        self.memory_limit = memory(self.memory_limit);
+        for stage in &mut self.stages {
+            stage.time_limit = stage.time_limit.mul_f64(time_multiplier);
+            stage.wall_time_limit = stage.wall_time_limit.mul_f64(time_multiplier);
+            stage.memory_limit = memory(stage.memory_limit);
+        }
+        self
+    }
 }
 
 /// 测试会话。
@@ -48,17 +110,80 @@ pub struct TaskParams {
 pub struct TestSession<'a> {
     runner: &'a mut dyn Runner,
     checker: &'a dyn Checker,
+    manager: Option<&'a dyn Manager>,
     params: TaskParams,
 }
 
+/// 将 Checker 或交互器的结果转换为评测状态、归一化得分与信息。
+fn verdict_status(verdict: Result<(JudgeResult, String)>) -> (TestCaseStatus, f64, Option<String>) {
+    match verdict {
+        Ok((JudgeResult::Accepted, msg)) => (TestCaseStatus::AC, 1.0, Some(msg)),
+        Ok((JudgeResult::WrongAnswer, msg)) => (TestCaseStatus::WA, 0.0, Some(msg)),
+        Ok((JudgeResult::PresentationError, msg)) => (TestCaseStatus::WA, 0.0, Some(msg)),
+        Ok((JudgeResult::Score(p), msg)) => (
+            TestCaseStatus::PC(p),
+            (p / 100.0).clamp(0.0, 1.0),
+            Some(msg),
+        ),
+        Ok((JudgeResult::Fail, msg)) => (TestCaseStatus::UKE, 0.0, Some(msg)),
+        Err(e) => (TestCaseStatus::UKE, 0.0, Some(format!("{e:#}"))),
+    }
+}
+
 /// 构造一个 UKE 结果（例如输入/答案文件读取失败）。
 fn uke_result(message: String) -> TestCaseResult {
     TestCaseResult {
         status: TestCaseStatus::UKE,
         score: 0.0,
-        time: None,
+        cpu_time: None,
+        wall_time: None,
         memory: None,
         message: Some(message),
+        stages: Vec::new(),
+    }
+}
+
+/// 用 Checker 检查输出；没有输出时记为 FE，读取数据失败时返回 UKE 结果
+async fn check(
+    checker: &dyn Checker,
+    data: &dyn Data,
+    output: Option<Box<dyn AsyncReader>>,
+) -> std::result::Result<(TestCaseStatus, f64, Option<String>), TestCaseResult> {
+    let Some(mut output) = output else {
+        return Ok((TestCaseStatus::FE, 0.0, Some("未找到输出文件".to_string())));
+    };
+    let mut input = match data.input().await {
+        Ok(i) => i,
+        Err(e) => return Err(uke_result(format!("读取输入失败：{e}"))),
+    };
+    let mut answer = match data.answer().await {
+        Ok(a) => a,
+        Err(e) => return Err(uke_result(format!("读取答案失败：{e}"))),
+    };
+    Ok(verdict_status(
+        checker.validate(&mut input, &mut output, &mut answer).await,
+    ))
+}
+
+/// 评测提交答案题的测试点：选手输出直接交给 Checker，不运行程序。
+///
+/// `output` 为 `None` 表示未提交该测试点的输出。
+pub async fn judge_output(
+    checker: &dyn Checker,
+    data: &dyn Data,
+    output: Option<Box<dyn AsyncReader>>,
+) -> TestCaseResult {
+    match check(checker, data, output).await {
+        Ok((status, score, message)) => TestCaseResult {
+            status,
+            score,
+            cpu_time: None,
+            wall_time: None,
+            memory: None,
+            message,
+            stages: Vec::new(),
+        },
+        Err(result) => result,
     }
 }
 
@@ -67,37 +192,114 @@ impl<'a> TestSession<'a> {
         Self {
             runner,
             checker,
+            manager: None,
             params,
         }
     }
 
-    /// 评测单个测试点：设置 limits/io_mode -> 注入输入 -> 执行 -> 校验 -> 返回结果。
-    pub async fn judge(&mut self, data: &dyn Data) -> Result<TestCaseResult> {
-        self.runner.set_limits(ResourceLimits::new(
-            self.params.time_limit,
-            self.params.memory_limit.as_u64(),
-        ));
-        self.runner.set_io_mode(if self.params.file_io {
+    /// 设置通信题的 manager
+    pub fn with_manager(mut self, manager: &'a dyn Manager) -> Self {
+        self.manager = Some(manager);
+        self
+    }
+
+    fn io_mode(&self) -> IoMode {
+        if self.params.file_io {
             IoMode::File {
                 input_name: format!("{}.in", self.params.problem_name),
                 output_name: format!("{}.out", self.params.problem_name),
             }
         } else {
             IoMode::Stdio
-        });
+        }
+    }
 
-        let input = match data.input().await {
-            Ok(i) => i,
-            Err(e) => return Ok(uke_result(format!("读取输入失败：{e}"))),
+    /// 评测通信题测试点：依次运行各阶段，阶段输入由 manager 生成，全部正常结束后由 manager 判定。
+    ///
+    /// 用时与内存为各阶段之和与最大值。
+    async fn judge_stages(&mut self, data: &dyn Data) -> Result<TestCaseResult> {
+        let manager = self.manager.context("通信题需要 manager")?;
+        let mut outputs = Vec::new();
+        let mut stages = Vec::new();
+        let mut failure = None;
+
+        for stage in self.params.stages.clone() {
+            let mut input = match data.input().await {
+                Ok(i) => i,
+                Err(e) => return Ok(uke_result(format!("读取输入失败：{e}"))),
+            };
+            let stage_input = match manager.stage_input(&stage.name, &mut input, &outputs).await {
+                Ok(i) => i,
+                Err(e) => {
+                    return Ok(uke_result(format!(
+                        "manager 生成 {} 阶段输入失败：{e:#}",
+                        stage.name
+                    )));
+                }
+            };
+
+            self.runner.set_limits(
+                ResourceLimits::new(stage.time_limit, stage.memory_limit.as_u64())
+                    .with_time_mode(self.params.time_mode, stage.wall_time_limit),
+            );
+            self.runner.set_io_mode(self.io_mode());
+            self.runner.set_input(stage_input);
+            let run = self.runner.execute().await?;
+
+            let status = match (run.status, run.output) {
+                (RunStatus::Success, Some(mut output)) => {
+                    let mut bytes = Vec::new();
+                    output.read_to_end(&mut bytes).await?;
+                    outputs.push(bytes);
+                    TestCaseStatus::AC
+                }
+                (RunStatus::Success, None) => TestCaseStatus::FE,
+                (RunStatus::NonZeroExit(_), _) => TestCaseStatus::RE,
+                (RunStatus::TimeLimitExceeded, _) => TestCaseStatus::TLE,
+                (RunStatus::MemoryLimitExceeded, _) => TestCaseStatus::MLE,
+                (RunStatus::InternalError(e), _) => {
+                    failure = Some(format!("{} 阶段：{e:#}", stage.name));
+                    TestCaseStatus::UKE
+                }
+            };
+            let ok = matches!(status, TestCaseStatus::AC);
+            if !ok && failure.is_none() {
+                failure = Some(format!("{} 阶段：{:?}", stage.name, status));
+            }
+            stages.push(StageResult {
+                name: stage.name,
+                status,
+                cpu_time: run.cpu_time,
+                wall_time: run.wall_time,
+                memory: run.memory.map(ByteSize),
+                time_limit: stage.time_limit,
+                memory_limit: stage.memory_limit,
+            });
+            if !ok {
+                break;
+            }
+        }
+
+        let sum = |f: fn(&StageResult) -> Option<Duration>| -> Option<Duration> {
+            stages.iter().map(f).sum()
         };
-        self.runner.set_input(input);
-        let run = self.runner.execute().await?;
+        let cpu_time = sum(|s| s.cpu_time);
+        let wall_time = sum(|s| s.wall_time);
+        let memory = stages.iter().filter_map(|s| s.memory).max();
 
-        let (status, score, message) = match (run.status, run.output) {
-            (RunStatus::Success, None) => {
-                (TestCaseStatus::FE, 0.0, Some("未找到输出文件".to_string()))
+        let (status, score, message) = match (stages.last(), failure) {
+            // 失败阶段的状态即为测试点状态
+            (Some(last), Some(message)) => {
+                let status = match last.status {
+                    TestCaseStatus::FE => TestCaseStatus::FE,
+                    TestCaseStatus::RE => TestCaseStatus::RE,
+                    TestCaseStatus::TLE => TestCaseStatus::TLE,
+                    TestCaseStatus::MLE => TestCaseStatus::MLE,
+                    _ => TestCaseStatus::UKE,
+                };
+                (status, 0.0, Some(message))
             }
-            (RunStatus::Success, Some(mut output)) => {
+            _ => {
                 let mut input = match data.input().await {
                     Ok(i) => i,
                     Err(e) => return Ok(uke_result(format!("读取输入失败：{e}"))),
@@ -106,37 +308,79 @@ impl<'a> TestSession<'a> {
                     Ok(a) => a,
                     Err(e) => return Ok(uke_result(format!("读取答案失败：{e}"))),
                 };
-                match self
-                    .checker
-                    .validate(&mut input, &mut output, &mut answer)
-                    .await
-                {
-                    Ok((JudgeResult::Accepted, msg)) => (TestCaseStatus::AC, 1.0, Some(msg)),
-                    Ok((JudgeResult::WrongAnswer, msg)) => (TestCaseStatus::WA, 0.0, Some(msg)),
-                    Ok((JudgeResult::PresentationError, msg)) => {
-                        (TestCaseStatus::WA, 0.0, Some(msg))
-                    }
-                    Ok((JudgeResult::Score(p), msg)) => (
-                        TestCaseStatus::PC(p),
-                        (p / 100.0).clamp(0.0, 1.0),
-                        Some(msg),
-                    ),
-                    Ok((JudgeResult::Fail, msg)) => (TestCaseStatus::UKE, 0.0, Some(msg)),
-                    Err(e) => (TestCaseStatus::UKE, 0.0, Some(format!("{e:#}"))),
-                }
+                verdict_status(manager.judge(&mut input, &mut answer, &outputs).await)
+            }
+        };
+
+        Ok(TestCaseResult {
+            status,
+            score,
+            cpu_time,
+            wall_time,
+            memory,
+            message,
+            stages,
+        })
+    }
+
+    /// 评测单个测试点：设置 limits/io_mode -> 注入输入 -> 执行 -> 校验 -> 返回结果。
+    pub async fn judge(&mut self, data: &dyn Data) -> Result<TestCaseResult> {
+        if !self.params.stages.is_empty() {
+            return self.judge_stages(data).await;
+        }
+
+        self.runner.set_limits(
+            ResourceLimits::new(self.params.time_limit, self.params.memory_limit.as_u64())
+                .with_time_mode(self.params.time_mode, self.params.wall_time_limit),
+        );
+        self.runner.set_io_mode(self.io_mode());
+
+        let input = match data.input().await {
+            Ok(i) => i,
+            Err(e) => return Ok(uke_result(format!("读取输入失败：{e}"))),
+        };
+        self.runner.set_input(input);
+        if self.params.interactor {
+            match data.answer().await {
+                Ok(a) => self.runner.set_answer(a),
+                Err(e) => return Ok(uke_result(format!("读取答案失败：{e}"))),
+            }
+        }
+        let run = self.runner.execute().await?;
+
+        let (status, score, message) = match (run.status, run.output, run.verdict) {
+            // 交互器模式：程序超时、超内存优先；交互器判为错误时以其为准，否则程序异常退出记为 RE
+            (RunStatus::TimeLimitExceeded, _, Some(_)) => (TestCaseStatus::TLE, 0.0, None),
+            (RunStatus::MemoryLimitExceeded, _, Some(_)) => (TestCaseStatus::MLE, 0.0, None),
+            (RunStatus::InternalError(e), _, Some(_)) => {
+                (TestCaseStatus::UKE, 0.0, Some(format!("{e:#}")))
+            }
+            (
+                RunStatus::NonZeroExit(_),
+                _,
+                Some(Ok((JudgeResult::Accepted | JudgeResult::Score(_), _))),
+            ) => (TestCaseStatus::RE, 0.0, None),
+            (_, _, Some(verdict)) => verdict_status(verdict),
+            (RunStatus::Success, output, None) => match check(self.checker, data, output).await {
+                Ok(verdict) => verdict,
+                Err(result) => return Ok(result),
+            },
+            (RunStatus::NonZeroExit(_), _, None) => (TestCaseStatus::RE, 0.0, None),
+            (RunStatus::TimeLimitExceeded, _, None) => (TestCaseStatus::TLE, 0.0, None),
+            (RunStatus::MemoryLimitExceeded, _, None) => (TestCaseStatus::MLE, 0.0, None),
+            (RunStatus::InternalError(e), _, None) => {
+                (TestCaseStatus::UKE, 0.0, Some(format!("{e:#}")))
             }
-            (RunStatus::NonZeroExit(_), _) => (TestCaseStatus::RE, 0.0, None),
-            (RunStatus::TimeLimitExceeded, _) => (TestCaseStatus::TLE, 0.0, None),
-            (RunStatus::MemoryLimitExceeded, _) => (TestCaseStatus::MLE, 0.0, None),
-            (RunStatus::InternalError(e), _) => (TestCaseStatus::UKE, 0.0, Some(format!("{e:#}"))),
         };
 
         Ok(TestCaseResult {
             status,
             score,
-            time: run.time,
+            cpu_time: run.cpu_time,
+            wall_time: run.wall_time,
             memory: run.memory.map(ByteSize),
             message,
+            stages: Vec::new(),
         })
     }
 }
@@ -3,6 +3,7 @@ use std::time::Duration;
 
 use crate::prelude::*;
 use crate::data::AsyncReader;
+use crate::utils::testlib::JudgeResult;
 
 /// 运行器元信息。
 pub struct RunnerManifest {
@@ -10,6 +11,17 @@ pub struct RunnerManifest {
     pub interactive: bool,
 }
 
+/// 时间限制所依据的计时方式。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeMode {
+    /// 用户态 + 内核态 CPU 时间，不受机器负载影响；无法获取时退化为墙钟时间
+    Cpu,
+    /// 墙钟时间，与早期版本的判定一致
+    #[default]
+    Wall,
+}
+
 /// 进程资源限制。
 #[derive(Debug, Clone)]
 pub struct ResourceLimits {
@@ -17,6 +29,10 @@ pub struct ResourceLimits {
     pub time_limit: Option<Duration>,
     /// 内存限制（字节），`None` 表示不限。
     pub memory_limit: Option<u64>,
+    /// `time_limit` 所依据的计时方式，沙箱后端忽略此项、总按 CPU 时间判定。
+    pub time_mode: TimeMode,
+    /// 墙钟时间上限，防止程序阻塞（如 `sleep`、等待输入），`None` 表示不限。
+    pub wall_time_limit: Option<Duration>,
 }
 
 impl ResourceLimits {
@@ -24,15 +40,44 @@ impl ResourceLimits {
         Self {
             time_limit: None,
             memory_limit: None,
+            time_mode: TimeMode::default(),
+            wall_time_limit: None,
         }
     }
 
+    /// 按默认计时方式（墙钟时间）限制，墙钟时间上限取 [`default_wall_time_limit`](Self::default_wall_time_limit)。
     pub fn new(time_limit: Duration, memory_limit: u64) -> Self {
         Self {
             time_limit: Some(time_limit),
             memory_limit: Some(memory_limit),
+            time_mode: TimeMode::default(),
+            wall_time_limit: Some(Self::default_wall_time_limit(time_limit)),
         }
     }
+
+    /// 设置计时方式与墙钟时间上限。
+    pub fn with_time_mode(mut self, time_mode: TimeMode, wall_time_limit: Duration) -> Self {
+        self.time_mode = time_mode;
+        self.wall_time_limit = Some(wall_time_limit);
+        self
+    }
+
+    /// 默认墙钟时间上限：时间限制的两倍再加一秒。
+    pub fn default_wall_time_limit(time_limit: Duration) -> Duration {
+        time_limit
+            .saturating_mul(2)
+            .saturating_add(Duration::from_secs(1))
+    }
+
+    /// 按计时方式判断是否超时；CPU 时间不可用时使用墙钟时间。
+    pub fn exceeds_time(&self, cpu_time: Option<Duration>, wall_time: Duration) -> bool {
+        let used = match (self.time_mode, cpu_time) {
+            (TimeMode::Cpu, Some(cpu_time)) => cpu_time,
+            _ => wall_time,
+        };
+        self.time_limit.is_some_and(|limit| used > limit)
+            || self.wall_time_limit.is_some_and(|limit| wall_time > limit)
+    }
 }
 
 /// 进程结束状态。
@@ -49,14 +94,27 @@ pub enum RunStatus {
 /// `execute()` 的返回结果。
 pub struct RunResult {
     pub status: RunStatus,
-    /// 运行时间，TLE/MLE 时为 `None`。
-    pub time: Option<Duration>,
+    /// 用户态 + 内核态 CPU 时间，TLE/MLE 或平台不支持时为 `None`。
+    pub cpu_time: Option<Duration>,
+    /// 墙钟时间，TLE/MLE 时为 `None`。
+    pub wall_time: Option<Duration>,
     /// 峰值内存（字节），TLE/MLE 时为 `None`。
     pub memory: Option<u64>,
     /// 程序输出流；`None` 表示输出文件不存在（文件 IO 程序未写输出）。
     pub output: Option<Box<dyn AsyncReader>>,
     /// 程序 stderr。
     pub stderr: Vec<u8>,
+    /// 交互器给出的结果，仅交互器模式下存在；交互器超时等异常为 `Err`。
+    pub verdict: Option<Result<(JudgeResult, String)>>,
+}
+
+/// 交互器（testlib interactor），程序的标准输入输出通过管道与其相连。
+#[derive(Debug, Clone)]
+pub struct Interactor {
+    /// 已编译的交互器可执行文件。
+    pub binary: PathBuf,
+    /// 交互器的资源限制。
+    pub limits: ResourceLimits,
 }
 
 /// IO 模式。
@@ -73,6 +131,17 @@ pub enum IoMode {
     },
 }
 
+/// 程序执行后端。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExecBackend {
+    /// 轮询进程信息监控，跨平台，按 [`TimeMode`] 计时（缺省为墙钟时间），不做隔离
+    #[default]
+    Supervisor,
+    /// Linux 沙箱：cgroup v2 统计 CPU 时间与峰值内存，总按 CPU 时间判定超时；
+    /// 程序在只含工作目录与系统库的根目录中运行，隔离网络，并以 seccomp 白名单限制系统调用
+    Sandbox,
+}
+
 /// 运行器：编译 + 资源限制执行。
 #[allow(unused)]
 #[async_trait]
@@ -84,6 +153,8 @@ pub trait Runner: Send {
     fn prepare(&mut self) -> Result<()>;
     /// 做必要的准备工作（异步）
     async fn prepare_async(&mut self) -> Result<()>;
+    /// 复制出一个拥有独立工作目录的运行器，复用已编译的产物（需在 `prepare` 之后调用）
+    fn fork(&self) -> Result<Box<dyn Runner>>;
 
     /// 设置运行限制
     fn set_limits(&mut self, limits: ResourceLimits);
@@ -91,8 +162,14 @@ pub trait Runner: Send {
     fn set_input(&mut self, input: Box<dyn AsyncReader>);
     /// 设置 IO 模式
     fn set_io_mode(&mut self, io_mode: IoMode);
+    /// 设置执行后端
+    fn set_backend(&mut self, backend: ExecBackend);
     /// 设置交互
     fn set_interactive(&mut self, grader_file: &Path, header_file: &Path) -> Result<()>;
+    /// 设置交互器，此后执行时忽略 IO 模式，程序与交互器对接
+    fn set_interactor(&mut self, interactor: Interactor);
+    /// 设置交互器读取的答案（消耗流），仅交互器模式下使用
+    fn set_answer(&mut self, answer: Box<dyn AsyncReader>);
 
     /// 清理
     fn cleanup(&mut self) -> Result<()>;
@@ -19,6 +19,8 @@ pub trait Generator: Send {
     fn prepare(&mut self) -> Result<()>;
     /// 运行生成器，返回生成的输入流
     async fn run(&self, args: IndexMap<String, Arg>, seed: u64) -> Result<Box<dyn AsyncReader>>;
+    /// 以原样的命令行参数（生成脚本中的写法）运行生成器，返回生成的输入流
+    async fn run_argv(&self, argv: Vec<String>, seed: u64) -> Result<Box<dyn AsyncReader>>;
 }
 
 /// Checker（SPJ）结果类型
@@ -43,6 +45,26 @@ pub trait Checker: Send {
     ) -> Result<(JudgeResult, String)>;
 }
 
+/// 通信题 manager：在各阶段之间传递数据并给出最终结果
+#[async_trait]
+pub trait Manager: Send {
+    fn prepare(&mut self) -> Result<()>;
+    /// 生成 `stage` 阶段选手程序的输入；`outputs` 为此前各阶段选手程序的输出
+    async fn stage_input(
+        &self,
+        stage: &str,
+        input: &mut dyn AsyncReader,
+        outputs: &[Vec<u8>],
+    ) -> Result<Box<dyn AsyncReader>>;
+    /// 根据各阶段选手程序的输出给出结果
+    async fn judge(
+        &self,
+        input: &mut dyn AsyncReader,
+        answer: &mut dyn AsyncReader,
+        outputs: &[Vec<u8>],
+    ) -> Result<(JudgeResult, String)>;
+}
+
 /// Validator（输入校验器）结果
 #[derive(Debug, Clone, PartialEq)]
 pub enum ValidatorResult {
@@ -0,0 +1,315 @@
+//! HTML 渲染器。
+//!
+//! 输出为 HTML 片段（不含 `<html>`/`<body>`），由调用方套页面框架：
+//! - 公式输出 KaTeX auto-render 可识别的 `\(...\)` / `\[...\]`，外包 `math` 类
+//! - 容器块输出 `<div class="kind">`，`figure` 输出 `<figure>` 与 `<figcaption>`
+//! - 脚注在引用点输出上标链接，定义统一收集到文末 `<section class="footnotes">`
+//!
+//! 表格**处理合并**：colspan/rowspan 输出为属性，`removed_by_extended_table` 的单元格跳过。
+
+use crate::ast::block::{BlockKind, CodeBlockKind, HeadingKind, SetextHeading};
+use crate::ast::inline::InlineKind;
+use crate::ast::list::ListKind;
+use crate::ast::{Alignment, Block, Document, Inline, Table};
+
+/// 渲染为 HTML 字符串。
+pub fn render_html(doc: &Document) -> String {
+    let mut ctx = Context::default();
+    for block in &doc.blocks {
+        if let BlockKind::FootnoteDefinition(fd) = &block.value {
+            if !ctx.footnotes.iter().any(|(label, _)| label == &fd.label) {
+                ctx.footnotes.push((fd.label.clone(), fd.blocks.clone()));
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for block in &doc.blocks {
+        render_block(&block.value, &ctx, &mut out);
+    }
+
+    if !ctx.footnotes.is_empty() {
+        out.push_str("<section class=\"footnotes\">\n<ol>\n");
+        for (label, blocks) in &ctx.footnotes {
+            out.push_str(&format!("<li id=\"fn-{}\">\n", escape_html(label)));
+            for b in blocks {
+                render_block(&b.value, &ctx, &mut out);
+            }
+            out.push_str("</li>\n");
+        }
+        out.push_str("</ol>\n</section>\n");
+    }
+    out
+}
+
+/// 渲染上下文：按定义顺序排列的脚注，编号即下标 + 1。
+#[derive(Default)]
+struct Context {
+    footnotes: Vec<(String, Vec<Block>)>,
+}
+
+fn render_block(block: &BlockKind, ctx: &Context, out: &mut String) {
+    match block {
+        BlockKind::Paragraph(inlines) => {
+            out.push_str("<p>");
+            render_inlines(inlines, ctx, out);
+            out.push_str("</p>\n");
+        }
+        BlockKind::Heading(h) => {
+            let level = match h.kind {
+                HeadingKind::Atx(level) => level,
+                HeadingKind::Setext(SetextHeading::Level1) => 1,
+                HeadingKind::Setext(SetextHeading::Level2) => 2,
+            };
+            out.push_str(&format!("<h{level}>"));
+            render_inlines(&h.content, ctx, out);
+            out.push_str(&format!("</h{level}>\n"));
+        }
+        BlockKind::ThematicBreak => out.push_str("<hr />\n"),
+        BlockKind::BlockQuote(blocks) => {
+            out.push_str("<blockquote>\n");
+            render_blocks(blocks, ctx, out);
+            out.push_str("</blockquote>\n");
+        }
+        BlockKind::List(list) => {
+            let tag = match &list.kind {
+                ListKind::Ordered => "ol",
+                ListKind::Bullet(_) => "ul",
+            };
+            out.push_str(&format!("<{tag}>\n"));
+            for item in &list.items {
+                out.push_str("<li>");
+                // 只有一个段落的列表项不包 `<p>`（紧凑列表）。
+                match item.value.blocks.as_slice() {
+                    [single] => match &single.value {
+                        BlockKind::Paragraph(inlines) => render_inlines(inlines, ctx, out),
+                        other => render_block(other, ctx, out),
+                    },
+                    blocks => render_blocks(blocks, ctx, out),
+                }
+                out.push_str("</li>\n");
+            }
+            out.push_str(&format!("</{tag}>\n"));
+        }
+        BlockKind::CodeBlock(cb) => {
+            let lang = match &cb.kind {
+                CodeBlockKind::Fenced { info } => info.clone().unwrap_or_default(),
+                CodeBlockKind::Indented => String::new(),
+            };
+            out.push_str("<pre><code");
+            if !lang.is_empty() {
+                out.push_str(&format!(" class=\"language-{}\"", escape_html(&lang)));
+            }
+            out.push('>');
+            out.push_str(&escape_html(&cb.literal));
+            out.push_str("</code></pre>\n");
+        }
+        BlockKind::HtmlBlock(html) => {
+            out.push_str(html);
+            if !html.ends_with('\n') {
+                out.push('\n');
+            }
+        }
+        BlockKind::Definition(_) => {}
+        BlockKind::Table(table) => render_table(table, ctx, out),
+        BlockKind::FootnoteDefinition(_) => {}
+        BlockKind::Container(c) => {
+            if c.kind == "figure" {
+                out.push_str("<figure>\n");
+                render_blocks(&c.blocks, ctx, out);
+                if let Some((_, caption)) = c.params.iter().find(|(k, _)| k == "caption") {
+                    out.push_str(&format!(
+                        "<figcaption>{}</figcaption>\n",
+                        escape_html(caption)
+                    ));
+                }
+                out.push_str("</figure>\n");
+            } else {
+                out.push_str(&format!("<div class=\"{}\"", escape_html(&c.kind)));
+                for (key, value) in &c.params {
+                    out.push_str(&format!(
+                        " data-{}=\"{}\"",
+                        escape_html(key),
+                        escape_html(value)
+                    ));
+                }
+                out.push_str(">\n");
+                render_blocks(&c.blocks, ctx, out);
+                out.push_str("</div>\n");
+            }
+        }
+        BlockKind::LatexBlock(latex) => {
+            out.push_str(&format!(
+                "<div class=\"math math-display\">\\[{}\\]</div>\n",
+                escape_html(latex)
+            ));
+        }
+        BlockKind::Empty => {}
+    }
+}
+
+fn render_blocks(blocks: &[Block], ctx: &Context, out: &mut String) {
+    for b in blocks {
+        render_block(&b.value, ctx, out);
+    }
+}
+
+fn render_inlines(inlines: &[Inline], ctx: &Context, out: &mut String) {
+    for inline in inlines {
+        render_inline(&inline.value, ctx, out);
+    }
+}
+
+fn render_inline(inline: &InlineKind, ctx: &Context, out: &mut String) {
+    match inline {
+        InlineKind::Text(t) => out.push_str(&escape_html(t)),
+        InlineKind::SoftBreak => out.push('\n'),
+        InlineKind::LineBreak => out.push_str("<br />\n"),
+        InlineKind::Code(code) => out.push_str(&format!("<code>{}</code>", escape_html(code))),
+        InlineKind::Latex(latex) => out.push_str(&format!(
+            "<span class=\"math math-inline\">\\({}\\)</span>",
+            escape_html(latex)
+        )),
+        InlineKind::Html(html) => out.push_str(html),
+        InlineKind::Link(link) => {
+            out.push_str(&format!("<a href=\"{}\"", escape_html(&link.destination)));
+            if let Some(title) = &link.title {
+                out.push_str(&format!(" title=\"{}\"", escape_html(title)));
+            }
+            out.push('>');
+            render_inlines(&link.children, ctx, out);
+            out.push_str("</a>");
+        }
+        InlineKind::LinkReference(r) => {
+            out.push_str(&format!("<a href=\"{}\"", escape_html(&r.destination)));
+            if let Some(title) = &r.title {
+                out.push_str(&format!(" title=\"{}\"", escape_html(title)));
+            }
+            out.push('>');
+            render_inlines(&r.text, ctx, out);
+            out.push_str("</a>");
+        }
+        InlineKind::Autolink(a) => {
+            out.push_str(&format!(
+                "<a href=\"{}\">{}</a>",
+                escape_html(&a.url),
+                escape_html(&a.text)
+            ));
+        }
+        InlineKind::Image(img) => {
+            out.push_str(&format!(
+                "<img src=\"{}\" alt=\"{}\"",
+                escape_html(&img.destination),
+                escape_html(&img.alt)
+            ));
+            if let Some(title) = &img.title {
+                out.push_str(&format!(" title=\"{}\"", escape_html(title)));
+            }
+            if let Some(attr) = &img.attr {
+                if let Some(width) = &attr.width {
+                    out.push_str(&format!(" width=\"{}\"", escape_html(width)));
+                }
+                if let Some(height) = &attr.height {
+                    out.push_str(&format!(" height=\"{}\"", escape_html(height)));
+                }
+            }
+            out.push_str(" />");
+        }
+        InlineKind::Emphasis(children) => {
+            out.push_str("<em>");
+            render_inlines(children, ctx, out);
+            out.push_str("</em>");
+        }
+        InlineKind::Strong(children) => {
+            out.push_str("<strong>");
+            render_inlines(children, ctx, out);
+            out.push_str("</strong>");
+        }
+        InlineKind::Strikethrough(children) => {
+            out.push_str("<del>");
+            render_inlines(children, ctx, out);
+            out.push_str("</del>");
+        }
+        InlineKind::FootnoteReference(label) => {
+            // 找不到定义时原样输出 `[^label]`。
+            match ctx.footnotes.iter().position(|(l, _)| l == label) {
+                Some(i) => out.push_str(&format!(
+                    "<sup class=\"footnote-ref\"><a href=\"#fn-{}\">{}</a></sup>",
+                    escape_html(label),
+                    i + 1
+                )),
+                None => out.push_str(&format!("[^{}]", escape_html(label))),
+            }
+        }
+        InlineKind::Empty => {}
+    }
+}
+
+/// 渲染表格：首行为 `<thead>`，其余为 `<tbody>`。
+fn render_table(table: &Table, ctx: &Context, out: &mut String) {
+    let Some((header, body)) = table.rows.split_first() else {
+        return;
+    };
+    out.push_str("<table>\n<thead>\n");
+    render_row(header, "th", &table.alignments, ctx, out);
+    out.push_str("</thead>\n");
+    if !body.is_empty() {
+        out.push_str("<tbody>\n");
+        for row in body {
+            render_row(row, "td", &table.alignments, ctx, out);
+        }
+        out.push_str("</tbody>\n");
+    }
+    out.push_str("</table>\n");
+}
+
+fn render_row(
+    row: &[crate::ast::TableCell],
+    tag: &str,
+    alignments: &[Alignment],
+    ctx: &Context,
+    out: &mut String,
+) {
+    out.push_str("<tr>");
+    for (col, cell) in row.iter().enumerate() {
+        if cell.value.removed_by_extended_table {
+            continue;
+        }
+        out.push_str(&format!("<{tag}"));
+        if let Some(colspan) = cell.value.colspan.filter(|&n| n > 1) {
+            out.push_str(&format!(" colspan=\"{colspan}\""));
+        }
+        if let Some(rowspan) = cell.value.rowspan.filter(|&n| n > 1) {
+            out.push_str(&format!(" rowspan=\"{rowspan}\""));
+        }
+        let align = match alignments.get(col) {
+            Some(Alignment::Left) => Some("left"),
+            Some(Alignment::Center) => Some("center"),
+            Some(Alignment::Right) => Some("right"),
+            Some(Alignment::None) | None => None,
+        };
+        if let Some(align) = align {
+            out.push_str(&format!(" style=\"text-align: {align}\""));
+        }
+        out.push('>');
+        render_inlines(&cell.value.content, ctx, out);
+        out.push_str(&format!("</{tag}>"));
+    }
+    out.push_str("</tr>\n");
+}
+
+/// 转义 HTML 特殊字符。
+pub fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -1,7 +1,9 @@
 //! Markdown 渲染器。
 
+pub mod html;
 pub mod markdown;
 pub mod typst;
 
+pub use html::render_html;
 pub use markdown::render_markdown;
 pub use typst::render_typst;
@@ -18,6 +18,9 @@ pub trait Transform {
 
     /// 变换所有链接的 URL。
     fn transform_link_urls<F: FnMut(&str) -> String>(&mut self, f: F) -> &mut Self;
+
+    /// 将所有公式替换为原始 HTML：`f(公式, 是否块级)` 返回替换后的 HTML。
+    fn transform_latex_to_html<F: FnMut(&str, bool) -> String>(&mut self, f: F) -> &mut Self;
 }
 
 impl Transform for Document {
@@ -51,6 +54,25 @@ impl Transform for Document {
         );
         self
     }
+
+    fn transform_latex_to_html<F: FnMut(&str, bool) -> String>(&mut self, f: F) -> &mut Self {
+        // 块级与行内回调都要调用 `f`，借用经由 RefCell 共享
+        let f = std::cell::RefCell::new(f);
+        transform_document(
+            self,
+            |block| {
+                if let BlockKind::LatexBlock(latex) = block {
+                    *block = BlockKind::HtmlBlock((f.borrow_mut())(latex, true));
+                }
+            },
+            |inline| {
+                if let InlineKind::Latex(latex) = &inline.value {
+                    inline.value = InlineKind::Html((f.borrow_mut())(latex, false));
+                }
+            },
+        );
+        self
+    }
 }
 
 /// 遍历文档，对每个块/行内节点执行变换。
@@ -51,6 +51,22 @@ fn transform_image_urls() {
     assert!(found, "应找到图片");
 }
 
+#[test]
+fn transform_latex_to_html() {
+    let mut doc = tuack_ng_parser::parse("**$x$**\n\n$$\ny\n$$\n");
+    doc.transform_latex_to_html(|latex, display| format!("<m {}>{}</m>", display, latex));
+    match &doc.blocks[0].value {
+        BlockKind::Paragraph(inlines) => match &inlines[0].value {
+            InlineKind::Strong(children) => {
+                assert!(matches!(&children[0].value, InlineKind::Html(h) if h == "<m false>x</m>"));
+            }
+            other => panic!("应为加粗：{:?}", other),
+        },
+        other => panic!("应为段落：{:?}", other),
+    }
+    assert!(matches!(&doc.blocks[1].value, BlockKind::HtmlBlock(h) if h == "<m true>y\n</m>"));
+}
+
 // ---- visitor ----
 
 #[test]
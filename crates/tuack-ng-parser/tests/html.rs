@@ -0,0 +1,104 @@
+//! HTML 渲染测试。
+//!
+//! 重点验证表格合并、公式的 KaTeX 标记、容器块与脚注的输出。
+
+mod common;
+
+use tuack_ng_parser::printers::render_html;
+
+fn html(src: &str) -> String {
+    render_html(&tuack_ng_parser::parse(src))
+}
+
+#[test]
+fn html_empty_document() {
+    assert_eq!(html("").trim(), "");
+}
+
+#[test]
+fn html_heading_and_paragraph() {
+    let out = html("# 标题\n\n段落 <b> & 文本");
+    assert!(out.contains("<h1>标题</h1>"), "应输出 h1，实际：{out}");
+    // 行内 HTML 原样输出，文本转义
+    assert!(
+        out.contains("<p>段落 <b> &amp; 文本</p>"),
+        "应输出段落，实际：{out}"
+    );
+}
+
+#[test]
+fn html_escape_text() {
+    let out = html("a & b \"c\"");
+    assert!(
+        out.contains("a &amp; b &quot;c&quot;"),
+        "应转义文本，实际：{out}"
+    );
+}
+
+#[test]
+fn html_latex_katex_markup() {
+    let out = html("设 $a<b$。\n\n$$\n\\sum_{i=1}^n i\n$$\n");
+    assert!(
+        out.contains("<span class=\"math math-inline\">\\(a&lt;b\\)</span>"),
+        "行内公式应输出 KaTeX 标记，实际：{out}"
+    );
+    assert!(
+        out.contains("<div class=\"math math-display\">\\["),
+        "块级公式应输出 KaTeX 标记，实际：{out}"
+    );
+}
+
+#[test]
+fn html_table_merged_cells() {
+    let src = "| A1 | < | A3 |\n| :-- | --- | --: |\n| B1 | B2 | ^ |\n";
+    let out = html(src);
+    assert!(out.contains("colspan=\"2\""), "应含 colspan，实际：{out}");
+    assert!(out.contains("rowspan=\"2\""), "应含 rowspan，实际：{out}");
+    assert!(
+        out.contains("style=\"text-align: left\""),
+        "应输出对齐，实际：{out}"
+    );
+    assert!(!out.contains("&lt;"), "`<` 格应被跳过，实际：{out}");
+    assert!(out.contains("<thead>") && out.contains("<tbody>"));
+}
+
+#[test]
+fn html_container_and_figure() {
+    let out = html(":::note\n内容\n:::\n");
+    assert!(
+        out.contains("<div class=\"note\">\n<p>内容</p>\n</div>"),
+        "容器应输出 div，实际：{out}"
+    );
+
+    let out = html("::: {.figure caption=\"图 1\"}\n![](img/a.png)\n:::\n");
+    assert!(
+        out.contains("<figure>\n<p><img src=\"img/a.png\" alt=\"\" /></p>\n<figcaption>图 1</figcaption>\n</figure>"),
+        "figure 应输出 figure 与 figcaption，实际：{out}"
+    );
+}
+
+#[test]
+fn html_footnote() {
+    let out = html("正文[^1]\n\n[^1]: 注释\n");
+    assert!(
+        out.contains("<sup class=\"footnote-ref\"><a href=\"#fn-1\">1</a></sup>"),
+        "应输出脚注引用，实际：{out}"
+    );
+    assert!(
+        out.contains("<li id=\"fn-1\">\n<p>注释</p>\n</li>"),
+        "应在文末输出脚注，实际：{out}"
+    );
+}
+
+#[test]
+fn html_list_and_code() {
+    let out = html("- a\n- b\n\n```cpp\nint x = a < b;\n```\n");
+    assert!(
+        out.contains("<ul>\n<li>a</li>\n<li>b</li>\n</ul>"),
+        "实际：{out}"
+    );
+    assert!(
+        out.contains("<pre><code class=\"language-cpp\">int x = a &lt; b;\n</code></pre>"),
+        "实际：{out}"
+    );
+}
@@ -0,0 +1,31 @@
+use crate::data::stats::StatsArgs;
+use crate::prelude::*;
+use clap::Args;
+use clap::Subcommand;
+
+pub mod stats;
+
+#[derive(Debug, Clone, Subcommand)]
+#[command(version)]
+#[command(infer_subcommands = false)]
+pub enum Targets {
+    #[command(version)]
+    /// 统计数据大小与格式
+    Stats(StatsArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+#[command(version)]
+pub struct DataArgs {
+    /// 执行的操作
+    #[command(subcommand)]
+    pub target: Targets,
+}
+
+pub fn main(args: DataArgs) -> Result<()> {
+    match args.target {
+        Targets::Stats(args) => stats::main(args)?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,278 @@
+use std::fmt;
+use std::io::{BufRead, BufReader};
+
+use bytesize::ByteSize;
+use clap::Args;
+use owo_colors::OwoColorize;
+
+use crate::prelude::*;
+use tuack_utils::data::FsTestData;
+
+#[derive(Args, Debug, Clone)]
+#[command(version)]
+pub struct StatsArgs {
+    /// 每道题目数据（输入与答案合计）的大小上限，如 `50MiB`，超出时以非零状态退出
+    #[arg(long)]
+    pub budget: Option<ByteSize>,
+
+    /// 本次统计的所有题目数据合计的大小上限，用于检查整个竞赛包的大小
+    #[arg(long)]
+    pub total_budget: Option<ByteSize>,
+}
+
+/// 单个数据文件的统计结果
+#[derive(Debug, Default)]
+struct FileStats {
+    size: u64,
+    lines: u64,
+    /// 文件中整数的最大绝对值
+    max_abs: Option<u128>,
+    /// 存在以 `\r\n` 结尾的行
+    crlf: bool,
+    /// 存在以空格或制表符结尾的行
+    trailing_space: bool,
+    /// 非空文件的最后一行没有换行符
+    missing_newline: bool,
+}
+
+impl FileStats {
+    /// 逐行读取文件，大文件不会整个读入内存
+    fn scan(path: &Path) -> Result<Self> {
+        let file = fs::File::open(path).with_context(|| format!("无法读取 {}", path.display()))?;
+        let mut stats = FileStats {
+            size: file.metadata()?.len(),
+            ..Default::default()
+        };
+        let mut reader = BufReader::new(file);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            stats.lines += 1;
+
+            let mut content = line.as_slice();
+            match content.strip_suffix(b"\n") {
+                Some(rest) => content = rest,
+                None => stats.missing_newline = true,
+            }
+            if let Some(rest) = content.strip_suffix(b"\r") {
+                stats.crlf = true;
+                content = rest;
+            }
+            if content.ends_with(b" ") || content.ends_with(b"\t") {
+                stats.trailing_space = true;
+            }
+
+            for token in content.split(u8::is_ascii_whitespace) {
+                let Some(value) = std::str::from_utf8(token)
+                    .ok()
+                    .and_then(|token| token.parse::<i128>().ok())
+                else {
+                    continue;
+                };
+                let value = value.unsigned_abs();
+                stats.max_abs = Some(stats.max_abs.map_or(value, |max| max.max(value)));
+            }
+        }
+        Ok(stats)
+    }
+
+    /// 文件的格式问题
+    fn issues(&self) -> Vec<&'static str> {
+        let mut issues = Vec::new();
+        if self.crlf {
+            issues.push("使用 Windows 换行符");
+        }
+        if self.trailing_space {
+            issues.push("有行末空格");
+        }
+        if self.missing_newline {
+            issues.push("末尾缺少换行");
+        }
+        issues
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "{} / {} 行 / 最大绝对值 {}",
+            ByteSize(self.size),
+            self.lines,
+            self.max_abs
+                .map_or_else(|| "-".to_string(), |max| max.to_string())
+        )
+    }
+}
+
+/// 同类文件（输入或答案）的合计
+#[derive(Debug, Default)]
+struct SizeTotal {
+    total: u64,
+    /// 最大的文件及其测试点编号
+    largest: Option<(u64, u32)>,
+}
+
+impl SizeTotal {
+    fn add(&mut self, id: u32, stats: &FileStats) {
+        self.total += stats.size;
+        if self.largest.is_none_or(|(size, _)| stats.size > size) {
+            self.largest = Some((stats.size, id));
+        }
+    }
+}
+
+impl fmt::Display for SizeTotal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "共 {}", ByteSize(self.total))?;
+        if let Some((size, id)) = self.largest {
+            write!(f, "，最大 {}（测试点 {}）", ByteSize(size), id)?;
+        }
+        Ok(())
+    }
+}
+
+/// 统计单个文件，不存在时给出警告；有格式问题时一并提示
+fn scan_file(problem: &ProblemConfig, path: &Path) -> Result<Option<FileStats>> {
+    let name = path.strip_prefix(&problem.path).unwrap_or(path).display();
+    if !path.exists() {
+        msg_warn!("{} 不存在", name);
+        return Ok(None);
+    }
+    let stats = FileStats::scan(path)?;
+    let issues = stats.issues();
+    if !issues.is_empty() {
+        msg_warn!("{} {}", name.to_string().cyan(), issues.join("、"));
+    }
+    Ok(Some(stats))
+}
+
+/// 统计一道题目的测试数据，返回输入与答案的总大小
+fn stats_problem(problem: &ProblemConfig) -> Result<u64> {
+    msg_progress!("统计题目 {} 的数据", problem.name.magenta());
+
+    let data_items: Vec<FsTestData> = tuack_utils::data::problem_test_data(problem);
+    if data_items.is_empty() {
+        msg_warn!("题目 {} 没有测试数据", problem.name.magenta());
+        return Ok(0);
+    }
+
+    let mut input = SizeTotal::default();
+    let mut answer = SizeTotal::default();
+    // subtask -> (测试点数, 输入大小, 答案大小)
+    let mut subtasks: BTreeMap<u32, (usize, u64, u64)> = BTreeMap::new();
+    for data in &data_items {
+        let input_stats = scan_file(problem, &data.input_path())?;
+        let answer_stats = scan_file(problem, &data.output_path())?;
+
+        let subtask = subtasks.entry(data.subtask()).or_default();
+        subtask.0 += 1;
+        if let Some(stats) = &input_stats {
+            input.add(data.id(), stats);
+            subtask.1 += stats.size;
+        }
+        if let Some(stats) = &answer_stats {
+            answer.add(data.id(), stats);
+            subtask.2 += stats.size;
+        }
+
+        let show = |stats: &Option<FileStats>| {
+            stats
+                .as_ref()
+                .map_or_else(|| "缺失".to_string(), FileStats::summary)
+        };
+        msg_item!(
+            format!("#{}", data.id()).cyan(),
+            "输入 {} | 答案 {}",
+            show(&input_stats),
+            show(&answer_stats)
+        );
+    }
+
+    for (subtask, (count, input_size, answer_size)) in &subtasks {
+        msg_info!(
+            "Subtask {} | {} 个测试点 | 输入 {} | 答案 {}",
+            subtask,
+            count,
+            ByteSize(*input_size),
+            ByteSize(*answer_size)
+        );
+    }
+    msg_info!("输入{}", input);
+    msg_info!("答案{}", answer);
+
+    let total = input.total + answer.total;
+    msg_info!(
+        "{}",
+        format!("题目 {} 的数据合计 {}", problem.name, ByteSize(total)).bold()
+    );
+    Ok(total)
+}
+
+pub fn main(args: StatsArgs) -> Result<()> {
+    let config = gctx().config.as_ref().context("没有可用的工程")?;
+
+    let problems: Vec<&ProblemConfig> = match &config.location {
+        CurrentLocation::None => bail!("没有可用的工程"),
+        CurrentLocation::Root => config
+            .config
+            .subconfig
+            .values()
+            .flat_map(|day| day.subconfig.values())
+            .collect(),
+        CurrentLocation::Day(day) => config
+            .config
+            .subconfig
+            .get(day)
+            .context(format!("无法获取天配置：{}", day))?
+            .subconfig
+            .values()
+            .collect(),
+        CurrentLocation::Problem(day, problem) => vec![
+            config
+                .config
+                .subconfig
+                .get(day)
+                .context(format!("无法获取天配置：{}", day))?
+                .subconfig
+                .get(problem)
+                .context(format!("无法获取题目配置：{}/{}", day, problem))?,
+        ],
+    };
+
+    let mut total = 0;
+    let mut over_budget = Vec::new();
+    for problem in problems {
+        let size = stats_problem(problem)?;
+        total += size;
+        if let Some(budget) = args.budget
+            && size > budget.as_u64()
+        {
+            msg_error!(
+                "题目 {} 的数据 {} 超出预算 {}",
+                problem.name.magenta(),
+                ByteSize(size),
+                budget
+            );
+            over_budget.push(problem.name.clone());
+        }
+    }
+
+    if !matches!(config.location, CurrentLocation::Problem(..)) {
+        msg_info!(
+            "{}",
+            format!("所有题目的数据合计 {}", ByteSize(total)).bold()
+        );
+    }
+    if let Some(budget) = args.total_budget
+        && total > budget.as_u64()
+    {
+        msg_error!("所有题目的数据 {} 超出预算 {}", ByteSize(total), budget);
+        over_budget.push("合计".to_string());
+    }
+
+    if !over_budget.is_empty() {
+        bail!("数据超出预算：{}", over_budget.join(", "));
+    }
+    Ok(())
+}
@@ -1,21 +1,26 @@
+pub mod script;
+
 use std::fmt;
 use std::str::FromStr;
+use std::sync::Mutex;
 use std::time::Duration;
 
 use clap::Args;
 use clap::ValueEnum;
+use futures::stream::{self, StreamExt};
 use indicatif::ProgressBar;
 use owo_colors::OwoColorize;
 use rand::Rng;
 
 use crate::context::gctx;
+use tuack_config::{GeneratorConfig, InteractiveMode};
 use tuack_utils::data::FsTestData;
 use crate::prelude::*;
 use tuack_lib::dmk::{DmkParams, DmkSession};
 use tuack_lib::utils::testlib::{Generator, Validator};
 use tuack_utils::compilers::cpp::CppRunner;
 use tuack_utils::compilers::general::GeneralRunner;
-use tuack_utils::compilers::generator::CppGenerator;
+use tuack_utils::compilers::generator::{CppGenerator, GeneralGenerator};
 use crate::utils::random::gen_rnd;
 use crate::utils::test_object::parse_test_object;
 use crate::validate::compile_validator;
@@ -26,6 +31,8 @@ pub enum Target {
     Data,
     /// 样例数据
     Sample,
+    /// 预测试数据
+    Pretest,
 }
 
 impl fmt::Display for Target {
@@ -33,6 +40,7 @@ impl fmt::Display for Target {
         match self {
             Target::Data => write!(f, "data"),
             Target::Sample => write!(f, "sample"),
+            Target::Pretest => write!(f, "pretest"),
         }
     }
 }
@@ -45,6 +53,8 @@ pub enum DmkCommand {
     Regen,
     /// 重置种子
     Reset,
+    /// 按生成脚本展开数据点，写回配置后重新生成
+    Script,
 }
 
 #[derive(Args, Debug)]
@@ -65,6 +75,10 @@ pub struct DmkArgs {
     /// 生成后校验输入（覆盖配置，如 --validate 或 --validate=false）
     #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
     validate: Option<bool>,
+
+    /// 并行生成的数据点数
+    #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    jobs: u32,
 }
 
 /// 数据点生成结果（展示状态）。
@@ -108,7 +122,7 @@ impl From<DmkCommand> for DmkResult {
     fn from(action: DmkCommand) -> Self {
         match action {
             DmkCommand::Gen => DmkResult::Gen,
-            DmkCommand::Regen => DmkResult::Regen,
+            DmkCommand::Regen | DmkCommand::Script => DmkResult::Regen,
             DmkCommand::Reset => DmkResult::Reset,
         }
     }
@@ -203,15 +217,17 @@ fn merge_seeds(seeds: &mut BTreeMap<u32, u64>, items: &[FsTestData], force: bool
     Ok(())
 }
 
-/// 保存种子
+/// 保存种子：先写临时文件再替换，中途失败不会留下残缺的 `.seed`
 fn save_seed(target_dir: &Path, seeds: &BTreeMap<u32, u64>) -> Result<()> {
     let seed_file = target_dir.join(".seed");
-    std::fs::write(seed_file, serde_json::to_string_pretty(seeds)?)?;
+    let tmp_file = target_dir.join(".seed.tmp");
+    std::fs::write(&tmp_file, serde_json::to_string_pretty(seeds)?)?;
+    std::fs::rename(&tmp_file, &seed_file)?;
     Ok(())
 }
 
 /// 查找标程（tests 中期望得分 == 100 的文件）
-fn find_std(problem: &ProblemConfig) -> Result<PathBuf> {
+pub fn find_std(problem: &ProblemConfig) -> Result<PathBuf> {
     for (name, case) in &problem.tests {
         if let ExpectedScore::Single(str) = &case.expected
             && str.replace(' ', "") == "==100"
@@ -225,8 +241,73 @@ fn find_std(problem: &ProblemConfig) -> Result<PathBuf> {
     bail!("未找到标程文件")
 }
 
+/// 按名称查找生成脚本中的生成器：与配置的生成器同名时使用配置，否则在题目目录下查找 `<名称>.<后缀>`
+fn find_generator(problem: &ProblemConfig, configured: &Path, name: &str) -> Result<PathBuf> {
+    if configured.file_stem().is_some_and(|stem| stem == name) {
+        return Ok(configured.to_path_buf());
+    }
+    ["cpp"]
+        .into_iter()
+        .chain(gctx().languages.keys().map(String::as_str))
+        .map(|ext| problem.path.join(format!("{}.{}", name, ext)))
+        .find(|path| path.is_file())
+        .with_context(|| format!("找不到生成器 {}", name))
+}
+
+/// 读取数据生成器的依赖文件
+pub fn generator_deps(
+    problem: &ProblemConfig,
+    generator_config: &GeneratorConfig,
+) -> Result<IndexMap<String, Vec<u8>>> {
+    let mut deps: IndexMap<String, Vec<u8>> = IndexMap::new();
+    for dep_path in &generator_config.deps {
+        let abs = problem.path.join(dep_path);
+        let content =
+            std::fs::read(&abs).with_context(|| format!("读取依赖文件失败：{}", abs.display()))?;
+        let name = abs
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        deps.insert(name, content);
+    }
+    Ok(deps)
+}
+
+/// 编译数据生成器
+pub fn compile_generator(
+    gen_path: &Path,
+    day_config: &ContestDayConfig,
+    deps: IndexMap<String, Vec<u8>>,
+) -> Result<Box<dyn Generator>> {
+    let gen_compile_pb = gctx().multiprogress.add(ProgressBar::new_spinner());
+    gen_compile_pb.enable_steady_tick(Duration::from_millis(100));
+    gen_compile_pb.set_message(format!(
+        "编译数据生成器 {}",
+        gen_path.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let mut generator: Box<dyn Generator> = match gen_path
+        .extension()
+        .context("数据生成器无后缀名")?
+        .to_string_lossy()
+        .as_ref()
+    {
+        "cpp" => Box::new(CppGenerator::new(gen_path, &day_config.compile, deps)?),
+        _ => Box::new(GeneralGenerator::new(
+            gen_path,
+            &day_config.compile,
+            deps,
+            &gctx().languages,
+        )?),
+    };
+    let gen_result = generator.prepare();
+    gen_compile_pb.finish_and_clear();
+    gen_result.with_context(|| format!("数据生成器 {} 编译失败", gen_path.display()))?;
+    Ok(generator)
+}
+
 /// 构造标程运行器
-fn build_std_runner(
+pub fn build_std_runner(
     std_path: &Path,
     day_config: &ContestDayConfig,
     problem_config: &ProblemConfig,
@@ -251,9 +332,15 @@ fn build_std_runner(
         )?),
     };
 
-    if problem_config.problem_type == ProblemType::Interactive && runner.manifest().interactive {
-        let interactive = problem_config.interactive.as_ref().unwrap();
-
+    // 交互器方式的题目由标程直接生成答案，交互器只在评测时使用
+    let interactive = problem_config
+        .interactive
+        .as_ref()
+        .filter(|i| i.mode == InteractiveMode::Grader);
+    if problem_config.problem_type == ProblemType::Interactive
+        && runner.manifest().interactive
+        && let Some(interactive) = interactive
+    {
         let resolve_path = |path: &String| -> Result<PathBuf> {
             let p = PathBuf::from_str(path)?;
             Ok(if p.is_absolute() {
@@ -302,14 +389,38 @@ pub async fn main(args: DmkArgs) -> Result<()> {
             bail!("本命令只能在题目目录下执行");
         };
 
+    let target_dir = match &args.target {
+        Target::Data => current_problem.path.join("data"),
+        Target::Sample => current_problem.path.join("sample"),
+        Target::Pretest => current_problem.path.join("pre"),
+    };
+    if !target_dir.exists() {
+        std::fs::create_dir_all(&target_dir)?;
+        info!("创建目标目录：{}", target_dir.display());
+    }
+
+    // 生成脚本模式：展开脚本写回配置，之后按新的配置生成
+    let expanded;
+    let current_problem = if args.action == DmkCommand::Script {
+        if args.target == Target::Sample {
+            bail!("样例不支持生成脚本");
+        }
+        expanded =
+            script::apply_script(current_problem, &target_dir, args.target == Target::Pretest)?;
+        &expanded
+    } else {
+        current_problem
+    };
+
     let selected: Vec<FsTestData> = match &args.target {
         Target::Data => tuack_utils::data::problem_test_data(current_problem),
+        Target::Pretest => tuack_utils::data::problem_pretest_data(current_problem),
         Target::Sample => tuack_utils::data::problem_sample_data(current_problem),
     };
     let selected = parse_test_object(&args.object, &selected, FsTestData::id)?;
 
     let generator_config = match &args.target {
-        Target::Data => current_problem
+        Target::Data | Target::Pretest => current_problem
             .generator
             .as_ref()
             .context("generator 未配置")?
@@ -328,27 +439,36 @@ pub async fn main(args: DmkArgs) -> Result<()> {
 
     let gen_path = resolve(&generator_config.source);
 
-    let mut deps: IndexMap<String, Vec<u8>> = IndexMap::new();
-    for dep_path in &generator_config.deps {
-        let abs = resolve(dep_path);
-        let content =
-            std::fs::read(&abs).with_context(|| format!("读取依赖文件失败：{}", abs.display()))?;
-        let name = abs
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
-        deps.insert(name, content);
-    }
+    let deps = generator_deps(current_problem, &generator_config)?;
 
-    // 编译数据生成器
-    let gen_compile_pb = gctx().multiprogress.add(ProgressBar::new_spinner());
-    gen_compile_pb.enable_steady_tick(Duration::from_millis(100));
-    gen_compile_pb.set_message("编译数据生成器");
-    let mut generator = CppGenerator::new(&gen_path, &current_day.compile, deps)?;
-    let gen_result = generator.prepare();
-    gen_compile_pb.finish_and_clear();
-    gen_result.context("数据生成器编译失败")?;
+    // 编译数据生成器：生成脚本写入的命令按首个词选用生成器，其余数据点使用配置的生成器
+    let mut commands: Vec<Option<(String, Vec<String>)>> = Vec::with_capacity(selected.len());
+    for item in &selected {
+        commands.push(match item.command() {
+            Some(command) => {
+                let mut argv = shellwords::split(command)
+                    .with_context(|| format!("测试点 {} 的生成命令无效", item.id()))?;
+                if argv.is_empty() {
+                    bail!("测试点 {} 的生成命令为空", item.id());
+                }
+                let name = argv.remove(0);
+                Some((name, argv))
+            }
+            None => None,
+        });
+    }
+    let mut generators: HashMap<Option<String>, Box<dyn Generator>> = HashMap::new();
+    for name in commands.iter().map(|c| c.as_ref().map(|(name, _)| name)) {
+        if generators.contains_key(&name.cloned()) {
+            continue;
+        }
+        let path = match name {
+            Some(name) => find_generator(current_problem, &gen_path, name)?,
+            None => gen_path.clone(),
+        };
+        let generator = compile_generator(&path, current_day, deps.clone())?;
+        generators.insert(name.cloned(), generator);
+    }
 
     let effective_validate = args.validate.unwrap_or(generator_config.validate);
     let validator: Option<Box<dyn Validator>> = if effective_validate {
@@ -360,15 +480,6 @@ pub async fn main(args: DmkArgs) -> Result<()> {
         None
     };
 
-    let target_dir = match &args.target {
-        Target::Data => current_problem.path.join("data"),
-        Target::Sample => current_problem.path.join("sample"),
-    };
-    if !target_dir.exists() {
-        std::fs::create_dir_all(&target_dir)?;
-        info!("创建目标目录：{}", target_dir.display());
-    }
-
     // 种子：加载 -> 合并（Reset 强制重生成）-> 结束时保存
     let mut seeds = load_seeds(&target_dir).await;
     merge_seeds(
@@ -394,11 +505,19 @@ pub async fn main(args: DmkArgs) -> Result<()> {
     std_compile_pb.finish_and_clear();
     std_result?;
 
+    // 每个并行任务各持有一个标程运行器
+    let jobs = (args.jobs as usize).min(selected.len()).max(1);
+    let mut pool = Vec::with_capacity(jobs);
+    for _ in 1..jobs {
+        pool.push(runner.fork()?);
+    }
+    pool.push(runner);
+    let pool = Mutex::new(pool);
+
     let params = DmkParams {
         problem_name: current_problem.name.clone(),
         file_io: current_problem.file_io.unwrap_or(true),
     };
-    let mut session = DmkSession::new(&mut *runner, &mut generator, validator.as_deref(), params);
 
     let dmk_pb = gctx()
         .multiprogress
@@ -410,6 +529,44 @@ pub async fn main(args: DmkArgs) -> Result<()> {
             .progress_chars("=> "),
     );
 
+    // 所有数据点并行生成，结果按数据点顺序取回
+    let generators_ref = &generators;
+    let validator_ref = validator.as_deref();
+    let pool_ref = &pool;
+    let params_ref = &params;
+    let seeds_ref = &seeds;
+    let action = args.action;
+    let mut generated = stream::iter(selected.iter().zip(&commands))
+        .map(move |(item, command)| async move {
+            let mut runner = pool_ref.lock().unwrap().pop().context("运行器已全部占用")?;
+            let name = command.as_ref().map(|(name, _)| name.clone());
+            let mut session = DmkSession::new(
+                runner.as_mut(),
+                generators_ref[&name].as_ref(),
+                validator_ref,
+                params_ref.clone(),
+            );
+            if let Some((_, argv)) = command {
+                session = session.with_argv(argv.clone());
+            }
+            let seed = seeds_ref[&item.id()];
+            let result = async {
+                let input = gen_input(&mut session, item, seed, action).await?;
+                let output = if matches!(input, DmkResult::Fail(_)) {
+                    DmkResult::Skip
+                } else {
+                    gen_output(&mut session, item, action).await?
+                };
+                Ok::<_, anyhow::Error>((input, output))
+            }
+            .await;
+            drop(session);
+            pool_ref.lock().unwrap().push(runner);
+            result
+        })
+        .buffered(jobs);
+
+    let mut error = None;
     for item in &selected {
         dmk_pb.set_message(format!(
             "{}/{} | 正在生成数据点 #{}",
@@ -418,23 +575,29 @@ pub async fn main(args: DmkArgs) -> Result<()> {
             item.id()
         ));
 
-        let seed = *seeds.get(&item.id()).unwrap();
-        let status = gen_input(&mut session, item, seed, args.action).await?;
-        report_status(item.id(), "输入", &status);
-
-        if matches!(status, DmkResult::Fail(_)) {
-            report_status(item.id(), "输出", &DmkResult::Skip);
-        } else {
-            let status = gen_output(&mut session, item, args.action).await?;
-            report_status(item.id(), "输出", &status);
+        match generated.next().await.context("生成任务意外结束")? {
+            Ok((input, output)) => {
+                report_status(item.id(), "输入", &input);
+                report_status(item.id(), "输出", &output);
+            }
+            Err(e) => {
+                error = Some(e);
+                break;
+            }
         }
 
         dmk_pb.inc(1);
     }
+    drop(generated);
 
-    dmk_pb.finish_with_message("数据生成完成！");
-
+    // 即使生成中途出错也保存种子
     save_seed(&target_dir, &seeds)?;
+    if let Some(e) = error {
+        dmk_pb.abandon();
+        return Err(e);
+    }
+
+    dmk_pb.finish_with_message("数据生成完成！");
 
     Ok(())
 }
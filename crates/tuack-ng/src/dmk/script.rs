@@ -0,0 +1,334 @@
+use bytesize::ByteSize;
+
+use crate::prelude::*;
+use tuack_config::msgs::LoadContext;
+use tuack_config::{BundleDataItem, CONFIG_FILE_NAME, SingleDataItem};
+
+/// 生成脚本中一行给出的数据来源
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptSource {
+    /// 运行生成器，首个词为生成器名，如 `gen_tree --n=100000 --type=chain`
+    Gen(Vec<String>),
+    /// 使用现成的输入文件（相对于题目目录），如 `file tests/07.in`
+    File(String),
+}
+
+/// 生成脚本展开后的一个测试点
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptPoint {
+    pub id: u32,
+    /// 所在行号，同一行展开的相邻测试点合并为一组
+    pub line: usize,
+    pub source: ScriptSource,
+}
+
+/// 解析 `> ` 之后的目标：`$` 为下一个测试点，另可写 `7`、`{3-5}`、`{1,4-6}`
+fn parse_target(target: &str, next: u32) -> Result<Vec<u32>> {
+    if target == "$" {
+        return Ok(vec![next]);
+    }
+    let ranges = match target.strip_prefix('{') {
+        Some(rest) => rest.strip_suffix('}').context("缺少 `}`")?,
+        None => target,
+    };
+    let mut ids = Vec::new();
+    for part in ranges.split(',') {
+        let part = part.trim();
+        let (start, end) = part.split_once('-').unwrap_or((part, part));
+        let start: u32 = start
+            .trim()
+            .parse()
+            .with_context(|| format!("无效的测试点 {}", part))?;
+        let end: u32 = end
+            .trim()
+            .parse()
+            .with_context(|| format!("无效的测试点 {}", part))?;
+        if start == 0 || start > end {
+            bail!("无效的测试点范围 {}", part);
+        }
+        ids.extend(start..=end);
+    }
+    Ok(ids)
+}
+
+fn parse_line(line: &str, next: u32) -> Result<(ScriptSource, Vec<u32>)> {
+    let mut tokens = shellwords::split(line)?;
+
+    // 目标写在最后的 `>` 之后，`>` 可以与目标连写；省略时为下一个测试点
+    let target = match tokens.iter().rposition(|t| t.starts_with('>')) {
+        Some(pos) => {
+            let mut target: Vec<String> = tokens.drain(pos..).collect();
+            let first = target[0].split_off(1);
+            target[0] = first;
+            target.retain(|t| !t.is_empty());
+            match target.as_slice() {
+                [target] => parse_target(target, next)?,
+                _ => bail!("`>` 之后应当恰好有一个目标"),
+            }
+        }
+        None => vec![next],
+    };
+
+    let source = match tokens.as_slice() {
+        [] => bail!("缺少生成命令"),
+        [file, path] if file == "file" => ScriptSource::File(path.clone()),
+        [file, ..] if file == "file" => bail!("`file` 之后应当恰好有一个文件"),
+        _ => ScriptSource::Gen(tokens),
+    };
+    Ok((source, target))
+}
+
+/// 解析生成脚本并展开为测试点，按编号排序
+///
+/// 每行一条，`#` 开头的行为注释：
+/// - `gen 1000 5 > $`：运行生成器 `gen`，写入下一个测试点
+/// - `gen_tree --n=1e5 --type=chain > {3-5}`：每个测试点各运行一次，种子各不相同
+/// - `file tests/07.in`：使用现成的输入文件
+pub fn expand_script(content: &str) -> Result<Vec<ScriptPoint>> {
+    let mut points: BTreeMap<u32, ScriptPoint> = BTreeMap::new();
+    let mut last = 0;
+    for (idx, line) in content.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (source, ids) = parse_line(line, last + 1)
+            .with_context(|| format!("生成脚本第 {} 行无效：{}", line_no, line))?;
+        for id in ids {
+            if let Some(prev) = points.get(&id) {
+                bail!(
+                    "生成脚本第 {} 行与第 {} 行都写入了测试点 {}",
+                    line_no,
+                    prev.line,
+                    id
+                );
+            }
+            last = last.max(id);
+            points.insert(
+                id,
+                ScriptPoint {
+                    id,
+                    line: line_no,
+                    source: source.clone(),
+                },
+            );
+        }
+    }
+    Ok(points.into_values().collect())
+}
+
+/// 拼接生成命令，只转义含空白、引号或反斜杠的参数，保持配置文件易读
+fn join_command(tokens: &[String]) -> String {
+    tokens
+        .iter()
+        .map(|token| {
+            if token.is_empty()
+                || token.contains(|c: char| c.is_whitespace() || matches!(c, '\'' | '"' | '\\'))
+            {
+                shellwords::escape(token)
+            } else {
+                token.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 已有配置中测试点的属性，脚本展开时保留
+#[derive(Debug, Clone, PartialEq)]
+struct PointAttrs {
+    score: u32,
+    subtask: u32,
+    time_limit: Option<f64>,
+    memory_limit: Option<ByteSize>,
+    input: Option<String>,
+    output: Option<String>,
+}
+
+fn existing_attrs(data: &[DataItem], id: u32) -> Option<PointAttrs> {
+    data.iter().find_map(|item| match item {
+        DataItem::Single(item) if item.id == id => Some(PointAttrs {
+            score: item.score,
+            subtask: item.subtask,
+            time_limit: item.time_limit,
+            memory_limit: item.memory_limit,
+            input: item.input.clone(),
+            output: item.output.clone(),
+        }),
+        DataItem::Bundle(item) if item.id.contains(&(id as i32)) => Some(PointAttrs {
+            score: item.score,
+            subtask: item.subtask,
+            time_limit: item.time_limit,
+            memory_limit: item.memory_limit,
+            input: None,
+            output: None,
+        }),
+        _ => None,
+    })
+}
+
+fn data_ids(data: &[DataItem]) -> Vec<u32> {
+    data.iter()
+        .flat_map(|item| match item {
+            DataItem::Single(item) => vec![item.id],
+            DataItem::Bundle(item) => item.id.iter().map(|&id| id as u32).collect(),
+        })
+        .collect()
+}
+
+/// 按展开结果生成数据配置
+///
+/// 已有测试点保留分值、subtask、限制与文件名，新增的测试点记 0 分；
+/// 同一行展开的相邻测试点属性相同时合并为一组。返回新配置与新增的测试点编号。
+pub fn script_data(
+    points: &[ScriptPoint],
+    data: &[DataItem],
+    dmk: DmkConfig,
+) -> (Vec<DataItem>, Vec<u32>) {
+    let mut added = Vec::new();
+    let attrs: Vec<PointAttrs> = points
+        .iter()
+        .map(|point| {
+            existing_attrs(data, point.id).unwrap_or_else(|| {
+                added.push(point.id);
+                PointAttrs {
+                    score: 0,
+                    subtask: 0,
+                    time_limit: None,
+                    memory_limit: None,
+                    input: None,
+                    output: None,
+                }
+            })
+        })
+        .collect();
+
+    let mut items: Vec<DataItem> = Vec::new();
+    let mut start = 0;
+    while start < points.len() {
+        let point = &points[start];
+        let attr = &attrs[start];
+        let mut end = start + 1;
+        let groupable = attr.input.is_none() && attr.output.is_none();
+        while groupable
+            && end < points.len()
+            && points[end].line == point.line
+            && points[end].id == points[end - 1].id + 1
+            && attrs[end] == *attr
+        {
+            end += 1;
+        }
+
+        let (command, point_dmk) = match &point.source {
+            // 脚本要求生成输入，题目缺省不生成时单独开启
+            ScriptSource::Gen(tokens) => (
+                Some(join_command(tokens)),
+                (dmk != DmkConfig::On).then_some(DmkConfig::On),
+            ),
+            ScriptSource::File(_) => (None, Some(DmkConfig::Output)),
+        };
+
+        if end - start > 1 {
+            items.push(DataItem::Bundle(BundleDataItem {
+                id: points[start..end].iter().map(|p| p.id as i32).collect(),
+                score: attr.score,
+                subtask: attr.subtask,
+                orig_args: IndexMap::new(),
+                dmk: point_dmk,
+                command,
+                time_limit: attr.time_limit,
+                memory_limit: attr.memory_limit,
+            }));
+        } else {
+            items.push(DataItem::Single(SingleDataItem {
+                id: point.id,
+                score: attr.score,
+                subtask: attr.subtask,
+                input: attr.input.clone(),
+                output: attr.output.clone(),
+                orig_args: IndexMap::new(),
+                dmk: point_dmk,
+                command,
+                time_limit: attr.time_limit,
+                memory_limit: attr.memory_limit,
+            }));
+        }
+        start = end;
+    }
+
+    (items, added)
+}
+
+/// 展开题目的生成脚本：复制 `file` 指定的输入，将数据点写回 `conf.json`，返回重新加载的题目配置
+pub fn apply_script(
+    problem: &ProblemConfig,
+    target_dir: &Path,
+    pretest: bool,
+) -> Result<ProblemConfig> {
+    let script = problem
+        .generator
+        .as_ref()
+        .and_then(|pair| pair.data.script.as_deref())
+        .context("未配置生成脚本（generator.data.script）")?;
+    let script_path = problem.path.join(script);
+    let content = fs::read_to_string(&script_path)
+        .with_context(|| format!("无法读取生成脚本 {}", script_path.display()))?;
+    let points = expand_script(&content)?;
+    if points.is_empty() {
+        bail!("生成脚本 {} 中没有测试点", script_path.display());
+    }
+
+    let config_path = problem.path.join(CONFIG_FILE_NAME);
+    let mut config = ProblemConfig::load(&mut LoadContext::new(), &config_path)?;
+    let data = if pretest {
+        &mut config.pretest
+    } else {
+        &mut config.data
+    };
+
+    let kept: Vec<u32> = points.iter().map(|p| p.id).collect();
+    for id in data_ids(data) {
+        if !kept.contains(&id) {
+            msg_warn!("测试点 {} 不在生成脚本中，已从配置中移除", id);
+        }
+    }
+
+    let (items, added) = script_data(&points, data, problem.dmk);
+    *data = items;
+    if !added.is_empty() {
+        let added: Vec<String> = added.iter().map(u32::to_string).collect();
+        msg_warn!(
+            "新增测试点 {} 的分值记为 0，请在 conf.json 中调整",
+            added.join(", ")
+        );
+    }
+
+    // `file` 指定的输入直接复制到测试点的输入文件
+    fs::create_dir_all(target_dir)?;
+    for point in &points {
+        if let ScriptSource::File(path) = &point.source {
+            let src = problem.path.join(path);
+            let input = existing_attrs(data, point.id)
+                .and_then(|attrs| attrs.input)
+                .unwrap_or_else(|| format!("{}.in", point.id));
+            fs::copy(&src, target_dir.join(&input))
+                .with_context(|| format!("无法复制 {}", src.display()))?;
+        }
+    }
+
+    fs::write(&config_path, config.save()?)?;
+    msg_info!(
+        "生成脚本展开为 {} 个测试点，已写入 {}",
+        points.len(),
+        CONFIG_FILE_NAME
+    );
+
+    let mut reloaded = ProblemConfig::load(&mut LoadContext::new(), &config_path)?;
+    // 由场次继承的设置不在题目配置文件中，沿用当前配置
+    reloaded.use_pretest = problem.use_pretest;
+    reloaded.noi_style = problem.noi_style;
+    reloaded.file_io = problem.file_io;
+    Ok(reloaded)
+}
@@ -1,11 +1,13 @@
 use crate::prelude::*;
 use tuack_lib::dump::{
-    DumpCase, DumpConfig, DumpDocument, DumpFile, DumpProblem, DumpSample, DumpSubtask, Dumper,
-    ScorePolicy,
+    DumpCase, DumpConfig, DumpDocument, DumpFile, DumpInteractive, DumpProblem, DumpSample,
+    DumpSubtask, Dumper, ScorePolicy,
 };
+use tuack_config::{DEFAULT_LANG, InteractiveMode};
 use tuack_lib::ren::ProblemType;
 use tuack_utils::assets::FsAssetProvider;
-use tuack_utils::dump::{arbiter, lemon};
+use tuack_utils::dump::{arbiter, hydro, lemon, polygon, syzoj};
+use tuack_utils::ren::manifest::{TargetType, TemplateManifest};
 use clap::Args;
 use clap::ValueEnum;
 use std::collections::HashSet;
@@ -15,6 +17,9 @@ use std::time::Duration;
 pub enum Target {
     Lemon,
     Arbiter,
+    Hydro,
+    Syzoj,
+    Polygon,
 }
 
 impl Target {
@@ -23,8 +28,29 @@ impl Target {
         match self {
             Target::Lemon => "lemon",
             Target::Arbiter => "arbiter",
+            Target::Hydro => "hydro",
+            Target::Syzoj => "syzoj",
+            Target::Polygon => "polygon",
         }
     }
+
+    /// 是否需要题面（需要时前端按 Markdown 目标展开题面）
+    fn needs_statement(&self) -> bool {
+        matches!(self, Target::Hydro | Target::Polygon)
+    }
+}
+
+/// 导出题面时使用的 Markdown 模板清单：在线评测平台均使用标准输入输出
+fn statement_manifest() -> TemplateManifest {
+    TemplateManifest {
+        use_pretest: false,
+        noi_style: true,
+        file_io: false,
+        target: TargetType::Markdown,
+        filelist: IndexMap::new(),
+        processor: Vec::new(),
+        languages: Vec::new(),
+    }
 }
 
 #[derive(Args, Debug)]
@@ -63,12 +89,52 @@ fn collect_extra_down(
     Ok(())
 }
 
+/// 转换展开后的测试点，`dir` 为数据所在目录（`data` 或 `pre`）
+fn dump_cases(dir: &str, items: &[tuack_config::ExpandedDataItem]) -> Vec<DumpCase> {
+    items
+        .iter()
+        .map(|case| DumpCase {
+            id: case.id,
+            score: case.score,
+            subtask: case.subtask,
+            input: PathBuf::from(format!("{}/{}", dir, case.input)),
+            output: PathBuf::from(format!("{}/{}", dir, case.output)),
+            time_limit: Duration::from_secs_f64(case.time_limit),
+            memory_limit: case.memory_limit,
+        })
+        .collect()
+}
+
+fn dump_subtasks(subtasks: &BTreeMap<u32, SubtaskItem>) -> BTreeMap<u32, DumpSubtask> {
+    subtasks
+        .iter()
+        .map(|(k, st)| {
+            (
+                *k,
+                DumpSubtask {
+                    items: st.items.clone(),
+                    max_score: st.max_score,
+                    policy: match st.policy {
+                        tuack_config::ScorePolicy::Sum => ScorePolicy::Sum,
+                        tuack_config::ScorePolicy::Min => ScorePolicy::Min,
+                        tuack_config::ScorePolicy::Max => ScorePolicy::Max,
+                        tuack_config::ScorePolicy::Product => ScorePolicy::Product,
+                    },
+                    dependencies: st.dependencies.clone(),
+                },
+            )
+        })
+        .collect()
+}
+
 /// 从 config 提取 day 级导出文档（前端构造纯数据，dumper 不接触 config 类型）。
 fn build_dump_document(
     contest: &ContestConfig,
     day: &ContestDayConfig,
     daynum: usize,
+    target: Target,
 ) -> Result<DumpDocument> {
+    let manifest = statement_manifest();
     let compile = day
         .compile
         .iter()
@@ -80,38 +146,10 @@ fn build_dump_document(
     for (idx, (_, prob)) in day.subconfig.iter().enumerate() {
         assets.register(idx as u64, prob.path.clone());
 
-        let data = prob
-            .runtime
-            .data
-            .iter()
-            .map(|case| DumpCase {
-                id: case.id,
-                score: case.score,
-                subtask: case.subtask,
-                input: PathBuf::from(format!("data/{}", case.input)),
-                output: PathBuf::from(format!("data/{}", case.output)),
-            })
-            .collect();
-
-        let subtasks = prob
-            .runtime
-            .subtasks
-            .iter()
-            .map(|(k, st)| {
-                (
-                    *k,
-                    DumpSubtask {
-                        items: st.items.clone(),
-                        max_score: st.max_score,
-                        policy: match st.policy {
-                            tuack_config::ScorePolicy::Sum => ScorePolicy::Sum,
-                            tuack_config::ScorePolicy::Min => ScorePolicy::Min,
-                            tuack_config::ScorePolicy::Max => ScorePolicy::Max,
-                        },
-                    },
-                )
-            })
-            .collect();
+        let data = dump_cases("data", &prob.runtime.data);
+        let subtasks = dump_subtasks(&prob.runtime.subtasks);
+        let pretest = dump_cases("pre", &prob.runtime.pretest);
+        let pretest_subtasks = dump_subtasks(&prob.runtime.pretest_subtasks);
 
         let samples = prob
             .samples
@@ -123,6 +161,28 @@ fn build_dump_document(
             .collect();
 
         let checker = prob.checker.as_ref().map(|c| PathBuf::from(&c.data.source));
+        let validator = prob
+            .validator
+            .as_ref()
+            .map(|v| PathBuf::from(&v.data.source));
+        let generator = prob
+            .generator
+            .as_ref()
+            .map(|g| PathBuf::from(&g.data.source));
+        let interactive = prob
+            .interactive
+            .as_ref()
+            .filter(|i| i.mode == InteractiveMode::Grader)
+            .map(|i| DumpInteractive {
+                grader: PathBuf::from(&i.grader),
+                header: PathBuf::from(&i.header),
+            });
+        let interactor = prob
+            .interactive
+            .as_ref()
+            .filter(|i| i.mode == InteractiveMode::Interactor)
+            .and_then(|i| i.interactor.as_ref())
+            .map(PathBuf::from);
 
         let mut extra_down = Vec::new();
         let extra_dir = prob.path.join("down");
@@ -135,6 +195,18 @@ fn build_dump_document(
             collect_extra_down(&extra_dir, "", &sample_files, &mut extra_down)?;
         }
 
+        let statement = if target.needs_statement() {
+            match crate::ren::load_statement(contest, &manifest, day, prob, DEFAULT_LANG) {
+                Ok(ast) => Some(ast),
+                Err(e) => {
+                    msg_warn!("读取题目 {} 的题面失败：{:?}", prob.name, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         problems.push(DumpProblem {
             idx: idx as u64,
             name: prob.name.clone(),
@@ -143,14 +215,22 @@ fn build_dump_document(
                 tuack_config::ProblemType::Program => ProblemType::Program,
                 tuack_config::ProblemType::Output => ProblemType::Output,
                 tuack_config::ProblemType::Interactive => ProblemType::Interactive,
+                tuack_config::ProblemType::Communication => ProblemType::Communication,
             },
             time_limit: Duration::from_secs_f64(prob.time_limit),
             memory_limit: prob.memory_limit,
             data,
             subtasks,
+            pretest,
+            pretest_subtasks,
             samples,
             extra_down,
             checker,
+            validator,
+            generator,
+            interactive,
+            interactor,
+            statement,
         });
     }
 
@@ -172,7 +252,7 @@ async fn dump_main(
     daynum: usize,
     target: Target,
 ) -> Result<()> {
-    let doc = build_dump_document(contest, day, daynum)?;
+    let doc = build_dump_document(contest, day, daynum, target)?;
     let dump_dir = day.path.join("dump");
 
     let tmp = tempfile::Builder::new()
@@ -186,6 +266,9 @@ async fn dump_main(
             tmp.path().to_path_buf(),
             gctx().assets_dirs.clone(),
         )),
+        Target::Hydro => Box::new(hydro::HydroDumper::new(tmp.path().to_path_buf())),
+        Target::Syzoj => Box::new(syzoj::SyzojDumper::new()),
+        Target::Polygon => Box::new(polygon::PolygonDumper::new()),
     };
 
     let (files, warnings) = match dumper.dump(&doc).await {
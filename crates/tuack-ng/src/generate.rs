@@ -1,4 +1,4 @@
-use tuack_config::SingleDataItem;
+use tuack_config::{SingleDataItem, SubtaskConfig};
 use tuack_config::msgs::LoadContext;
 use crate::prelude::*;
 use crate::utils::filesystem::copy_dir_recursive;
@@ -82,22 +82,26 @@ pub struct GenArgs {
     pub target: Targets,
 }
 
-fn gen_contest(args: GenStatementArgs) -> Result<()> {
-    let current_dir = std::env::current_dir()?;
-
+/// 从 scaffold 创建竞赛文件夹，返回已写入名称的配置（尚未保存）
+pub(crate) fn create_contest(parent: &Path, contest_name: &str) -> Result<ContestConfig> {
     // 查找 scaffold/contest 目录（在程序上下文中的列表中第一个存在的）
     let scaffold_path = find_in_scaffold("contest", true)?;
 
-    for contest_name in &args.name {
-        copy_dir_recursive(&scaffold_path, current_dir.join(contest_name))?;
+    copy_dir_recursive(&scaffold_path, parent.join(contest_name))?;
 
-        let mut _ctx = LoadContext::new();
-        let mut contest_json: ContestConfig = ContestConfig::load(
-            &mut _ctx,
-            &current_dir.join(contest_name).join(CONFIG_FILE_NAME),
-        )?;
+    let mut _ctx = LoadContext::new();
+    let mut contest_json: ContestConfig =
+        ContestConfig::load(&mut _ctx, &parent.join(contest_name).join(CONFIG_FILE_NAME))?;
 
-        contest_json.name = contest_name.to_string();
+    contest_json.name = contest_name.to_string();
+    Ok(contest_json)
+}
+
+fn gen_contest(args: GenStatementArgs) -> Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    for contest_name in &args.name {
+        let contest_json = create_contest(&current_dir, contest_name)?;
 
         let updated_content = contest_json.save()?;
         std::fs::write(
@@ -108,41 +112,28 @@ fn gen_contest(args: GenStatementArgs) -> Result<()> {
     Ok(())
 }
 
-fn gen_day(args: GenStatementArgs) -> Result<()> {
-    // 检查是否在 contest 目录下执行
-    let current_dir = std::env::current_dir()?;
-    let config_path = current_dir.join(CONFIG_FILE_NAME);
+/// 从 scaffold 创建竞赛日文件夹，返回已写入名称的配置（尚未保存）
+pub(crate) fn create_day(contest_dir: &Path, day_name: &str) -> Result<ContestDayConfig> {
+    // 查找 scaffold/day 目录（在程序上下文中的列表中第一个存在的）
+    let scaffold_path = find_in_scaffold("day", true)?;
 
-    // 检查当前目录是否存在 contest 配置文件
-    if !config_path.exists() {
-        bail!("day 命令必须在 contest 目录下执行");
-    }
+    copy_dir_recursive(&scaffold_path, contest_dir.join(day_name))?;
 
-    // 检查配置文件是否为 contest 类型
-    let content = std::fs::read_to_string(&config_path)?;
-    let json_value: serde_json::Value = serde_json::from_str(&content)?;
+    let mut _ctx = LoadContext::new();
+    let mut day_json: ContestDayConfig =
+        ContestDayConfig::load(&mut _ctx, &contest_dir.join(day_name).join(CONFIG_FILE_NAME))?;
 
-    if let Some(folder) = json_value.get("folder").and_then(|v| v.as_str()) {
-        if folder != "contest" {
-            bail!("day 命令必须在 contest 目录下执行");
-        }
-    } else {
-        bail!("无效的配置文件");
-    }
+    day_json.name = day_name.to_string();
+    Ok(day_json)
+}
 
-    // 查找 scaffold/day 目录（在程序上下文中的列表中第一个存在的）
-    let scaffold_path = find_in_scaffold("day", true)?;
+fn gen_day(args: GenStatementArgs) -> Result<()> {
+    // 检查是否在 contest 目录下执行
+    let current_dir = std::env::current_dir()?;
+    let (config_path, content) = current_folder_config("contest", "day")?;
 
     for day_name in &args.name {
-        copy_dir_recursive(&scaffold_path, current_dir.join(day_name))?;
-
-        let mut _ctx = LoadContext::new();
-        let mut day_json: ContestDayConfig = ContestDayConfig::load(
-            &mut _ctx,
-            &current_dir.join(day_name).join(CONFIG_FILE_NAME),
-        )?;
-
-        day_json.name = day_name.to_string();
+        let day_json = create_day(&current_dir, day_name)?;
 
         let updated_content = day_json.save()?;
         std::fs::write(
@@ -152,56 +143,75 @@ fn gen_day(args: GenStatementArgs) -> Result<()> {
     }
 
     // 更新 contest 配置文件的 subdir 字段
-    let mut contest_config: serde_json::Value = serde_json::from_str(&content)?;
-    if let Some(subdir) = contest_config
-        .get_mut("subdir")
-        .and_then(|v| v.as_array_mut())
-    {
-        for day_name in &args.name {
-            subdir.push(serde_json::Value::String(day_name.clone()));
-        }
-    }
-
-    let updated_content = serde_json::to_string_pretty(&contest_config)?;
-    std::fs::write(&config_path, updated_content)?;
-    Ok(())
+    register_subdirs(&config_path, &content, &args.name)
 }
 
-fn gen_problem(args: GenStatementArgs) -> Result<()> {
-    // 检查是否在 day 目录下执行
+/// 检查当前目录是否为 `folder` 层级，返回配置文件路径与内容
+pub(crate) fn current_folder_config(folder: &str, command: &str) -> Result<(PathBuf, String)> {
     let current_dir = std::env::current_dir()?;
     let config_path = current_dir.join(CONFIG_FILE_NAME);
 
-    // 检查当前目录是否存在 day 配置文件
+    // 检查当前目录是否存在配置文件
     if !config_path.exists() {
-        bail!("problem 命令必须在 day 目录下执行");
+        bail!("{} 命令必须在 {} 目录下执行", command, folder);
     }
 
-    // 检查配置文件是否为 day 类型
+    // 检查配置文件层级
     let content = std::fs::read_to_string(&config_path)?;
     let json_value: serde_json::Value = serde_json::from_str(&content)?;
 
-    if let Some(folder) = json_value.get("folder").and_then(|v| v.as_str()) {
-        if folder != "day" {
-            bail!("problem 命令必须在 day 目录下执行");
+    if let Some(found) = json_value.get("folder").and_then(|v| v.as_str()) {
+        if found != folder {
+            bail!("{} 命令必须在 {} 目录下执行", command, folder);
         }
     } else {
         bail!("无效的配置文件");
     }
 
+    Ok((config_path, content))
+}
+
+/// 从 scaffold 创建题目文件夹，返回已写入题目名的配置（尚未保存）
+pub(crate) fn create_problem(day_dir: &Path, problem_name: &str) -> Result<ProblemConfig> {
     // 查找 scaffold/problem 目录（在程序上下文中的列表中第一个存在的）
     let scaffold_path = find_in_scaffold("problem", true)?;
 
-    for problem_name in &args.name {
-        copy_dir_recursive(&scaffold_path, current_dir.join(problem_name))?;
+    copy_dir_recursive(&scaffold_path, day_dir.join(problem_name))?;
 
-        let mut _ctx = LoadContext::new();
-        let mut problem_json: ProblemConfig = ProblemConfig::load(
-            &mut _ctx,
-            &current_dir.join(problem_name).join(CONFIG_FILE_NAME),
-        )?;
+    let mut _ctx = LoadContext::new();
+    let mut problem_json: ProblemConfig = ProblemConfig::load(
+        &mut _ctx,
+        &day_dir.join(problem_name).join(CONFIG_FILE_NAME),
+    )?;
 
-        problem_json.name = problem_name.to_string();
+    problem_json.name = problem_name.to_string();
+    Ok(problem_json)
+}
+
+/// 更新上级配置文件的 subdir 字段
+pub(crate) fn register_subdirs(config_path: &Path, content: &str, names: &[String]) -> Result<()> {
+    let mut parent_config: serde_json::Value = serde_json::from_str(content)?;
+    if let Some(subdir) = parent_config
+        .get_mut("subdir")
+        .and_then(|v| v.as_array_mut())
+    {
+        for name in names {
+            subdir.push(serde_json::Value::String(name.clone()));
+        }
+    }
+
+    let updated_content = serde_json::to_string_pretty(&parent_config)?;
+    std::fs::write(config_path, updated_content)?;
+    Ok(())
+}
+
+fn gen_problem(args: GenStatementArgs) -> Result<()> {
+    // 检查是否在 day 目录下执行
+    let current_dir = std::env::current_dir()?;
+    let (config_path, content) = current_folder_config("day", "problem")?;
+
+    for problem_name in &args.name {
+        let problem_json = create_problem(&current_dir, problem_name)?;
 
         let updated_content = problem_json.save()?;
         std::fs::write(
@@ -210,17 +220,7 @@ fn gen_problem(args: GenStatementArgs) -> Result<()> {
         )?;
     }
 
-    // 更新 day 配置文件的 subdir 字段
-    let mut day_config: serde_json::Value = serde_json::from_str(&content)?;
-    if let Some(subdir) = day_config.get_mut("subdir").and_then(|v| v.as_array_mut()) {
-        for problem_name in &args.name {
-            subdir.push(serde_json::Value::String(problem_name.clone()));
-        }
-    }
-
-    let updated_content = serde_json::to_string_pretty(&day_config)?;
-    std::fs::write(&config_path, updated_content)?;
-    Ok(())
+    register_subdirs(&config_path, &content, &args.name)
 }
 
 fn gen_lfs() -> Result<()> {
@@ -355,6 +355,9 @@ fn gen_data(args: GenConfirmArgs) -> Result<()> {
                         subtask: 0,
                         orig_args: IndexMap::new(),
                         dmk: None,
+                        command: None,
+                        time_limit: None,
+                        memory_limit: None,
                     })
                 })
                 .collect();
@@ -365,7 +368,8 @@ fn gen_data(args: GenConfirmArgs) -> Result<()> {
                 item.score += 100 % count;
             }
 
-            let subtasks: BTreeMap<u32, ScorePolicy> = BTreeMap::from([(0, ScorePolicy::Sum)]);
+            let subtasks: BTreeMap<u32, SubtaskConfig> =
+                BTreeMap::from([(0, ScorePolicy::Sum.into())]);
 
             let mut _ctx = LoadContext::new();
             let mut now_problem =
@@ -0,0 +1,173 @@
+use crate::generate::{
+    create_contest, create_day, create_problem, current_folder_config, register_subdirs,
+};
+use crate::prelude::*;
+use clap::Args;
+use clap::Subcommand;
+use tuack_config::CONFIG_FILE_NAME;
+use tuack_utils::import::polygon::PolygonImporter;
+use tuack_utils::import::tuack::{TuackFolder, TuackImporter};
+
+#[derive(Debug, Clone, Subcommand)]
+#[command(version)]
+#[command(infer_subcommands = false)]
+pub enum Sources {
+    /// 导入 Codeforces Polygon 题目包
+    #[command(version)]
+    Polygon(ImportPackageArgs),
+    /// 导入原 Tuack 工程（竞赛、竞赛日或题目）
+    #[command(version)]
+    Tuack(ImportTuackArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+#[command(version)]
+pub struct ImportPackageArgs {
+    /// 题目包路径（目录或 zip）
+    #[arg(required = true)]
+    path: PathBuf,
+    /// 题目英文名称，缺省使用题目包中的名称
+    #[arg(short, long)]
+    name: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+#[command(version)]
+pub struct ImportTuackArgs {
+    /// 原 Tuack 工程目录
+    #[arg(required = true)]
+    path: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+#[command(version)]
+pub struct ImportArgs {
+    /// 题目包格式
+    #[command(subcommand)]
+    pub source: Sources,
+}
+
+fn import_polygon(args: ImportPackageArgs) -> Result<()> {
+    let current_dir = std::env::current_dir()?;
+    let (config_path, content) = current_folder_config("day", "import")?;
+
+    let mut importer = PolygonImporter::open(&args.path)?;
+    let name = args
+        .name
+        .unwrap_or_else(|| importer.short_name().to_string());
+    if name.is_empty() {
+        bail!("题目包中没有题目名称，请使用 --name 指定");
+    }
+
+    let dest = current_dir.join(&name);
+    if dest.exists() {
+        bail!("目录 {} 已存在", dest.display());
+    }
+
+    let mut problem_config = create_problem(&current_dir, &name)?;
+    let warnings = match importer.import(&dest, &mut problem_config) {
+        Ok(warnings) => warnings,
+        Err(e) => {
+            msg_info!("导入失败，保留目录以供检查：{}", dest.display());
+            return Err(e);
+        }
+    };
+    for warning in &warnings {
+        msg_warn!("{}", warning);
+    }
+
+    fs::write(dest.join(CONFIG_FILE_NAME), problem_config.save()?)?;
+    register_subdirs(&config_path, &content, std::slice::from_ref(&name))?;
+
+    msg_info!("已导入题目 {}", name);
+    Ok(())
+}
+
+fn print_warnings(name: &str, warnings: &[String]) {
+    for warning in warnings {
+        msg_warn!("{}：{}", name, warning);
+    }
+}
+
+/// 导入原 Tuack 题目到 `day_dir`，返回题目目录名
+fn import_tuack_problem(day_dir: &Path, importer: &TuackImporter) -> Result<String> {
+    let name = importer.name();
+    let dest = day_dir.join(&name);
+    if dest.exists() {
+        bail!("目录 {} 已存在", dest.display());
+    }
+
+    let mut problem_config = create_problem(day_dir, &name)?;
+    let warnings = importer
+        .import_problem(&dest, &mut problem_config)
+        .with_context(|| format!("导入题目 {} 失败", name))?;
+    print_warnings(&name, &warnings);
+
+    fs::write(dest.join(CONFIG_FILE_NAME), problem_config.save()?)?;
+    msg_info!("已导入题目 {}", name);
+    Ok(name)
+}
+
+/// 导入原 Tuack 竞赛日及其题目到 `contest_dir`，返回竞赛日目录名
+fn import_tuack_day(contest_dir: &Path, importer: &TuackImporter) -> Result<String> {
+    let name = importer.name();
+    let dest = contest_dir.join(&name);
+    if dest.exists() {
+        bail!("目录 {} 已存在", dest.display());
+    }
+
+    let mut day_config = create_day(contest_dir, &name)?;
+    print_warnings(&name, &importer.import_day(&mut day_config));
+    for child in importer.children()? {
+        let problem = import_tuack_problem(&dest, &child)?;
+        day_config.subdir.push(problem);
+    }
+
+    fs::write(dest.join(CONFIG_FILE_NAME), day_config.save()?)?;
+    msg_info!("已导入竞赛日 {}", name);
+    Ok(name)
+}
+
+fn import_tuack(args: ImportTuackArgs) -> Result<()> {
+    let current_dir = std::env::current_dir()?;
+    let importer = TuackImporter::open(&args.path)?;
+
+    match importer.folder() {
+        TuackFolder::Problem => {
+            let (config_path, content) = current_folder_config("day", "import")?;
+            let name = import_tuack_problem(&current_dir, &importer)?;
+            register_subdirs(&config_path, &content, std::slice::from_ref(&name))?;
+        }
+        TuackFolder::Day => {
+            let (config_path, content) = current_folder_config("contest", "import")?;
+            let name = import_tuack_day(&current_dir, &importer)?;
+            register_subdirs(&config_path, &content, std::slice::from_ref(&name))?;
+        }
+        TuackFolder::Contest => {
+            let name = importer.name();
+            let dest = current_dir.join(&name);
+            if dest.exists() {
+                bail!("目录 {} 已存在", dest.display());
+            }
+
+            let mut contest_config = create_contest(&current_dir, &name)?;
+            print_warnings(&name, &importer.import_contest(&mut contest_config));
+            for child in importer.children()? {
+                let day = import_tuack_day(&dest, &child)?;
+                contest_config.subdir.push(day);
+            }
+
+            fs::write(dest.join(CONFIG_FILE_NAME), contest_config.save()?)?;
+            msg_info!("已导入竞赛 {}", name);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn main(args: ImportArgs) -> Result<()> {
+    match args.source {
+        Sources::Polygon(args) => import_polygon(args),
+        Sources::Tuack(args) => import_tuack(args),
+    }
+}
@@ -1,10 +1,13 @@
 use crate::conf::ConfArgs;
+use crate::data::DataArgs;
 use crate::dmk::DmkArgs;
 use crate::doc::DocArgs;
 use crate::dump::DumpArgs;
 use crate::generate::GenArgs;
+use crate::import::ImportArgs;
 use crate::prelude::*;
 use crate::ren::RenArgs;
+use crate::stress::StressArgs;
 use crate::test::TestArgs;
 use crate::validate::ValidateArgs;
 use clap::{Parser, Subcommand};
@@ -12,14 +15,17 @@ use clap_i18n_richformatter::clap_i18n;
 
 mod conf;
 mod context;
+mod data;
 mod develop;
 mod dmk;
 mod doc;
 mod dump;
 mod generate;
+mod import;
 mod init;
 mod prelude;
 mod ren;
+mod stress;
 mod test;
 mod utils;
 mod validate;
@@ -53,8 +59,14 @@ enum Commands {
     Dmk(DmkArgs),
     /// 校验输入数据
     Validate(ValidateArgs),
+    /// 数据工具
+    Data(DataArgs),
+    /// 随机生成数据与标程对拍
+    Stress(StressArgs),
     /// 导出到评测系统
     Dump(DumpArgs),
+    /// 从其他格式导入题目
+    Import(ImportArgs),
     /// 文档相关
     Doc(DocArgs),
     /// 开发工具
@@ -84,7 +96,10 @@ async fn tuack_ng(cli: Cli) -> Result<()> {
         Commands::Conf(args) => conf::main(args),
         Commands::Dmk(args) => dmk::main(args).await,
         Commands::Validate(args) => validate::main(args).await,
+        Commands::Data(args) => data::main(args),
+        Commands::Stress(args) => stress::main(args).await,
         Commands::Dump(args) => dump::main(args).await,
+        Commands::Import(args) => import::main(args),
         Commands::Doc(args) => doc::main(args),
         Commands::Develop(args) => develop::main(args),
     }
@@ -8,10 +8,13 @@ use opener::open;
 use std::time::Duration;
 use tuack_lib::ren::{
     DateInfo, Problem, ProblemMeta, ProblemType, RenConfig, RenderDocument, Renderer,
-    SupportLanguage,
+    SupportLanguage, Translation,
 };
+use tuack_config::DEFAULT_LANG;
+use tuack_ng_parser::ast::Document;
 use tuack_ng_parser::parse;
 use tuack_utils::assets::FsAssetProvider;
+use tuack_utils::ren::html::HtmlRenderer;
 use tuack_utils::ren::manifest::{TargetType, TemplateManifest};
 use tuack_utils::ren::markdown::MarkdownRenderer;
 use tuack_utils::ren::processors::process_ast;
@@ -33,6 +36,10 @@ pub struct RenArgs {
     /// 不自动打开渲染成果
     #[arg(short = 's')]
     pub no_auto_open: bool,
+
+    /// 渲染语言，可用逗号分隔多个（如 `zh-cn,en`），第一个为主语言，其余作为译文
+    #[arg(long = "lang", value_delimiter = ',')]
+    pub langs: Vec<String>,
 }
 
 /// 构造自洽渲染配置（day -> contest -> manifest 覆盖链合并）
@@ -40,6 +47,7 @@ fn build_ren_config(
     config: &ContestConfig,
     day_config: &ContestDayConfig,
     manifest: &TemplateManifest,
+    lang: &str,
 ) -> Result<RenConfig> {
     let date = if let (Some(start), Some(end)) = (day_config.start_time, day_config.end_time) {
         Some(DateInfo { start, end })
@@ -62,14 +70,15 @@ fn build_ren_config(
 
     let mut support_languages = Vec::new();
     for (lang_key, compile_options) in &day_config.compile {
-        let language_name = gctx()
+        let language = gctx()
             .languages
             .get(lang_key)
-            .map(|lang| lang.language.clone())
             .ok_or_else(|| anyhow!("在语言配置中未找到 {}", lang_key))?;
         support_languages.push(SupportLanguage {
-            name: language_name,
+            name: language.language.clone(),
             compile_options: compile_options.clone(),
+            time_multiplier: language.time_multiplier,
+            memory_multiplier: language.memory_multiplier,
         });
     }
 
@@ -78,10 +87,11 @@ fn build_ren_config(
     }
 
     Ok(RenConfig {
-        title: config.title.clone(),
+        lang: lang.to_string(),
+        title: config.localized_title(lang).to_string(),
         short_title: config.short_title.clone(),
         day_key: day_config.name.clone(),
-        dayname: day_config.title.clone(),
+        dayname: day_config.localized_title(lang).to_string(),
         date,
         use_pretest,
         noi_style,
@@ -91,7 +101,11 @@ fn build_ren_config(
 }
 
 /// 从 ProblemConfig 提取题目渲染元信息
-fn build_problem_meta(problem: &ProblemConfig, day_config: &ContestDayConfig) -> ProblemMeta {
+fn build_problem_meta(
+    problem: &ProblemConfig,
+    day_config: &ContestDayConfig,
+    lang: &str,
+) -> ProblemMeta {
     let submit_filenames = day_config
         .compile
         .keys()
@@ -107,28 +121,93 @@ fn build_problem_meta(problem: &ProblemConfig, day_config: &ContestDayConfig) ->
 
     ProblemMeta {
         name: problem.name.clone(),
-        title: problem.title.clone(),
+        title: problem.localized_title(lang).to_string(),
         problem_type: match problem.problem_type {
             tuack_config::ProblemType::Program => ProblemType::Program,
             tuack_config::ProblemType::Output => ProblemType::Output,
             tuack_config::ProblemType::Interactive => ProblemType::Interactive,
+            tuack_config::ProblemType::Communication => ProblemType::Communication,
         },
         time_limit: Duration::from_secs_f64(problem.time_limit),
         memory_limit: problem.memory_limit,
+        max_time_limit: Duration::from_secs_f64(
+            problem
+                .runtime
+                .data
+                .iter()
+                .map(|item| item.time_limit)
+                .fold(problem.time_limit, f64::max),
+        ),
+        max_memory_limit: problem
+            .runtime
+            .data
+            .iter()
+            .map(|item| item.memory_limit)
+            .fold(problem.memory_limit, Ord::max),
         testcase: problem.runtime.data.len(),
+        pretestcase: problem.runtime.pretest.len(),
         point_equal,
         submit_filename: submit_filenames,
     }
 }
 
+/// 读取 `lang` 语言的题面：移除注释 -> 模板展开 -> 解析 -> 处理器，模板警告直接输出。
+pub(crate) fn load_statement(
+    config: &ContestConfig,
+    manifest: &TemplateManifest,
+    day_config: &ContestDayConfig,
+    problem_config: &ProblemConfig,
+    lang: &str,
+) -> Result<Document> {
+    let statement_path = problem_config.statement_path(lang);
+    if !statement_path.exists() {
+        bail!("未找到题面文件：{}", statement_path.display());
+    }
+
+    // 解析题面同时展开模板，移除注释
+    let re = regex::Regex::new(r"<!--[\s\S]*?-->").unwrap();
+    let (content, warnings) = render_template(
+        re.replace_all(&fs::read_to_string(&statement_path)?, "")
+            .as_ref(),
+        problem_config,
+        day_config,
+        config,
+        problem_config.path.clone(),
+        manifest.clone(),
+        lang,
+    )
+    .with_context(|| format!("读取题面文件/展开模板失败：{}", statement_path.display()))?;
+
+    if !warnings.is_empty() {
+        let joined = warnings
+            .iter()
+            .map(|w| format!("  {}", w))
+            .collect::<Vec<_>>()
+            .join("\n");
+        msg_warn!(
+            "在解析题目 {} 时产生了警告：\n{}",
+            problem_config.name.magenta(),
+            joined
+        );
+    }
+
+    let mut ast = parse(&content);
+    process_ast(&mut ast, &manifest.processor)
+}
+
 /// 构造一天的可渲染文档：读题面 -> 模板展开 -> 解析 -> 处理器 -> 图片扫描登记。
+///
+/// `langs` 的第一个为主语言，其余语言的题面作为译文，缺失时跳过并警告。
 fn build_render_document(
     config: &ContestConfig,
     manifest: &TemplateManifest,
     day_config: &ContestDayConfig,
     problem: Option<String>,
+    langs: &[String],
     problem_pb: &ProgressBar,
 ) -> Result<RenderDocument> {
+    let (lang, translation_langs) = langs.split_first().context("没有指定渲染语言")?;
+
     let problems_to_render: IndexMap<String, &ProblemConfig> = match problem {
         Some(ref problem_key) => day_config
             .subconfig
@@ -161,7 +240,6 @@ fn build_render_document(
         day_config.clone()
     };
 
-    let re = regex::Regex::new(r"<!--[\s\S]*?-->").unwrap();
     let mut assets = FsAssetProvider::new();
     let mut problems = Vec::new();
 
@@ -169,53 +247,41 @@ fn build_render_document(
         problem_pb.set_message(format!("处理问题：{}", problem_config.name));
         info!("处理问题：{}", problem_config.name);
 
-        let problem_dir = &problem_config.path;
-        let statement_path = problem_dir.join("statement.md");
-        if !statement_path.exists() {
-            bail!("未找到题面文件：{}", statement_path.display());
-        }
-
-        // 解析题面同时展开模板，移除注释
-        let (content, warnings) = render_template(
-            re.replace_all(&fs::read_to_string(&statement_path)?, "")
-                .as_ref(),
-            problem_config,
-            &day_to_render,
-            config,
-            problem_config.path.clone(),
-            manifest.clone(),
-        )
-        .with_context(|| format!("读取题面文件/展开模板失败：{}", statement_path.display()))?;
-
-        if !warnings.is_empty() {
-            let joined = warnings
-                .iter()
-                .map(|w| format!("  {}", w))
-                .collect::<Vec<_>>()
-                .join("\n");
-            msg_warn!(
-                "在解析题目 {} 时产生了警告：\n{}",
-                problem_config.name.magenta(),
-                joined
-            );
+        let ast = load_statement(config, manifest, &day_to_render, problem_config, lang)?;
+
+        let mut translations = Vec::new();
+        for other in translation_langs {
+            if !problem_config.statement_path(other).exists() {
+                msg_warn!(
+                    "题目 {} 没有 {} 语言的题面，跳过该译文",
+                    problem_config.name.magenta(),
+                    other
+                );
+                continue;
+            }
+            translations.push(Translation {
+                lang: other.clone(),
+                title: problem_config.localized_title(other).to_string(),
+                ast: load_statement(config, manifest, &day_to_render, problem_config, other)?,
+            });
         }
 
-        let mut ast = parse(&content);
-        ast = process_ast(&mut ast, &manifest.processor)?;
-
         assets.register(idx as u64, problem_config.path.clone());
 
         problems.push(Problem {
             idx: idx as u64,
-            meta: build_problem_meta(problem_config, day_config),
+            meta: build_problem_meta(problem_config, day_config, lang),
             ast,
+            translations,
         });
 
         problem_pb.inc(1);
     }
 
-    // 处理注意事项文件
-    let precaution_path = config.path.join("precaution.md");
+    // 处理注意事项文件，优先使用主语言版本 `precaution.<lang>.md`
+    let precaution_path = Some(config.path.join(format!("precaution.{}.md", lang)))
+        .filter(|path| lang != DEFAULT_LANG && path.exists())
+        .unwrap_or_else(|| config.path.join("precaution.md"));
     if !precaution_path.exists() {
         bail!("未找到注意事项文件：{}", precaution_path.display());
     }
@@ -225,7 +291,7 @@ fn build_render_document(
     }
     info!("处理注意事项文件：{}", precaution_path.display());
 
-    let config = build_ren_config(config, day_config, manifest)?;
+    let config = build_ren_config(config, day_config, manifest, lang)?;
 
     Ok(RenderDocument {
         config,
@@ -264,7 +330,14 @@ async fn ren(
             .progress_chars("=> "),
     );
 
-    let doc = match build_render_document(config, manifest, day_config, problem, &problem_pb) {
+    let doc = match build_render_document(
+        config,
+        manifest,
+        day_config,
+        problem,
+        &args.langs,
+        &problem_pb,
+    ) {
         Ok(doc) => doc,
         Err(e) => {
             problem_pb.finish_with_message("遇到错误，停止处理");
@@ -285,6 +358,11 @@ async fn ren(
             &gctx().assets_dirs,
         )?),
         TargetType::Markdown => Box::new(MarkdownRenderer::new()),
+        TargetType::Html => Box::new(HtmlRenderer::new(
+            tmp_dir.clone(),
+            manifest,
+            &gctx().assets_dirs,
+        )?),
     };
 
     let render_result = renderer.render(&doc).await;
@@ -357,6 +435,16 @@ pub async fn main(args: RenArgs) -> Result<()> {
 
     let manifest = serde_json::from_str::<TemplateManifest>(&fs::read_to_string(&manifest_file)?)?;
 
+    // 渲染语言：命令行 > 模板清单 > 缺省语言
+    let mut args = args;
+    if args.langs.is_empty() {
+        args.langs = if manifest.languages.is_empty() {
+            vec![DEFAULT_LANG.to_string()]
+        } else {
+            manifest.languages.clone()
+        };
+    }
+
     let statements_dir = match current_location {
         CurrentLocation::Problem(day_name, problem_name) => Path::new(&config.path)
             .join(day_name)
@@ -372,7 +460,12 @@ pub async fn main(args: RenArgs) -> Result<()> {
         fs::create_dir(&statements_dir)?;
     }
 
-    let statements_dir = statements_dir.join(&args.target);
+    // 非缺省主语言输出到 `<target>.<lang>`，避免覆盖缺省语言的成果
+    let statements_dir = if args.langs[0] == DEFAULT_LANG {
+        statements_dir.join(&args.target)
+    } else {
+        statements_dir.join(format!("{}.{}", args.target, args.langs[0]))
+    };
     if !statements_dir.exists() {
         info!(
             "创建 {} 目标输出目录：{}",
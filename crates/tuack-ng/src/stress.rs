@@ -0,0 +1,410 @@
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use indicatif::ProgressBar;
+use owo_colors::OwoColorize;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tempfile::TempDir;
+
+use crate::dmk::{build_std_runner, compile_generator, find_std, generator_deps};
+use crate::prelude::*;
+use crate::test::{
+    DisplayStatus, build_runner, language_multipliers, prepare_checker, status_color,
+};
+use crate::utils::random::gen_rnd;
+use crate::validate::{Target, compile_validator};
+use tuack_config::ExpandedDataItem;
+use tuack_lib::dmk::{DmkParams, DmkSession};
+use tuack_lib::test::{TaskParams, TestCaseStatus, TestSession};
+use tuack_lib::utils::compiler::ExecBackend;
+use tuack_lib::utils::testlib::{Arg, Validator};
+use tuack_utils::data::FsTestData;
+
+/// 未指定轮数与时间时的默认轮数
+const DEFAULT_ITERATIONS: u64 = 1000;
+
+#[derive(Args, Debug)]
+#[command(version)]
+pub struct StressArgs {
+    /// 参与对拍的测试者，缺省为除标程外的所有测试者
+    pub testers: Vec<String>,
+
+    /// 最多对拍的轮数，与 `--time` 均未指定时为 1000 轮
+    #[arg(short = 'n', long)]
+    pub iterations: Option<u64>,
+
+    /// 最长对拍时间（秒）
+    #[arg(short, long)]
+    pub time: Option<f64>,
+
+    /// 生成器参数的取值范围，可多次指定：`n=1..100`、`p=0.1..0.9`、`type=chain|star`、`k=5`
+    #[arg(short, long = "arg", value_name = "KEY=RANGE")]
+    pub args: Vec<ArgRange>,
+
+    /// 对拍使用的随机种子，指定后每轮的输入可以复现
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// 在 Linux 沙箱中运行测试者（cgroup v2 + namespace + seccomp），按 cgroup 统计的 CPU 时间判定超时，不受 time mode 影响
+    #[arg(long)]
+    pub sandbox: bool,
+}
+
+/// 生成器参数的取值方式
+#[derive(Debug, Clone)]
+pub enum ArgValues {
+    /// 闭区间内均匀随机的整数
+    Integer(i64, i64),
+    /// 区间内均匀随机的浮点数
+    Float(f64, f64),
+    /// 若干候选值中等概率选取
+    Choice(Vec<Arg>),
+}
+
+/// 命令行给出的单个生成器参数范围，形如 `key=range`
+#[derive(Debug, Clone)]
+pub struct ArgRange {
+    pub key: String,
+    pub values: ArgValues,
+}
+
+/// 按整数、浮点数、布尔值、字符串的顺序解析单个参数值
+fn parse_value(value: &str) -> Arg {
+    if let Ok(v) = value.parse() {
+        Arg::Integer(v)
+    } else if let Ok(v) = value.parse() {
+        Arg::Float(v)
+    } else if let Ok(v) = value.parse() {
+        Arg::Bool(v)
+    } else {
+        Arg::Str(value.to_string())
+    }
+}
+
+impl FromStr for ArgRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (key, range) = s.split_once('=').context("参数范围应写作 key=range")?;
+        if key.is_empty() {
+            bail!("参数名为空");
+        }
+        let values = match range.split_once("..") {
+            Some((lo, hi)) => match (lo.parse::<i64>(), hi.parse::<i64>()) {
+                (Ok(lo), Ok(hi)) => {
+                    if lo > hi {
+                        bail!("参数 {} 的范围下界大于上界", key);
+                    }
+                    ArgValues::Integer(lo, hi)
+                }
+                _ => {
+                    let lo: f64 = lo
+                        .parse()
+                        .with_context(|| format!("参数 {} 的范围无效：{}", key, range))?;
+                    let hi: f64 = hi
+                        .parse()
+                        .with_context(|| format!("参数 {} 的范围无效：{}", key, range))?;
+                    if lo.is_nan() || hi.is_nan() || lo > hi {
+                        bail!("参数 {} 的范围下界大于上界", key);
+                    }
+                    ArgValues::Float(lo, hi)
+                }
+            },
+            None => ArgValues::Choice(range.split('|').map(parse_value).collect()),
+        };
+        Ok(ArgRange {
+            key: key.to_string(),
+            values,
+        })
+    }
+}
+
+impl ArgRange {
+    fn sample(&self, rng: &mut StdRng) -> Arg {
+        match &self.values {
+            ArgValues::Integer(lo, hi) => Arg::Integer(rng.random_range(*lo..=*hi)),
+            ArgValues::Float(lo, hi) if lo == hi => Arg::Float(*lo),
+            ArgValues::Float(lo, hi) => Arg::Float(rng.random_range(*lo..*hi)),
+            ArgValues::Choice(choices) => choices[rng.random_range(0..choices.len())].clone(),
+        }
+    }
+}
+
+/// 对拍失败时保存的现场
+#[derive(Debug, Serialize)]
+struct FailureRecord<'a> {
+    /// 对拍的随机种子
+    stress_seed: u64,
+    /// 轮次，从 1 开始
+    round: u64,
+    /// 生成器的种子
+    seed: u64,
+    args: &'a IndexMap<String, Arg>,
+    tester: &'a str,
+    status: Option<&'a TestCaseStatus>,
+    message: Option<String>,
+}
+
+/// 把失败的输入（及答案）保存到题目的 `stress/` 目录，返回输入文件的路径
+fn save_failure(
+    problem: &ProblemConfig,
+    data: &FsTestData,
+    record: &FailureRecord,
+) -> Result<PathBuf> {
+    let dir = problem.path.join("stress");
+    fs::create_dir_all(&dir)?;
+    let id = (1..)
+        .find(|k| !dir.join(format!("{}.in", k)).exists())
+        .unwrap();
+
+    let input = dir.join(format!("{}.in", id));
+    fs::copy(data.input_path(), &input)?;
+    if data.output_path().exists() {
+        fs::copy(data.output_path(), dir.join(format!("{}.ans", id)))?;
+    }
+    fs::write(
+        dir.join(format!("{}.json", id)),
+        serde_json::to_string_pretty(record)?,
+    )?;
+    Ok(input)
+}
+
+pub async fn main(args: StressArgs) -> Result<()> {
+    let config = gctx().config.as_ref().context("没有找到有效的工程")?;
+
+    let (problem, day) = if let CurrentLocation::Problem(ref day, ref prog) = config.location {
+        let day_config = config
+            .config
+            .subconfig
+            .get(day)
+            .context(format!("无法获取天配置：{}", day))?;
+        let problem_config = day_config
+            .subconfig
+            .get(prog)
+            .context(format!("无法获取题目配置：{}/{}", day, prog))?;
+        (problem_config, day_config)
+    } else {
+        bail!("本命令只能在题目目录下执行");
+    };
+
+    if problem.problem_type != ProblemType::Program {
+        bail!("对拍只支持传统题");
+    }
+    if args.time.is_some_and(|t| t.is_nan() || t <= 0.0) {
+        bail!("对拍时间应为正数");
+    }
+
+    // 标程与测试者
+    let std_path = find_std(problem)?;
+    let std_name = problem
+        .tests
+        .iter()
+        .find(|(_, case)| problem.path.join(&case.path) == std_path)
+        .map(|(name, _)| name.clone());
+    let testers: Vec<(&String, &TestCase)> = if args.testers.is_empty() {
+        problem
+            .tests
+            .iter()
+            .filter(|(name, _)| Some(*name) != std_name.as_ref())
+            .collect()
+    } else {
+        args.testers
+            .iter()
+            .map(|name| {
+                problem
+                    .tests
+                    .get_key_value(name)
+                    .with_context(|| format!("找不到测试者 {}", name))
+            })
+            .collect::<Result<_>>()?
+    };
+    if testers.is_empty() {
+        bail!("没有参与对拍的测试者");
+    }
+
+    let generator_config = &problem.generator.as_ref().context("generator 未配置")?.data;
+    let generator = compile_generator(
+        &problem.path.join(&generator_config.source),
+        day,
+        generator_deps(problem, generator_config)?,
+    )?;
+    let validator: Option<Box<dyn Validator>> = if generator_config.validate {
+        Some(
+            compile_validator(problem, Target::Data)
+                .with_context(|| format!("题目 {} 的 Validator 不可用", problem.name))?,
+        )
+    } else {
+        None
+    };
+    let checker = prepare_checker(problem, false)?
+        .with_context(|| format!("题目 {} 的 Checker 不可用", problem.name))?;
+
+    let compile_pb = gctx().multiprogress.add(ProgressBar::new_spinner());
+    compile_pb.enable_steady_tick(Duration::from_millis(100));
+    compile_pb.set_message("编译标程");
+    let mut std_runner = build_std_runner(&std_path, day, problem)?;
+    let std_result = std_runner.prepare_async().await;
+    compile_pb.finish_and_clear();
+    std_result?;
+
+    let mut runners = Vec::with_capacity(testers.len());
+    for (name, test) in &testers {
+        let mut runner = build_runner(day, problem, test, false, None)?;
+        if args.sandbox {
+            runner.set_backend(ExecBackend::Sandbox);
+        }
+        let compile_pb = gctx().multiprogress.add(ProgressBar::new_spinner());
+        compile_pb.enable_steady_tick(Duration::from_millis(100));
+        compile_pb.set_message(format!("编译测试 {}", name));
+        let prepared = runner.prepare();
+        compile_pb.finish_and_clear();
+        prepared.with_context(|| format!("测试 {} 编译失败", name))?;
+        runners.push(runner);
+    }
+
+    let time_limit = Duration::from_secs_f64(problem.time_limit);
+    let params = TaskParams {
+        problem_name: problem.name.clone(),
+        time_limit,
+        memory_limit: problem.memory_limit,
+        time_mode: problem.time_mode,
+        wall_time_limit: match problem.wall_time_limit {
+            Some(limit) => Duration::from_secs_f64(limit),
+            None => ResourceLimits::default_wall_time_limit(time_limit),
+        },
+        file_io: problem.file_io.unwrap_or(true),
+        interactor: false,
+        stages: Vec::new(),
+    };
+    let dmk_params = DmkParams {
+        problem_name: problem.name.clone(),
+        file_io: problem.file_io.unwrap_or(true),
+    };
+
+    let stress_seed = match args.seed {
+        Some(seed) => seed,
+        None => gen_rnd()?.random(),
+    };
+    let mut rng = StdRng::seed_from_u64(stress_seed);
+    msg_info!("对拍随机种子 {}", stress_seed.to_string().cyan());
+
+    let iterations = match (args.iterations, args.time) {
+        (None, None) => Some(DEFAULT_ITERATIONS),
+        (iterations, _) => iterations,
+    };
+    let deadline = args
+        .time
+        .map(|t| Instant::now() + Duration::from_secs_f64(t));
+
+    let tmp_dir = TempDir::with_prefix("tuack-ng-stress-")?;
+    let pb = gctx().multiprogress.add(ProgressBar::new_spinner());
+    pb.enable_steady_tick(Duration::from_millis(100));
+
+    let mut round = 0;
+    loop {
+        if iterations.is_some_and(|n| round >= n)
+            || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            break;
+        }
+        round += 1;
+        pb.set_message(format!("第 {} 轮对拍", round));
+
+        let mut item_args = problem.args.clone();
+        for range in &args.args {
+            item_args.insert(range.key.clone(), range.sample(&mut rng));
+        }
+        let seed: u64 = rng.random();
+        let item = ExpandedDataItem {
+            id: 1,
+            score: 100,
+            subtask: 0,
+            input: "stress.in".to_string(),
+            output: "stress.ans".to_string(),
+            orig_args: IndexMap::new(),
+            args: item_args,
+            dmk: DmkConfig::On,
+            command: None,
+            time_limit: problem.time_limit,
+            memory_limit: problem.memory_limit,
+        };
+        let data = FsTestData::from_data(tmp_dir.path().to_path_buf(), &item);
+
+        let mut session = DmkSession::new(
+            std_runner.as_mut(),
+            generator.as_ref(),
+            validator.as_deref(),
+            dmk_params.clone(),
+        );
+        session
+            .gen_input(&data, seed)
+            .await
+            .with_context(|| format!("第 {} 轮生成输入失败（种子 {}）", round, seed))?;
+        if let Err(e) = session.gen_output(&data).await {
+            pb.finish_and_clear();
+            let record = FailureRecord {
+                stress_seed,
+                round,
+                seed,
+                args: &item.args,
+                tester: std_name.as_deref().unwrap_or("std"),
+                status: None,
+                message: Some(format!("{:#}", e)),
+            };
+            let saved = save_failure(problem, &data, &record)?;
+            msg_error!(
+                "第 {} 轮标程运行失败，输入已保存到 {}",
+                round,
+                saved.display()
+            );
+            return Err(e);
+        }
+
+        for ((name, test), runner) in testers.iter().zip(runners.iter_mut()) {
+            let (time_multiplier, memory_multiplier) = language_multipliers(test);
+            let params = params.clone().scaled(time_multiplier, memory_multiplier);
+            let result = TestSession::new(runner.as_mut(), checker.as_ref(), params)
+                .judge(&data)
+                .await?;
+            if matches!(result.status, TestCaseStatus::AC) {
+                continue;
+            }
+
+            pb.finish_and_clear();
+            let record = FailureRecord {
+                stress_seed,
+                round,
+                seed,
+                args: &item.args,
+                tester: name,
+                status: Some(&result.status),
+                message: result.message.clone(),
+            };
+            let saved = save_failure(problem, &data, &record)?;
+            msg_item!(
+                status_color(&DisplayStatus::from(&result.status)).bold(),
+                "测试 {} 在第 {} 轮出错",
+                name.cyan(),
+                round
+            );
+            if let Some(message) = &result.message {
+                msg_info!("{}", message);
+            }
+            let item_args = serde_json::to_string(&item.args)?;
+            msg_info!("生成器种子 {}，参数 {}", seed, item_args);
+            msg_info!("输入已保存到 {}", saved.display());
+            bail!("对拍发现不一致");
+        }
+    }
+
+    pb.finish_and_clear();
+    msg_info!(
+        "{}",
+        format!("对拍 {} 轮，所有测试者均与标程一致", round)
+            .green()
+            .bold()
+    );
+    Ok(())
+}
@@ -1,30 +1,51 @@
 use std::str::FromStr;
+use std::sync::Mutex;
 use std::time::Duration;
 
 use clap::{Args, ValueEnum};
-use csv::Writer;
-use evalexpr::eval_boolean;
+use futures::stream::{self, StreamExt};
 use indicatif::ProgressBar;
 use owo_colors::OwoColorize;
 
+pub mod cache;
+pub mod expect;
 pub mod policy;
+pub mod report;
+pub mod summary;
 
-use tuack_utils::data::FsTestData;
+use tuack_config::InteractiveMode;
+use tuack_utils::data::{AnswerSet, FsTestData};
 use crate::prelude::*;
-use crate::test::policy::{DataPolicy, SamplePolicy, ScorePolicy as _};
-use tuack_lib::test::{TaskParams, TestCaseStatus, TestSession};
-use tuack_lib::utils::testlib::Checker;
-use tuack_utils::checkers::{cpp::CppChecker, prebuilt::PrebuiltChecker};
+use crate::test::cache::{Fingerprint, TestCache, toolchain_version};
+use crate::test::expect::{CaseOutcome, check_expected};
+use crate::test::policy::{DataPolicy, PretestPolicy, SamplePolicy, ScorePolicy as _, ScoreReport};
+use crate::test::report::{
+    ExpectedOutcome, IndividualTestCaseResult, ProblemReport, ProblemTestResult, ReportFormat,
+    write_report,
+};
+use crate::test::summary::TestOutcome;
+use tuack_lib::test::{
+    StageParams, TaskParams, TestCaseResult, TestCaseStatus, TestSession, judge_output,
+};
+use tuack_lib::utils::compiler::{ExecBackend, Interactor};
+use tuack_lib::utils::testlib::{Checker, Manager};
+use tuack_utils::checkers::{
+    cpp::CppChecker, general::GeneralChecker, helper::checker_limits, interactor::CppInteractor,
+    manager::CppManager, prebuilt::PrebuiltChecker,
+};
 use tuack_utils::compilers::cpp::CppRunner;
 use tuack_utils::compilers::general::*;
 use crate::utils::duration::format_duration;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Target {
     /// 正式测试数据
     Data,
     /// 样例数据
     Sample,
+    /// 预测试数据
+    Pretest,
 }
 
 /// 前端展示状态 (评测状态 + 编译失败)
@@ -42,6 +63,29 @@ pub(crate) enum DisplayStatus {
     CE,
 }
 
+impl DisplayStatus {
+    /// 状态的简写，部分分不含比例
+    pub fn code(&self) -> &'static str {
+        match self {
+            DisplayStatus::AC => "AC",
+            DisplayStatus::WA => "WA",
+            DisplayStatus::RE => "RE",
+            DisplayStatus::TLE => "TLE",
+            DisplayStatus::MLE => "MLE",
+            DisplayStatus::UKE => "UKE",
+            DisplayStatus::FE => "FE",
+            DisplayStatus::PC(_) => "PC",
+            DisplayStatus::CE => "CE",
+        }
+    }
+}
+
+impl Serialize for DisplayStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
 impl From<&TestCaseStatus> for DisplayStatus {
     fn from(status: &TestCaseStatus) -> Self {
         match status {
@@ -57,37 +101,55 @@ impl From<&TestCaseStatus> for DisplayStatus {
     }
 }
 
-// 记录测试用例结果
-#[derive(Debug)]
-pub struct IndividualTestCaseResult {
-    pub test_case_id: u32,
-    pub status: DisplayStatus,
-    pub score: u32,
-    pub full_score: u32,
-    pub time: String,
-    pub memory: String,
-    /// 校验器诊断信息（完整内容，用于 CSV）
-    pub message: Option<String>,
-}
-
-// 记录题目测试结果
-#[derive(Debug)]
-pub struct ProblemTestResult {
-    pub tester_name: String,
-    pub test_case_results: Vec<IndividualTestCaseResult>,
-    pub total_score: u32,
-    pub full_score: u32,
-}
-
 #[derive(Args, Debug)]
 #[command(version)]
 pub struct TestArgs {
     /// 目标类型
     #[arg(value_enum, default_value = "data")]
     pub target: Target,
+
+    /// 并行评测的测试点数
+    #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub jobs: u32,
+
+    /// 在 Linux 沙箱中运行测试者（cgroup v2 + namespace + seccomp），按 cgroup 统计的 CPU 时间判定超时，不受 time mode 影响
+    #[arg(long)]
+    pub sandbox: bool,
+
+    /// 额外生成的测试报告格式，未指定输出路径时写入题目目录
+    #[arg(long, value_enum)]
+    pub report: Option<ReportFormat>,
+
+    /// 测试报告的输出路径，包含本次测试的所有题目
+    #[arg(long, requires = "report")]
+    pub output: Option<PathBuf>,
+
+    /// 某道题目测试出错时继续测试其余题目，最后一并汇总
+    #[arg(long, conflicts_with = "fail_fast")]
+    pub keep_going: bool,
+
+    /// 遇到第一个编译错误或不满足期望的测试者时立即停止
+    #[arg(long)]
+    pub fail_fast: bool,
+
+    /// 不使用 `.tuack-cache/` 中缓存的评测结果，全部重新评测
+    #[arg(long)]
+    pub no_cache: bool,
+}
+
+/// 测试者的编译结果
+enum PreparedTester {
+    /// 编译成功，每个并行任务各持有一个运行器
+    Ready(Mutex<Vec<Box<dyn Runner>>>),
+    /// 提交答案题：选手的输出文件
+    Answers(AnswerSet),
+    /// 所有测试点均已读出缓存结果，无需编译；每个结果评测时取走
+    Cached(Mutex<Vec<Option<TestCaseResult>>>),
+    /// 编译失败（提交答案题为读取失败）
+    CompileError(anyhow::Error),
 }
 
-fn status_color(status: &DisplayStatus) -> String {
+pub(crate) fn status_color(status: &DisplayStatus) -> String {
     match status {
         DisplayStatus::AC => "AC".green().to_string(),
         DisplayStatus::WA => "WA".red().to_string(),
@@ -101,93 +163,221 @@ fn status_color(status: &DisplayStatus) -> String {
     }
 }
 
-fn check_test_case(test_case: &TestCase, actual_score: u32) -> bool {
-    let conditions = match &test_case.expected {
-        ExpectedScore::Single(cond) => vec![cond.clone()],
-        ExpectedScore::Multiple(conds) => conds.clone(),
-    };
+/// 输出期望条件的判定结果
+fn show_expected(test_name: &str, expected: &ExpectedOutcome) {
+    if expected.passed {
+        info!("测试 {} 通过", test_name);
+        return;
+    }
+    info!("测试 {} 不满足所有条件", test_name);
+    msg_warn!("{}", "不满足所有条件".bold());
+    for failure in &expected.failures {
+        msg_warn!("  {}", failure);
+    }
+}
 
-    for condition in &conditions {
-        let expr = format!("{} {}", actual_score, condition);
+/// 测试者语言的时间、空间限制倍率，未知语言不放宽
+pub fn language_multipliers(test: &TestCase) -> (f64, f64) {
+    Path::new(&test.path)
+        .extension()
+        .and_then(|ext| gctx().languages.get(ext.to_string_lossy().as_ref()))
+        .map_or((1.0, 1.0), |lang| {
+            (lang.time_multiplier, lang.memory_multiplier)
+        })
+}
 
-        debug!("条件：{}", expr);
+/// 时间限制为 `time_limit` 时的墙钟时间上限。
+///
+/// 题目设置了墙钟时间上限时按其与题目时间限制的比例放宽，否则取默认上限，
+/// 保证测试点或 subtask 单独放宽时间限制后墙钟时间上限不会低于时间限制。
+fn wall_time_limit(problem_config: &ProblemConfig, time_limit: Duration) -> Duration {
+    scale_wall_time_limit(
+        problem_config.wall_time_limit,
+        problem_config.time_limit,
+        time_limit,
+    )
+}
 
-        if !eval_boolean(&expr).unwrap_or(false) {
-            return false;
-        }
+fn scale_wall_time_limit(
+    wall_time_limit: Option<f64>,
+    problem_time_limit: f64,
+    time_limit: Duration,
+) -> Duration {
+    match wall_time_limit {
+        Some(limit) if problem_time_limit > 0.0 => time_limit.mul_f64(limit / problem_time_limit),
+        Some(limit) => Duration::from_secs_f64(limit),
+        None => ResourceLimits::default_wall_time_limit(time_limit),
     }
+}
 
-    true
+/// 单个测试点的评测参数：测试点单独的限制优先，再按测试者语言的倍率放宽
+fn case_params(
+    base: &TaskParams,
+    problem_config: &ProblemConfig,
+    data: &FsTestData,
+    (time_multiplier, memory_multiplier): (f64, f64),
+) -> TaskParams {
+    let mut params = base.clone();
+    if let Some(time_limit) = data.time_limit() {
+        params.time_limit = Duration::from_secs_f64(time_limit);
+        params.wall_time_limit = wall_time_limit(problem_config, params.time_limit);
+    }
+    if let Some(memory_limit) = data.memory_limit() {
+        params.memory_limit = memory_limit;
+    }
+    params.scaled(time_multiplier, memory_multiplier)
 }
 
-// 将测试结果写入 CSV
-fn write_results_to_csv(results: Vec<ProblemTestResult>, csv_path: &Path) -> Result<()> {
-    let mut wtr = Writer::from_path(csv_path)?;
-
-    wtr.write_record([
-        "测试者",
-        "测试点 ID",
-        "状态",
-        "得分",
-        "满分",
-        "时间",
-        "空间",
-        "信息",
-    ])?;
-
-    // 写入所有测试者的结果
-    for result in &results {
-        // 写入每个测试用例的结果
-        for test_case_result in &result.test_case_results {
-            let message = test_case_result
-                .message
-                .as_deref()
-                .unwrap_or("")
-                .trim()
-                .replace('\r', "\\r")
-                .replace('\n', "\\n");
-            wtr.write_record(&[
-                result.tester_name.clone(),
-                test_case_result.test_case_id.to_string(),
-                format!("{:?}", test_case_result.status),
-                test_case_result.score.to_string(),
-                test_case_result.full_score.to_string(),
-                test_case_result.time.clone(),
-                test_case_result.memory.clone(),
-                message,
-            ])?;
+/// 题目中影响所有测试者评测结果的内容：Checker、交互器、grader、manager 及其配置
+fn problem_fingerprint(problem_config: &ProblemConfig, is_sample: bool) -> Result<Fingerprint> {
+    let mut fp = Fingerprint::new();
+    let resolve = |path: &str| problem_config.path.join(path);
+    fp.bytes(format!("{:?}", problem_config.problem_type));
+    // checker、交互器与 manager 均由 g++ 编译
+    fp.bytes(toolchain_version("cpp"));
+
+    fp.bytes(format!("{:?}", problem_config.checker));
+    if let Some(pair) = &problem_config.checker {
+        let chk_config = if is_sample {
+            pair.sample.as_ref().unwrap_or(&pair.data)
+        } else {
+            &pair.data
+        };
+        fp.file(&resolve(&chk_config.source))?;
+        for dep in &chk_config.deps {
+            fp.file(&resolve(dep))?;
         }
+    }
 
-        // 给这个测试者写入总分
-        wtr.write_record(&[
-            result.tester_name.clone(),
-            "".to_string(),                 // 测试点 ID
-            "TOTAL".to_string(),            // 状态
-            result.total_score.to_string(), // 得分
-            result.full_score.to_string(),  // 满分
-            "".to_string(),
-            "".to_string(),
-            "".to_string(),
-        ])?;
+    fp.bytes(format!("{:?}", problem_config.interactive));
+    if let Some(interactive) = &problem_config.interactive {
+        let grader = match &interactive.sample_grader {
+            Some(sample_grader) if is_sample => sample_grader,
+            _ => &interactive.grader,
+        };
+        for path in [grader, &interactive.header]
+            .into_iter()
+            .chain(&interactive.interactor)
+            .chain(&interactive.interactor_deps)
+        {
+            fp.file(&resolve(path))?;
+        }
     }
 
-    wtr.flush()?;
-    Ok(())
+    fp.bytes(format!("{:?}", problem_config.communication));
+    if let Some(communication) = &problem_config.communication {
+        fp.file(&resolve(&communication.manager))?;
+        for dep in &communication.deps {
+            fp.file(&resolve(dep))?;
+        }
+    }
+    Ok(fp)
 }
 
-pub async fn test_problem(
+/// 测试者的源文件与编译方式：所用语言的编译参数、语言配置与工具链版本
+fn tester_fingerprint(
+    base: &Fingerprint,
     day_config: &ContestDayConfig,
     problem_config: &ProblemConfig,
-    target: Target,
-    in_problem: bool,
-) -> Result<()> {
-    let data_items: Vec<FsTestData<'_>> = match target {
-        Target::Data => tuack_utils::data::problem_test_data(problem_config),
-        Target::Sample => tuack_utils::data::problem_sample_data(problem_config),
+    test: &TestCase,
+    sandbox: bool,
+) -> Result<Fingerprint> {
+    let ext = Path::new(&test.path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut fp = base.clone();
+    fp.bytes(&test.path)
+        .file(&problem_config.path.join(&test.path))?
+        .bytes(format!("{:?}", day_config.compile.get(&ext)))
+        .bytes(format!("{:?}", gctx().languages.get(&ext)))
+        .bytes(toolchain_version(&ext))
+        // 交互器按天配置的编译参数编译
+        .bytes(format!("{:?}", day_config.compile))
+        .bytes([u8::from(sandbox)]);
+    Ok(fp)
+}
+
+/// 按测试者源文件后缀构造运行器，并完成交互配置
+pub fn build_runner(
+    day_config: &ContestDayConfig,
+    problem_config: &ProblemConfig,
+    test: &TestCase,
+    is_sample: bool,
+    interactor: Option<&Interactor>,
+) -> Result<Box<dyn Runner>> {
+    let path = if PathBuf::from_str(&test.path)?.is_absolute() {
+        PathBuf::from_str(&test.path)?
+    } else {
+        dunce::canonicalize(problem_config.path.join(&test.path))?
     };
-    let is_sample = matches!(target, Target::Sample);
+    info!("文件路径：{}", path.display());
+
+    let mut runner: Box<dyn Runner> = match path
+        .extension()
+        .context("文件无后缀名")?
+        .to_string_lossy()
+        .to_string()
+        .as_str()
+    {
+        "cpp" => Box::new(CppRunner::new(
+            &path,
+            &day_config.compile,
+            problem_config.name.clone(),
+        )?),
+        _ => Box::new(GeneralRunner::new(
+            &path,
+            &day_config.compile,
+            problem_config.name.clone(),
+            &gctx().languages,
+        )?),
+    };
+
+    // 交互配置
+    if let Some(interactor) = interactor {
+        runner.set_interactor(interactor.clone());
+    } else if problem_config.problem_type == ProblemType::Interactive {
+        if runner.manifest().interactive {
+            let interactive = problem_config.interactive.as_ref().unwrap();
+            let resolve_path = |path: &String| -> Result<PathBuf> {
+                let p = PathBuf::from_str(path)?;
+                Ok(if p.is_absolute() {
+                    p
+                } else {
+                    dunce::canonicalize(problem_config.path.join(p))?
+                })
+            };
+            let grader_path = if is_sample {
+                match &interactive.sample_grader {
+                    Some(sg) => resolve_path(sg)?,
+                    None => resolve_path(&interactive.grader)?,
+                }
+            } else {
+                resolve_path(&interactive.grader)?
+            };
+            let header_path = resolve_path(&interactive.header)?;
+
+            if !grader_path.exists() {
+                bail!("grader 不存在")
+            }
+            if !header_path.exists() {
+                bail!("header 不存在")
+            }
+
+            runner.set_interactive(&grader_path, &header_path)?;
+        } else {
+            bail!("该语言不支持交互")
+        }
+    }
 
-    // 准备 Checker(无配置时用默认 normal diff 检查器兜底)
+    Ok(runner)
+}
+
+/// 准备 Checker（无配置时用默认 normal diff 检查器兜底），不可用时给出警告并返回 `None`
+pub fn prepare_checker(
+    problem_config: &ProblemConfig,
+    is_sample: bool,
+) -> Result<Option<Box<dyn Checker>>> {
     let checker: Box<dyn Checker> = match &problem_config.checker {
         Some(pair) => {
             let chk_config = if is_sample {
@@ -201,7 +391,7 @@ pub async fn test_problem(
 
             if !source_path.exists() {
                 msg_warn!("题目 {} 的 Checker 不存在", problem_config.name.magenta());
-                return Ok(());
+                return Ok(None);
             }
 
             let mut deps: IndexMap<String, Vec<u8>> = IndexMap::new();
@@ -215,7 +405,7 @@ pub async fn test_problem(
                             problem_config.name.magenta(),
                             e
                         );
-                        return Ok(());
+                        return Ok(None);
                     }
                 };
                 let name = abs.file_name().unwrap().to_string_lossy().to_string();
@@ -226,8 +416,27 @@ pub async fn test_problem(
             compile_pb.enable_steady_tick(Duration::from_millis(100));
             compile_pb.set_message(format!("编译 {} 题目的 Checker", problem_config.name));
 
-            let mut cpp_checker = match CppChecker::new(&source_path, &IndexMap::new(), "chk", deps)
+            let limits = checker_limits(
+                chk_config.time_limit.map(Duration::from_secs_f64),
+                chk_config.memory_limit.map(|m| m.as_u64()),
+            );
+            let checker: Result<Box<dyn Checker>> = match source_path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_string())
+                .as_deref()
             {
+                Some("cpp") => CppChecker::new(&source_path, &IndexMap::new(), "chk", deps)
+                    .map(|c| Box::new(c.with_limits(limits)) as Box<dyn Checker>),
+                _ => GeneralChecker::new(
+                    &source_path,
+                    &IndexMap::new(),
+                    "chk",
+                    deps,
+                    &gctx().languages,
+                )
+                .map(|c| Box::new(c.with_limits(limits)) as Box<dyn Checker>),
+            };
+            let mut checker = match checker {
                 Ok(c) => c,
                 Err(e) => {
                     msg_warn!(
@@ -235,19 +444,19 @@ pub async fn test_problem(
                         problem_config.name.magenta(),
                         e
                     );
-                    return Ok(());
+                    return Ok(None);
                 }
             };
 
-            if let Err(e) = cpp_checker.prepare() {
+            if let Err(e) = checker.prepare() {
                 msg_warn!("题目 {} 的 Checker 编译失败", problem_config.name.magenta());
                 msg_warn!("{}", e);
                 compile_pb.finish_and_clear();
-                return Ok(());
+                return Ok(None);
             }
 
             compile_pb.finish_and_clear();
-            Box::new(cpp_checker) as Box<dyn Checker>
+            checker
         }
         None => {
             let default_binary = gctx()
@@ -263,16 +472,298 @@ pub async fn test_problem(
             Box::new(PrebuiltChecker::new(default_binary)) as Box<dyn Checker>
         }
     };
+    Ok(Some(checker))
+}
+
+pub async fn test_problem(
+    day_config: &ContestDayConfig,
+    problem_config: &ProblemConfig,
+    args: &TestArgs,
+    in_problem: bool,
+) -> Result<Option<ProblemReport>> {
+    let target = args.target;
+    let data_items: Vec<FsTestData<'_>> = match target {
+        Target::Data => tuack_utils::data::problem_test_data(problem_config),
+        Target::Pretest => tuack_utils::data::problem_pretest_data(problem_config),
+        Target::Sample => tuack_utils::data::problem_sample_data(problem_config),
+    };
+    let is_sample = matches!(target, Target::Sample);
+
+    let Some(checker) = prepare_checker(problem_config, is_sample)? else {
+        return Ok(None);
+    };
+
+    // 交互器方式：交互器单独编译，之后交给每个运行器
+    let interactive = problem_config
+        .interactive
+        .as_ref()
+        .filter(|_| problem_config.problem_type == ProblemType::Interactive)
+        .filter(|i| i.mode == InteractiveMode::Interactor);
+    let mut cpp_interactor = None;
+    if let Some(interactive) = interactive {
+        let source = problem_config
+            .path
+            .join(interactive.interactor.as_ref().unwrap());
+        if !source.exists() {
+            msg_warn!("题目 {} 的交互器不存在", problem_config.name.magenta());
+            return Ok(None);
+        }
+        let mut deps = IndexMap::new();
+        for dep_path in &interactive.interactor_deps {
+            let abs = problem_config.path.join(dep_path);
+            let name = abs.file_name().unwrap().to_string_lossy().to_string();
+            deps.insert(name, fs::read(&abs)?);
+        }
 
+        let compile_pb = gctx().multiprogress.add(ProgressBar::new_spinner());
+        compile_pb.enable_steady_tick(Duration::from_millis(100));
+        compile_pb.set_message(format!("编译 {} 题目的交互器", problem_config.name));
+        let mut built = CppInteractor::new(&source, &day_config.compile, deps, &gctx().languages)?;
+        let prepared = built.prepare();
+        compile_pb.finish_and_clear();
+        if let Err(e) = prepared {
+            msg_warn!("题目 {} 的交互器编译失败", problem_config.name.magenta());
+            msg_warn!("{}", e);
+            return Ok(None);
+        }
+        cpp_interactor = Some(built);
+    }
+
+    // 通信题：manager 单独编译，各阶段共用
+    let communication = problem_config
+        .communication
+        .as_ref()
+        .filter(|_| problem_config.problem_type == ProblemType::Communication);
+    let mut manager = None;
+    if let Some(communication) = communication {
+        let source = problem_config.path.join(&communication.manager);
+        if !source.exists() {
+            msg_warn!("题目 {} 的 manager 不存在", problem_config.name.magenta());
+            return Ok(None);
+        }
+        let mut deps = IndexMap::new();
+        for dep_path in &communication.deps {
+            let abs = problem_config.path.join(dep_path);
+            let name = abs.file_name().unwrap().to_string_lossy().to_string();
+            deps.insert(name, fs::read(&abs)?);
+        }
+
+        let compile_pb = gctx().multiprogress.add(ProgressBar::new_spinner());
+        compile_pb.enable_steady_tick(Duration::from_millis(100));
+        compile_pb.set_message(format!("编译 {} 题目的 manager", problem_config.name));
+        let mut built = CppManager::new(&source, &IndexMap::new(), deps)?;
+        let prepared = built.prepare();
+        compile_pb.finish_and_clear();
+        if let Err(e) = prepared {
+            msg_warn!("题目 {} 的 manager 编译失败", problem_config.name.magenta());
+            msg_warn!("{}", e);
+            return Ok(None);
+        }
+        manager = Some(built);
+    }
+
+    let time_limit = Duration::from_secs_f64(problem_config.time_limit);
+    // 交互器的墙钟时间需覆盖选手程序在时间限制最宽的测试点上的运行
+    let interactor = cpp_interactor
+        .as_ref()
+        .map(|i| {
+            let max_time_limit = data_items
+                .iter()
+                .filter_map(|data| data.time_limit())
+                .map(Duration::from_secs_f64)
+                .fold(time_limit, Duration::max);
+            i.interactor(checker_limits(
+                Some(wall_time_limit(problem_config, max_time_limit)),
+                None,
+            ))
+        })
+        .transpose()?;
     let params = TaskParams {
         problem_name: problem_config.name.clone(),
-        time_limit: Duration::from_secs_f64(problem_config.time_limit),
+        time_limit,
         memory_limit: problem_config.memory_limit,
+        time_mode: problem_config.time_mode,
+        wall_time_limit: wall_time_limit(problem_config, time_limit),
         file_io: problem_config.file_io.unwrap_or(true),
+        interactor: interactor.is_some(),
+        stages: communication
+            .map(|c| {
+                c.stages
+                    .iter()
+                    .map(|stage| {
+                        let time_limit =
+                            stage.time_limit.map_or(time_limit, Duration::from_secs_f64);
+                        StageParams {
+                            name: stage.name.clone(),
+                            time_limit,
+                            memory_limit: stage.memory_limit.unwrap_or(problem_config.memory_limit),
+                            wall_time_limit: wall_time_limit(problem_config, time_limit),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
     };
 
+    let jobs = (args.jobs as usize).min(data_items.len()).max(1);
     let mut all_test_results = Vec::new();
 
+    // 先编译所有测试者，编译成功的按并行数复制出各自独立的运行器
+    let is_output = problem_config.problem_type == ProblemType::Output;
+    // 提交答案题评测很快，不使用缓存
+    let Config {
+        config: contest, ..
+    } = gctx().config.as_ref().context("找不到配置文件")?;
+    let cache = TestCache::new(&contest.path, !args.no_cache && !is_output);
+    let problem_fp = cache
+        .enabled()
+        .then(|| problem_fingerprint(problem_config, is_sample))
+        .transpose()?;
+    // 各测试点输入与答案的摘要，所有测试者共用
+    let data_digests = match problem_fp {
+        Some(_) => data_items
+            .iter()
+            .map(|data| {
+                Ok(Fingerprint::new()
+                    .file(&data.input_path())?
+                    .file(&data.output_path())?
+                    .finish())
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => Vec::new(),
+    };
+
+    let mut testers = Vec::new();
+    for (test_name, test) in &problem_config.tests {
+        // 提交答案题不编译运行，测试者即输出文件所在的目录或 zip
+        if is_output {
+            let prepared = match AnswerSet::open(&problem_config.path.join(&test.path)) {
+                Ok(answers) => PreparedTester::Answers(answers),
+                Err(e) => PreparedTester::CompileError(e),
+            };
+            testers.push((test_name, test, prepared, vec![None; data_items.len()]));
+            continue;
+        }
+
+        // 每个测试点的缓存键
+        let keys: Vec<Option<String>> = match &problem_fp {
+            Some(problem_fp) => {
+                let tester_fp =
+                    tester_fingerprint(problem_fp, day_config, problem_config, test, args.sandbox)?;
+                let multipliers = language_multipliers(test);
+                let mut keys = Vec::with_capacity(data_items.len());
+                for (data, digest) in data_items.iter().zip(&data_digests) {
+                    let mut fp = tester_fp.clone();
+                    fp.bytes(digest).bytes(format!(
+                        "{:?}",
+                        case_params(&params, problem_config, data, multipliers)
+                    ));
+                    keys.push(Some(fp.finish()));
+                }
+                keys
+            }
+            None => vec![None; data_items.len()],
+        };
+        // 缓存条目可能损坏或被删除，须实际读出全部结果才能跳过编译
+        let cached: Option<Vec<_>> = keys
+            .iter()
+            .map(|key| key.as_deref().and_then(|key| cache.load(key)).map(Some))
+            .collect();
+        if let Some(results) = cached.filter(|results| !results.is_empty()) {
+            info!("测试 {} 的所有测试点均有缓存，跳过编译", test_name);
+            let prepared = PreparedTester::Cached(Mutex::new(results));
+            testers.push((test_name, test, prepared, keys));
+            continue;
+        }
+
+        let mut runner = build_runner(
+            day_config,
+            problem_config,
+            test,
+            is_sample,
+            interactor.as_ref(),
+        )?;
+        if args.sandbox {
+            runner.set_backend(ExecBackend::Sandbox);
+        }
+
+        let compile_pb = gctx().multiprogress.add(ProgressBar::new_spinner());
+        compile_pb.enable_steady_tick(Duration::from_millis(100));
+        compile_pb.set_message(format!(
+            "编译 {} 题目的测试 {}",
+            problem_config.name, test_name
+        ));
+
+        let prepared = match runner.prepare() {
+            Ok(()) => {
+                let mut pool = Vec::with_capacity(jobs);
+                for _ in 1..jobs {
+                    pool.push(runner.fork()?);
+                }
+                pool.push(runner);
+                PreparedTester::Ready(Mutex::new(pool))
+            }
+            Err(e) => PreparedTester::CompileError(e),
+        };
+        compile_pb.finish_and_clear();
+
+        testers.push((test_name, test, prepared, keys));
+    }
+
+    // 所有 (测试者, 测试点) 并行评测，结果按提交顺序取回
+    let tasks: Vec<(usize, usize)> = testers
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, _, prepared, _))| !matches!(prepared, PreparedTester::CompileError(_)))
+        .flat_map(|(tester_idx, _)| {
+            (0..data_items.len()).map(move |case_idx| (tester_idx, case_idx))
+        })
+        .collect();
+    let testers_ref = &testers;
+    let data_ref = &data_items;
+    let checker_ref: &dyn Checker = checker.as_ref();
+    let params_ref = &params;
+    let manager_ref = manager.as_ref().map(|m| m as &dyn Manager);
+    let cache_ref = &cache;
+    // 每个结果附带是否来自缓存
+    let mut judged = stream::iter(tasks)
+        .map(move |(tester_idx, case_idx)| async move {
+            let data = &data_ref[case_idx];
+            let (_, test, prepared, keys) = &testers_ref[tester_idx];
+            let key = keys[case_idx].as_deref();
+            if let PreparedTester::Cached(results) = prepared {
+                let result = results.lock().unwrap()[case_idx].take();
+                return Ok::<_, anyhow::Error>((result.context("缓存结果已被取走")?, true));
+            }
+            if let Some(result) = key.and_then(|key| cache_ref.load(key)) {
+                return Ok((result, true));
+            }
+            let pool = match prepared {
+                PreparedTester::Ready(pool) => pool,
+                PreparedTester::Answers(answers) => {
+                    let name = format!("{}{}.out", problem_config.name, data.id());
+                    let output = answers.output(&name).await?;
+                    return Ok((judge_output(checker_ref, data, output).await, false));
+                }
+                PreparedTester::Cached(_) => unreachable!("缓存结果已在上方返回"),
+                PreparedTester::CompileError(_) => unreachable!("编译失败的测试者不参与评测"),
+            };
+            let mut runner = pool.lock().unwrap().pop().context("运行器已全部占用")?;
+            let params = case_params(params_ref, problem_config, data, language_multipliers(test));
+            let mut session = TestSession::new(runner.as_mut(), checker_ref, params);
+            if let Some(manager) = manager_ref {
+                session = session.with_manager(manager);
+            }
+            let result = session.judge(data).await;
+            pool.lock().unwrap().push(runner);
+            let result = result?;
+            if let Some(key) = key {
+                cache_ref.store(key, &result);
+            }
+            Ok((result, false))
+        })
+        .buffered(jobs);
+
     let tester_pb = gctx()
         .multiprogress
         .add(ProgressBar::new(problem_config.tests.len() as u64));
@@ -284,7 +775,7 @@ pub async fn test_problem(
     );
 
     let mut tester_count = 0;
-    for (test_name, test) in &problem_config.tests {
+    for (test_name, test, prepared, _) in &testers {
         tester_count += 1;
         tester_pb.set_message(format!(
             "处理第 {}/{} 个测试者：{}",
@@ -300,83 +791,29 @@ pub async fn test_problem(
             test_name.cyan()
         );
 
-        let path = if PathBuf::from_str(&test.path)?.is_absolute() {
-            PathBuf::from_str(&test.path)?
-        } else {
-            dunce::canonicalize(problem_config.path.join(&test.path))?
-        };
-        info!("文件路径：{}", path.display());
-
-        let mut runner: Box<dyn Runner> = match path
-            .extension()
-            .context("文件无后缀名")?
-            .to_string_lossy()
-            .to_string()
-            .as_str()
-        {
-            "cpp" => Box::new(CppRunner::new(
-                &path,
-                &day_config.compile,
-                problem_config.name.clone(),
-            )?),
-            _ => Box::new(GeneralRunner::new(
-                &path,
-                &day_config.compile,
-                problem_config.name.clone(),
-                &gctx().languages,
-            )?),
-        };
-
-        // 交互配置
-        if problem_config.problem_type == ProblemType::Interactive {
-            if runner.manifest().interactive {
-                let interactive = problem_config.interactive.as_ref().unwrap();
-                let resolve_path = |path: &String| -> Result<PathBuf> {
-                    let p = PathBuf::from_str(path)?;
-                    Ok(if p.is_absolute() {
-                        p
-                    } else {
-                        dunce::canonicalize(problem_config.path.join(p))?
-                    })
-                };
-                let grader_path = if is_sample {
-                    match &interactive.sample_grader {
-                        Some(sg) => resolve_path(sg)?,
-                        None => resolve_path(&interactive.grader)?,
-                    }
-                } else {
-                    resolve_path(&interactive.grader)?
-                };
-                let header_path = resolve_path(&interactive.header)?;
-
-                if !grader_path.exists() {
-                    bail!("grader 不存在")
-                }
-                if !header_path.exists() {
-                    bail!("header 不存在")
-                }
-
-                runner.set_interactive(&grader_path, &header_path)?;
-            } else {
-                bail!("该语言不支持交互")
-            }
-        }
-
         // 编译失败 -> 前端直接记录 CE，不进入评测
-        if let Err(e) = runner.prepare() {
-            msg_item!("CE".yellow().bold(), "编译错误");
+        if let PreparedTester::CompileError(e) = prepared {
+            let reason = if is_output {
+                "读取输出失败"
+            } else {
+                "编译错误"
+            };
+            msg_item!("CE".yellow().bold(), "{}", reason);
             msg_error!("{}", e);
 
-            let full_score: u32 = if is_sample {
+            let report = match target {
+                Target::Data => ScoreReport::zero(&problem_config.runtime.subtasks),
+                Target::Pretest => ScoreReport::zero(&problem_config.runtime.pretest_subtasks),
+                Target::Sample => ScoreReport::zero(&BTreeMap::new()),
+            };
+            // 样例不分 subtask，每个样例记 1 分
+            let full_score = if is_sample {
                 data_items.len() as u32
             } else {
-                problem_config
-                    .runtime
-                    .subtasks
-                    .values()
-                    .map(|g| g.max_score)
-                    .sum()
+                report.full_score
             };
+            let expected =
+                (target == Target::Data).then(|| check_expected(&test.expected, &report, &[]));
             let problem_result = ProblemTestResult {
                 tester_name: test_name.to_string(),
                 test_case_results: vec![IndividualTestCaseResult {
@@ -384,12 +821,16 @@ pub async fn test_problem(
                     status: DisplayStatus::CE,
                     score: 0,
                     full_score: data_items.iter().map(|d| d.full_score()).sum(),
-                    time: "N/A".to_string(),
-                    memory: "N/A".to_string(),
-                    message: None,
+                    cpu_time: None,
+                    wall_time: None,
+                    memory: None,
+                    message: Some(format!("{:#}", e)),
+                    stages: Vec::new(),
                 }],
                 total_score: 0,
                 full_score,
+                subtasks: report.groups,
+                expected,
             };
             msg_info!(
                 "{}",
@@ -400,14 +841,20 @@ pub async fn test_problem(
                 )
                 .bold()
             );
+            if let Some(expected) = &problem_result.expected {
+                show_expected(test_name, expected);
+            }
             all_test_results.push(problem_result);
             tester_pb.inc(1);
+            if args.fail_fast {
+                break;
+            }
             continue;
         }
 
         // 运行所有测试点
-        let mut session = TestSession::new(runner.as_mut(), checker.as_ref(), params.clone());
         let mut individual_results = Vec::new();
+        let mut case_outcomes = Vec::new();
         let mut results = Vec::new();
 
         let case_test_pb = gctx()
@@ -422,11 +869,13 @@ pub async fn test_problem(
         case_test_pb.set_message(format!("运行测试点：{}/{}", 1, data_items.len()));
 
         let mut case_count = 0;
+        let mut cache_hits = 0;
         for data_item in &data_items {
             case_count += 1;
             info!("运行测试点：{}", data_item.id());
 
-            let result = session.judge(data_item).await?;
+            let (result, cached) = judged.next().await.context("评测任务意外结束")??;
+            cache_hits += usize::from(cached);
             info!("测试点结果：{:?}", result.status);
 
             let display_status: DisplayStatus = (&result.status).into();
@@ -454,15 +903,25 @@ pub async fn test_problem(
                 status: display_status.clone(),
                 score: earned_score,
                 full_score,
-                time: match result.time {
-                    Some(duration) => format_duration(duration),
-                    None => "N/A".to_string(),
-                },
-                memory: match result.memory {
-                    Some(memory) => format!("{}", memory),
-                    None => "N/A".to_string(),
-                },
+                cpu_time: result.cpu_time,
+                wall_time: result.wall_time,
+                memory: result.memory,
                 message: result.message.clone(),
+                stages: result.stages.iter().map(Into::into).collect(),
+            });
+            let limits = case_params(
+                &params,
+                problem_config,
+                data_item,
+                language_multipliers(test),
+            );
+            case_outcomes.push(CaseOutcome {
+                id: data_item.id(),
+                status: display_status.clone(),
+                time: result.time(params.time_mode),
+                memory: result.memory,
+                time_limit: limits.time_limit,
+                memory_limit: limits.memory_limit,
             });
 
             match info_line {
@@ -470,7 +929,7 @@ pub async fn test_problem(
                     status_str.clone().bold(),
                     "测试点 {}  | {} | {} | {}",
                     data_item.id().to_string().bold(),
-                    match result.time {
+                    match result.time(params.time_mode) {
                         Some(duration) => format_duration(duration),
                         None => "N/A".to_string(),
                     }
@@ -486,7 +945,7 @@ pub async fn test_problem(
                     status_str.clone().bold(),
                     "测试点 {}  | {} | {}",
                     data_item.id().to_string().bold(),
-                    match result.time {
+                    match result.time(params.time_mode) {
                         Some(duration) => format_duration(duration),
                         None => "N/A".to_string(),
                     }
@@ -499,6 +958,25 @@ pub async fn test_problem(
                 ),
             }
 
+            // 通信题逐阶段列出用时与内存
+            for stage in &result.stages {
+                msg_item!(
+                    status_color(&(&stage.status).into()).bright_black(),
+                    "  阶段 {} | {}/{} | {}/{}",
+                    stage.name,
+                    match stage.cpu_time {
+                        Some(duration) => format_duration(duration),
+                        None => "N/A".to_string(),
+                    },
+                    format_duration(stage.time_limit),
+                    match stage.memory {
+                        Some(memory) => format!("{}", memory),
+                        None => "N/A".to_string(),
+                    },
+                    stage.memory_limit
+                );
+            }
+
             // UKE 错误信息在结果行后打印
             if matches!(display_status, DisplayStatus::UKE)
                 && let Some(m) = result.message.as_deref()
@@ -522,11 +1000,19 @@ pub async fn test_problem(
         }
 
         case_test_pb.finish_and_clear();
+        if cache_hits > 0 {
+            msg_info!(
+                "{}/{} 个测试点使用了缓存的结果",
+                cache_hits,
+                data_items.len()
+            );
+        }
 
         // 判分 (前端，按 target 选择策略)
         let report = match target {
             Target::Data => DataPolicy.score(problem_config, &data_items, &results),
             Target::Sample => SamplePolicy.score(problem_config, &data_items, &results),
+            Target::Pretest => PretestPolicy.score(problem_config, &data_items, &results),
         };
 
         msg_info!("测试结果：");
@@ -540,6 +1026,14 @@ pub async fn test_problem(
                     group.earned.to_string().cyan(),
                     group.full.to_string().green()
                 );
+                if !group.failed_dependencies.is_empty() {
+                    let deps: Vec<String> = group
+                        .failed_dependencies
+                        .iter()
+                        .map(|dep| format!("#{}", dep))
+                        .collect();
+                    msg_warn!("依赖的 Subtask {} 未满分，记 0 分", deps.join(" "));
+                }
             }
         }
         msg_info!(
@@ -552,25 +1046,36 @@ pub async fn test_problem(
             .bold()
         );
 
+        let expected = (target == Target::Data)
+            .then(|| check_expected(&test.expected, &report, &case_outcomes));
+        if let Some(expected) = &expected {
+            show_expected(test_name, expected);
+        }
+
+        let violated = expected.as_ref().is_some_and(|e| !e.passed);
         let problem_result = ProblemTestResult {
             tester_name: test_name.to_string(),
             test_case_results: individual_results,
             total_score: report.total,
             full_score: report.full_score,
+            subtasks: report.groups,
+            expected,
         };
         all_test_results.push(problem_result);
 
-        if target == Target::Data {
-            if check_test_case(test, report.total) {
-                info!("测试 {} 通过", test_name);
-            } else {
-                info!("测试 {} 不满足所有条件", test_name);
-                msg_warn!("{}", "不满足所有条件".bold());
-            }
+        tester_pb.inc(1);
+        if args.fail_fast && violated {
+            break;
         }
+    }
 
-        tester_pb.inc(1);
-        runner.cleanup()?;
+    drop(judged);
+    for (_, _, prepared, _) in &mut testers {
+        if let PreparedTester::Ready(pool) = prepared {
+            for runner in pool.get_mut().unwrap() {
+                runner.cleanup()?;
+            }
+        }
     }
 
     if in_problem {
@@ -579,16 +1084,42 @@ pub async fn test_problem(
         tester_pb.finish_and_clear();
     }
 
-    let csv_path = problem_config.path.join(match target {
-        Target::Data => "result.csv",
-        Target::Sample => "result-sample.csv",
-    });
-    write_results_to_csv(all_test_results, &csv_path)?;
+    let problem_report = ProblemReport {
+        problem: problem_config.name.clone(),
+        target,
+        testers: all_test_results,
+    };
+    let stem = match target {
+        Target::Data => "result",
+        Target::Sample => "result-sample",
+        Target::Pretest => "result-pretest",
+    };
+    let reports = std::slice::from_ref(&problem_report);
+    write_report(
+        ReportFormat::Csv,
+        reports,
+        &problem_config.path.join(format!("{}.csv", stem)),
+        false,
+    )?;
+    // 指定输出路径时由调用方汇总写出
+    if let Some(format) = args.report
+        && format != ReportFormat::Csv
+        && args.output.is_none()
+    {
+        let path = problem_config
+            .path
+            .join(format!("{}.{}", stem, format.extension()));
+        write_report(format, reports, &path, false)?;
+    }
 
-    Ok(())
+    Ok(Some(problem_report))
 }
 
-async fn test_day(day_config: &ContestDayConfig, target: Target, in_day: bool) -> Result<()> {
+async fn test_day(
+    day_config: &ContestDayConfig,
+    args: &TestArgs,
+    in_day: bool,
+) -> Result<TestOutcome> {
     let total_problems = day_config.subconfig.len();
     let day_pb = gctx()
         .multiprogress
@@ -599,16 +1130,44 @@ async fn test_day(day_config: &ContestDayConfig, target: Target, in_day: bool) -
             .unwrap()
             .progress_chars("=> "),
     );
+    let mut outcome = TestOutcome::default();
     for (idx, (_, problem_config)) in day_config.subconfig.iter().enumerate() {
         day_pb.set_message(format!("处理第 {}/{} 题", idx + 1, total_problems));
-        test_problem(day_config, problem_config, target, false).await?;
+        record_problem(
+            &mut outcome,
+            problem_config,
+            args,
+            test_problem(day_config, problem_config, args, false).await,
+        )?;
         day_pb.inc(1);
+        if args.fail_fast && outcome.is_failed() {
+            break;
+        }
     }
     if in_day {
         day_pb.finish_with_message("测试完成！");
     } else {
         day_pb.finish_and_clear();
     }
+    Ok(outcome)
+}
+
+/// 将一道题目的测试结果计入汇总；`--keep-going` 时测试出错也只记录，不中止
+fn record_problem(
+    outcome: &mut TestOutcome,
+    problem_config: &ProblemConfig,
+    args: &TestArgs,
+    result: Result<Option<ProblemReport>>,
+) -> Result<()> {
+    match result {
+        Ok(Some(report)) => outcome.push_report(report),
+        Ok(None) => outcome.push_error(&problem_config.name, "未能完成测试".to_string()),
+        Err(e) if args.keep_going => {
+            msg_error!("题目 {} 测试出错：{:#}", problem_config.name.magenta(), e);
+            outcome.push_error(&problem_config.name, format!("测试出错：{:#}", e));
+        }
+        Err(e) => return Err(e),
+    }
     Ok(())
 }
 
@@ -618,7 +1177,11 @@ pub async fn main(args: TestArgs) -> Result<()> {
         location: current_location,
     } = gctx().config.as_ref().context("找不到配置文件")?;
 
-    match current_location {
+    if args.sandbox {
+        tuack_utils::sandbox::check_available().context("无法使用沙箱")?;
+    }
+
+    let outcome = match current_location {
         CurrentLocation::Problem(day_key, prob_key) => {
             let day_config = config
                 .subconfig
@@ -628,14 +1191,21 @@ pub async fn main(args: TestArgs) -> Result<()> {
                 .subconfig
                 .get(prob_key)
                 .with_context(|| format!("未找到题目配置：{}", prob_key))?;
-            test_problem(day_config, problem_config, args.target, true).await?;
+            let mut outcome = TestOutcome::default();
+            record_problem(
+                &mut outcome,
+                problem_config,
+                &args,
+                test_problem(day_config, problem_config, &args, true).await,
+            )?;
+            outcome
         }
         CurrentLocation::Day(day_key) => {
             let day_config = config
                 .subconfig
                 .get(day_key)
                 .with_context(|| format!("未找到天配置：{}", day_key))?;
-            test_day(day_config, args.target, true).await?;
+            test_day(day_config, &args, true).await?
         }
         CurrentLocation::Root => {
             let total_days = config.subconfig.len();
@@ -648,15 +1218,53 @@ pub async fn main(args: TestArgs) -> Result<()> {
                     .unwrap()
                     .progress_chars("=> "),
             );
+            let mut outcome = TestOutcome::default();
             for (day_idx, (_, day_config)) in config.subconfig.iter().enumerate() {
                 day_pb.set_message(format!("处理第 {}/{} 天", day_idx + 1, total_days));
-                test_day(day_config, args.target, false).await?; // 复用 test_day
+                outcome.extend(test_day(day_config, &args, false).await?); // 复用 test_day
                 day_pb.inc(1);
+                if args.fail_fast && outcome.is_failed() {
+                    break;
+                }
             }
             day_pb.finish_with_message("测试完成！");
+            outcome
         }
         CurrentLocation::None => bail!("此命令必须在工程下执行"),
+    };
+
+    if let (Some(format), Some(output)) = (args.report, &args.output) {
+        write_report(format, &outcome.reports, output, true)
+            .with_context(|| format!("无法写入测试报告 {}", output.display()))?;
+        msg_info!("测试报告已写入 {}", output.display());
+    }
+
+    outcome.print_summary();
+    if outcome.is_failed() {
+        bail!("{} 项测试未通过", outcome.failures.len());
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wall_time_limit_follows_time_limit() {
+        let secs = Duration::from_secs;
+        // 显式上限按与题目时间限制的比例放宽
+        assert_eq!(scale_wall_time_limit(Some(3.0), 1.0, secs(1)), secs(3));
+        assert_eq!(scale_wall_time_limit(Some(3.0), 1.0, secs(4)), secs(12));
+        assert_eq!(scale_wall_time_limit(Some(3.0), 2.0, secs(1)), secs_f(1.5));
+        assert_eq!(
+            scale_wall_time_limit(None, 1.0, secs(4)),
+            ResourceLimits::default_wall_time_limit(secs(4))
+        );
+    }
+
+    fn secs_f(secs: f64) -> Duration {
+        Duration::from_secs_f64(secs)
+    }
+}
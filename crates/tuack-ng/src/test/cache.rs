@@ -0,0 +1,162 @@
+//! `test` 评测结果的持久缓存。
+//!
+//! 只缓存评测结果：`dmk` 中生成器与 std 的编译、运行不在缓存范围内，每次仍重新进行。
+
+use std::sync::{Mutex, OnceLock};
+
+use sha2::{Digest, Sha256};
+use strfmt::strfmt;
+
+use crate::prelude::*;
+use tuack_lib::test::{TestCaseResult, TestCaseStatus};
+use tuack_utils::command::string_to_command;
+
+/// 缓存目录名，位于竞赛根目录下
+pub const CACHE_DIR: &str = ".tuack-cache";
+
+/// 缓存键的摘要，依次写入影响评测结果的内容
+#[derive(Clone)]
+pub struct Fingerprint(Sha256);
+
+impl Fingerprint {
+    pub fn new() -> Self {
+        let mut hasher = Sha256::new();
+        // 程序版本变化时评测方式可能改变，旧缓存全部作废
+        hasher.update(env!("CARGO_PKG_VERSION"));
+        Fingerprint(hasher)
+    }
+
+    /// 写入一段内容，带长度前缀以免相邻内容拼接后冲突
+    pub fn bytes(&mut self, data: impl AsRef<[u8]>) -> &mut Self {
+        let data = data.as_ref();
+        self.0.update((data.len() as u64).to_le_bytes());
+        self.0.update(data);
+        self
+    }
+
+    /// 写入文件内容，文件不存在时写入空标记
+    pub fn file(&mut self, path: &Path) -> Result<&mut Self> {
+        if path.is_file() {
+            let content = fs::read(path).with_context(|| format!("无法读取 {}", path.display()))?;
+            self.bytes("file").bytes(content);
+        } else {
+            self.bytes("missing");
+        }
+        Ok(self)
+    }
+
+    pub fn finish(&self) -> String {
+        format!("{:x}", self.0.clone().finalize())
+    }
+}
+
+impl Default for Fingerprint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 编译、运行后缀名为 `ext` 的程序所用工具链的版本信息，每种后缀名在一次运行中只查询一次
+pub fn toolchain_version(ext: &str) -> String {
+    static VERSIONS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    let mut versions = VERSIONS.get_or_init(Default::default).lock().unwrap();
+    versions
+        .entry(ext.to_string())
+        .or_insert_with(|| {
+            version_checks(ext)
+                .iter()
+                .map(|check| run_check(check))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .clone()
+}
+
+/// C++ 固定由 g++ 编译，其他语言取 `langs.json` 中编译器与运行器的 `check` 命令
+fn version_checks(ext: &str) -> Vec<String> {
+    if ext == "cpp" {
+        return vec!["g++ --version".to_string()];
+    }
+    let Some(lang) = gctx().languages.get(ext) else {
+        return Vec::new();
+    };
+    lang.compiler
+        .iter()
+        .map(|c| (&c.check, &c.executable))
+        .chain(lang.runner.iter().map(|r| (&r.check, &r.executable)))
+        .filter_map(|(check, executable)| {
+            let vars = HashMap::from([("executable".to_string(), executable.clone())]);
+            strfmt(check, &vars).ok()
+        })
+        .collect()
+}
+
+/// 命令的输出；无法执行时记为不可用，工具链恢复后缓存随之失效
+fn run_check(check: &str) -> String {
+    string_to_command(check)
+        .ok()
+        .and_then(|mut cmd| cmd.output().ok())
+        .filter(|output| output.status.success())
+        .map_or_else(
+            || format!("{} 不可用", check),
+            |output| {
+                format!(
+                    "{}{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                )
+            },
+        )
+}
+
+/// 测试结果缓存：`.tuack-cache/test/<键>.json`
+pub struct TestCache {
+    dir: Option<PathBuf>,
+}
+
+impl TestCache {
+    /// `enabled` 为假时不读也不写缓存
+    pub fn new(root: &Path, enabled: bool) -> Self {
+        TestCache {
+            dir: enabled.then(|| root.join(CACHE_DIR).join("test")),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    fn path(&self, key: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.json", key)))
+    }
+
+    /// 读取缓存的结果，缓存损坏时视为未命中
+    pub fn load(&self, key: &str) -> Option<TestCaseResult> {
+        let path = self.path(key)?;
+        let content = fs::read_to_string(&path).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(result) => Some(result),
+            Err(e) => {
+                warn!("缓存 {} 损坏：{}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// 写入结果；UKE 多为评测环境的问题，不缓存
+    pub fn store(&self, key: &str, result: &TestCaseResult) {
+        let Some(path) = self.path(key) else { return };
+        if matches!(result.status, TestCaseStatus::UKE) {
+            return;
+        }
+        let written = fs::create_dir_all(path.parent().unwrap())
+            .map_err(anyhow::Error::from)
+            .and_then(|_| Ok(serde_json::to_string(result)?))
+            .and_then(|json| Ok(fs::write(&path, json)?));
+        if let Err(e) = written {
+            warn!("无法写入缓存 {}：{}", path.display(), e);
+        }
+    }
+}
@@ -0,0 +1,438 @@
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::Duration;
+
+use bytesize::ByteSize;
+use evalexpr::{
+    ContextWithMutableVariables, DefaultNumericTypes, HashMapContext, Value, eval_boolean,
+    eval_boolean_with_context,
+};
+
+use crate::prelude::*;
+use crate::test::DisplayStatus;
+use crate::test::policy::ScoreReport;
+use crate::test::report::ExpectedOutcome;
+use crate::utils::duration::format_duration;
+
+/// 单个期望条件
+///
+/// 支持的写法：
+/// - `>= 60`、`score >= 60`：总分
+/// - `subtask 2 == 0`：Subtask 得分
+/// - `cases 7-10 TLE|MLE`：指定测试点的结果只能是所列之一
+/// - `never WA`：任何测试点都不出现所列结果
+/// - `max time < 0.5 * TL`：每个测试点的用时（秒），`TL` 为该测试点的时间限制
+/// - `max memory < ML / 2`：每个测试点的内存（MiB），`ML` 为该测试点的空间限制
+#[derive(Debug, Clone, PartialEq)]
+pub enum Assertion {
+    Score(String),
+    Subtask(u32, String),
+    Cases(Vec<RangeInclusive<u32>>, Vec<String>),
+    Never(Vec<String>),
+    MaxTime(String),
+    MaxMemory(String),
+}
+
+/// 单个测试点的运行结果，用于判定期望条件
+#[derive(Debug)]
+pub struct CaseOutcome {
+    pub id: u32,
+    pub status: DisplayStatus,
+    /// 按题目的计时方式取得的用时
+    pub time: Option<Duration>,
+    pub memory: Option<ByteSize>,
+    pub time_limit: Duration,
+    pub memory_limit: ByteSize,
+}
+
+impl CaseOutcome {
+    /// 判定用时：超时的测试点没有用时，按不少于时间限制计
+    fn judged_time(&self) -> Option<Duration> {
+        match self.status {
+            DisplayStatus::TLE => Some(
+                self.time
+                    .map_or(self.time_limit, |t| t.max(self.time_limit)),
+            ),
+            _ => self.time,
+        }
+    }
+
+    /// 判定内存：内存超限的测试点按不少于空间限制计
+    fn judged_memory(&self) -> Option<ByteSize> {
+        match self.status {
+            DisplayStatus::MLE => Some(
+                self.memory
+                    .map_or(self.memory_limit, |m| m.max(self.memory_limit)),
+            ),
+            _ => self.memory,
+        }
+    }
+}
+
+const STATUSES: [&str; 9] = ["AC", "WA", "RE", "TLE", "MLE", "UKE", "FE", "PC", "CE"];
+
+fn parse_statuses(token: &str) -> Result<Vec<String>> {
+    token
+        .split('|')
+        .map(|status| {
+            let status = status.to_ascii_uppercase();
+            if STATUSES.contains(&status.as_str()) {
+                Ok(status)
+            } else {
+                bail!("未知的评测结果 {}", status)
+            }
+        })
+        .collect()
+}
+
+fn parse_ranges(token: &str) -> Result<Vec<RangeInclusive<u32>>> {
+    token
+        .split(',')
+        .map(|part| {
+            let (start, end) = part.split_once('-').unwrap_or((part, part));
+            let start: u32 = start
+                .parse()
+                .with_context(|| format!("无效的测试点 {}", part))?;
+            let end: u32 = end
+                .parse()
+                .with_context(|| format!("无效的测试点 {}", part))?;
+            if start > end {
+                bail!("无效的测试点范围 {}", part);
+            }
+            Ok(start..=end)
+        })
+        .collect()
+}
+
+impl FromStr for Assertion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().replace('×', "*");
+        if s.starts_with(['=', '!', '<', '>']) {
+            return Ok(Assertion::Score(s));
+        }
+
+        let tokens: Vec<&str> = s.split_whitespace().collect();
+        let rest = |n: usize| tokens[n.min(tokens.len())..].join(" ");
+        match tokens.as_slice() {
+            ["score", _, ..] => Ok(Assertion::Score(rest(1))),
+            ["subtask", id, _, ..] => Ok(Assertion::Subtask(
+                id.parse()
+                    .with_context(|| format!("无效的 Subtask 编号 {}", id))?,
+                rest(2),
+            )),
+            ["cases", ranges, statuses] => Ok(Assertion::Cases(
+                parse_ranges(ranges)?,
+                parse_statuses(statuses)?,
+            )),
+            ["never", statuses] => Ok(Assertion::Never(parse_statuses(statuses)?)),
+            ["max", "time", _, ..] => Ok(Assertion::MaxTime(rest(2))),
+            ["max", "memory", _, ..] => Ok(Assertion::MaxMemory(rest(2))),
+            _ => bail!("无法识别的条件"),
+        }
+    }
+}
+
+/// 判定 `<实际值> <条件>`，可用 `vars` 中的变量
+fn compare(actual: String, condition: &str, vars: &[(&str, f64)]) -> Result<bool> {
+    let expr = format!("{} {}", actual, condition);
+    debug!("条件：{}", expr);
+    if vars.is_empty() {
+        return Ok(eval_boolean(&expr)?);
+    }
+    let mut context = HashMapContext::<DefaultNumericTypes>::new();
+    for (name, value) in vars {
+        context.set_value(name.to_string(), Value::Float(*value))?;
+    }
+    Ok(eval_boolean_with_context(&expr, &context)?)
+}
+
+fn mebibytes(size: ByteSize) -> f64 {
+    size.as_u64() as f64 / (1024.0 * 1024.0)
+}
+
+impl Assertion {
+    /// 判定条件，不满足时返回原因
+    fn check(&self, report: &ScoreReport, cases: &[CaseOutcome]) -> Result<Option<String>> {
+        match self {
+            Assertion::Score(cond) => Ok((!compare(report.total.to_string(), cond, &[])?)
+                .then(|| format!("总得分 {} 不满足 {}", report.total, cond))),
+            Assertion::Subtask(id, cond) => {
+                let group = report
+                    .groups
+                    .get(id)
+                    .with_context(|| format!("Subtask {} 不存在", id))?;
+                Ok((!compare(group.earned.to_string(), cond, &[])?)
+                    .then(|| format!("Subtask {} 得分 {} 不满足 {}", id, group.earned, cond)))
+            }
+            Assertion::Cases(ranges, statuses) => {
+                for id in ranges.iter().flat_map(|r| r.clone()) {
+                    let Some(case) = cases.iter().find(|c| c.id == id) else {
+                        return Ok(Some(format!("测试点 {} 没有运行结果", id)));
+                    };
+                    if !statuses.iter().any(|s| s == case.status.code()) {
+                        return Ok(Some(format!(
+                            "测试点 {} 的结果为 {}，期望 {}",
+                            id,
+                            case.status.code(),
+                            statuses.join("|")
+                        )));
+                    }
+                }
+                Ok(None)
+            }
+            Assertion::Never(statuses) => Ok(cases
+                .iter()
+                .find(|c| statuses.iter().any(|s| s == c.status.code()))
+                .map(|c| format!("测试点 {} 的结果为 {}", c.id, c.status.code()))),
+            Assertion::MaxTime(cond) => {
+                let mut slowest: Option<(&CaseOutcome, Duration)> = None;
+                for case in cases {
+                    let Some(time) = case.judged_time() else {
+                        continue;
+                    };
+                    let vars = [("TL", case.time_limit.as_secs_f64())];
+                    if !compare(time.as_secs_f64().to_string(), cond, &vars)?
+                        && slowest.is_none_or(|(_, t)| time > t)
+                    {
+                        slowest = Some((case, time));
+                    }
+                }
+                Ok(slowest.map(|(case, time)| {
+                    let time = match case.status {
+                        DisplayStatus::TLE => format!("超时（≥ {}）", format_duration(time)),
+                        _ => format_duration(time),
+                    };
+                    format!(
+                        "测试点 {} 用时 {} 不满足 {}（TL = {}）",
+                        case.id,
+                        time,
+                        cond,
+                        format_duration(case.time_limit)
+                    )
+                }))
+            }
+            Assertion::MaxMemory(cond) => {
+                let mut largest: Option<(&CaseOutcome, ByteSize)> = None;
+                for case in cases {
+                    let Some(memory) = case.judged_memory() else {
+                        continue;
+                    };
+                    let vars = [("ML", mebibytes(case.memory_limit))];
+                    if !compare(mebibytes(memory).to_string(), cond, &vars)?
+                        && largest.is_none_or(|(_, m)| memory > m)
+                    {
+                        largest = Some((case, memory));
+                    }
+                }
+                Ok(largest.map(|(case, memory)| {
+                    let memory = match case.status {
+                        DisplayStatus::MLE => format!("超限（≥ {}）", memory),
+                        _ => memory.to_string(),
+                    };
+                    format!(
+                        "测试点 {} 内存 {} 不满足 {}（ML = {}）",
+                        case.id, memory, cond, case.memory_limit
+                    )
+                }))
+            }
+        }
+    }
+}
+
+/// 判定测试者的全部期望条件
+pub fn check_expected(
+    expected: &ExpectedScore,
+    report: &ScoreReport,
+    cases: &[CaseOutcome],
+) -> ExpectedOutcome {
+    let conditions = match expected {
+        ExpectedScore::Single(cond) => vec![cond.clone()],
+        ExpectedScore::Multiple(conds) => conds.clone(),
+    };
+
+    let failures = conditions
+        .iter()
+        .filter_map(|condition| {
+            condition
+                .parse::<Assertion>()
+                .and_then(|assertion| assertion.check(report, cases))
+                .unwrap_or_else(|e| Some(format!("条件 `{}` 无效：{:#}", condition, e)))
+        })
+        .collect::<Vec<_>>();
+
+    ExpectedOutcome {
+        conditions,
+        passed: failures.is_empty(),
+        failures,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::policy::GroupScore;
+
+    fn case(
+        id: u32,
+        status: DisplayStatus,
+        time_ms: Option<u64>,
+        memory_mib: Option<u64>,
+    ) -> CaseOutcome {
+        CaseOutcome {
+            id,
+            status,
+            time: time_ms.map(Duration::from_millis),
+            memory: memory_mib.map(ByteSize::mib),
+            time_limit: Duration::from_secs(1),
+            memory_limit: ByteSize::mib(256),
+        }
+    }
+
+    fn report(total: u32, groups: &[(u32, u32)]) -> ScoreReport {
+        ScoreReport {
+            groups: groups
+                .iter()
+                .map(|&(id, earned)| {
+                    (
+                        id,
+                        GroupScore {
+                            earned,
+                            full: 50,
+                            failed_dependencies: Vec::new(),
+                        },
+                    )
+                })
+                .collect(),
+            total,
+            full_score: 100,
+        }
+    }
+
+    fn check(condition: &str, report: &ScoreReport, cases: &[CaseOutcome]) -> Option<String> {
+        condition
+            .parse::<Assertion>()
+            .unwrap()
+            .check(report, cases)
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_assertions() {
+        assert_eq!(
+            ">= 60".parse::<Assertion>().unwrap(),
+            Assertion::Score(">= 60".to_string())
+        );
+        assert_eq!(
+            "score == 100".parse::<Assertion>().unwrap(),
+            Assertion::Score("== 100".to_string())
+        );
+        assert_eq!(
+            "subtask 2 == 0".parse::<Assertion>().unwrap(),
+            Assertion::Subtask(2, "== 0".to_string())
+        );
+        assert_eq!(
+            "cases 1,7-10 tle|MLE".parse::<Assertion>().unwrap(),
+            Assertion::Cases(
+                vec![1..=1, 7..=10],
+                vec!["TLE".to_string(), "MLE".to_string()]
+            )
+        );
+        assert_eq!(
+            "never WA".parse::<Assertion>().unwrap(),
+            Assertion::Never(vec!["WA".to_string()])
+        );
+        assert_eq!(
+            "max time < 0.5 × TL".parse::<Assertion>().unwrap(),
+            Assertion::MaxTime("< 0.5 * TL".to_string())
+        );
+        assert_eq!(
+            "max memory < ML / 2".parse::<Assertion>().unwrap(),
+            Assertion::MaxMemory("< ML / 2".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_invalid_assertions() {
+        assert!("never XX".parse::<Assertion>().is_err());
+        assert!("cases 5-3 AC".parse::<Assertion>().is_err());
+        assert!("subtask x == 0".parse::<Assertion>().is_err());
+        assert!("whatever".parse::<Assertion>().is_err());
+    }
+
+    #[test]
+    fn score_and_subtask() {
+        let report = report(60, &[(1, 50), (2, 10)]);
+        assert_eq!(check(">= 60", &report, &[]), None);
+        assert!(check("== 100", &report, &[]).is_some());
+        assert_eq!(check("subtask 1 == 50", &report, &[]), None);
+        assert!(check("subtask 2 == 0", &report, &[]).is_some());
+        assert!(
+            "subtask 3 == 0"
+                .parse::<Assertion>()
+                .unwrap()
+                .check(&report, &[])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn cases_and_never() {
+        let report = report(0, &[]);
+        let cases = [
+            case(1, DisplayStatus::AC, Some(100), Some(1)),
+            case(2, DisplayStatus::TLE, None, Some(1)),
+            case(3, DisplayStatus::WA, Some(100), Some(1)),
+        ];
+        assert_eq!(check("cases 2 TLE|MLE", &report, &cases), None);
+        assert!(check("cases 2-3 TLE", &report, &cases).is_some());
+        assert!(check("cases 4 AC", &report, &cases).is_some());
+        assert_eq!(check("never RE|MLE", &report, &cases), None);
+        assert!(check("never WA", &report, &cases).is_some());
+    }
+
+    #[test]
+    fn max_time() {
+        let report = report(0, &[]);
+        let fast = [
+            case(1, DisplayStatus::AC, Some(100), None),
+            case(2, DisplayStatus::AC, Some(400), None),
+        ];
+        assert_eq!(check("max time < 0.5 * TL", &report, &fast), None);
+
+        let slow = [
+            case(1, DisplayStatus::AC, Some(100), None),
+            case(2, DisplayStatus::AC, Some(700), None),
+        ];
+        let failure = check("max time < 0.5 * TL", &report, &slow).unwrap();
+        assert!(failure.contains("测试点 2"), "{}", failure);
+    }
+
+    #[test]
+    fn max_time_counts_tle() {
+        let report = report(0, &[]);
+        let cases = [
+            case(1, DisplayStatus::TLE, None, Some(1)),
+            case(2, DisplayStatus::TLE, None, Some(1)),
+        ];
+        let failure = check("max time < 0.5 * TL", &report, &cases).unwrap();
+        assert!(failure.contains("超时"), "{}", failure);
+        assert!(check("max time < 2 * TL", &report, &cases).is_none());
+    }
+
+    #[test]
+    fn max_memory_counts_mle() {
+        let report = report(0, &[]);
+        let ok = [case(1, DisplayStatus::AC, Some(100), Some(64))];
+        assert_eq!(check("max memory < ML / 2", &report, &ok), None);
+
+        let cases = [
+            case(1, DisplayStatus::AC, Some(100), Some(64)),
+            case(2, DisplayStatus::MLE, None, None),
+        ];
+        let failure = check("max memory < ML / 2", &report, &cases).unwrap();
+        assert!(failure.contains("测试点 2"), "{}", failure);
+        assert!(failure.contains("超限"), "{}", failure);
+    }
+}
@@ -1,14 +1,18 @@
 use std::collections::BTreeMap;
 
+use serde::Serialize;
+
 use tuack_config::{ProblemConfig, ScorePolicy as ConfigScorePolicy, SubtaskItem};
 use tuack_utils::data::FsTestData;
 use tuack_lib::test::TestCaseResult;
 
 /// 组的得分
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct GroupScore {
     pub earned: u32,
     pub full: u32,
+    /// 未满分的依赖 subtask，非空时该组记 0 分
+    pub failed_dependencies: Vec<u32>,
 }
 
 /// 判分报告
@@ -22,6 +26,28 @@ pub struct ScoreReport {
     pub full_score: u32,
 }
 
+impl ScoreReport {
+    /// 全部 subtask 记 0 分，用于编译失败的测试者
+    pub fn zero(subtasks: &BTreeMap<u32, SubtaskItem>) -> Self {
+        let groups: BTreeMap<u32, GroupScore> = subtasks
+            .iter()
+            .map(|(id, item)| {
+                let group = GroupScore {
+                    earned: 0,
+                    full: item.max_score,
+                    failed_dependencies: Vec::new(),
+                };
+                (*id, group)
+            })
+            .collect();
+        ScoreReport {
+            full_score: groups.values().map(|g| g.full).sum(),
+            groups,
+            total: 0,
+        }
+    }
+}
+
 /// 判分策略，解释一组执行结果，并返回判分结果。
 pub trait ScorePolicy {
     fn score(
@@ -47,6 +73,21 @@ impl ScorePolicy for DataPolicy {
     }
 }
 
+/// 预测试判分：按 pretest 所属的 subtask 计算
+pub struct PretestPolicy;
+
+impl ScorePolicy for PretestPolicy {
+    fn score(
+        &self,
+        config: &ProblemConfig,
+        data_items: &[FsTestData],
+        results: &[TestCaseResult],
+    ) -> ScoreReport {
+        let case_scores = pair_scores(data_items, results);
+        finish_score(&config.runtime.pretest_subtasks, &case_scores)
+    }
+}
+
 /// 样例判分：加和计算
 pub struct SamplePolicy;
 
@@ -64,6 +105,7 @@ impl ScorePolicy for SamplePolicy {
                 items: (0..n).collect(),
                 max_score: n as u32,
                 policy: ConfigScorePolicy::Sum,
+                dependencies: Vec::new(),
             },
         )]);
         let case_scores = pair_scores(data_items, results);
@@ -71,48 +113,96 @@ impl ScorePolicy for SamplePolicy {
     }
 }
 
-/// 按位置配对 `data_items` 与 `results`,产出 `(subtask, earned)` 元组。
+/// 单个测试点的得分
+struct CaseScore {
+    subtask: u32,
+    earned: u32,
+    /// 归一化得分比例
+    ratio: f64,
+}
+
+/// 按位置配对 `data_items` 与 `results`，产出各测试点的得分。
 ///
 /// 单点得分 = 归一化比例 * 满分，四舍五入。
-fn pair_scores(data_items: &[FsTestData], results: &[TestCaseResult]) -> Vec<(u32, u32)> {
+fn pair_scores(data_items: &[FsTestData], results: &[TestCaseResult]) -> Vec<CaseScore> {
     data_items
         .iter()
         .zip(results)
-        .map(|(item, r)| {
-            let earned = (r.score * item.full_score() as f64).round() as u32;
-            (item.subtask(), earned)
+        .map(|(item, r)| CaseScore {
+            subtask: item.subtask(),
+            earned: (r.score * item.full_score() as f64).round() as u32,
+            ratio: r.score,
         })
         .collect()
 }
 
 /// 按计分模型聚合各组的得分，产出报告。
-/// `case_scores` 为 `(subtask, earned)` 元组。
-fn finish_score(groups: &BTreeMap<u32, SubtaskItem>, case_scores: &[(u32, u32)]) -> ScoreReport {
-    let mut by_group: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
-    for (subtask, earned) in case_scores {
-        by_group.entry(*subtask).or_default().push(*earned);
+///
+/// 依赖的 subtask 未满分时该组记 0 分；不在 `groups` 中的依赖（如 pretest 未覆盖的 subtask）不参与判断。
+fn finish_score(groups: &BTreeMap<u32, SubtaskItem>, case_scores: &[CaseScore]) -> ScoreReport {
+    let mut by_group: BTreeMap<u32, Vec<&CaseScore>> = BTreeMap::new();
+    for case in case_scores {
+        by_group.entry(case.subtask).or_default().push(case);
     }
 
-    let mut score_groups = BTreeMap::new();
-    let mut total = 0;
-    let mut full_score = 0;
+    let mut raw = BTreeMap::new();
     for (id, group) in groups {
-        let scores = by_group.get(id).cloned().unwrap_or_default();
+        let cases = by_group.get(id).cloned().unwrap_or_default();
+        let scores = cases.iter().map(|c| c.earned);
         let earned = match group.policy {
-            ConfigScorePolicy::Sum => scores.iter().sum(),
-            ConfigScorePolicy::Max => *scores.iter().max().unwrap_or(&0),
-            ConfigScorePolicy::Min => *scores.iter().min().unwrap_or(&0),
+            ConfigScorePolicy::Sum => scores.sum(),
+            ConfigScorePolicy::Max => scores.max().unwrap_or(0),
+            ConfigScorePolicy::Min => scores.min().unwrap_or(0),
+            // 空组的乘积为 1，需与其他策略一致记 0 分
+            ConfigScorePolicy::Product if cases.is_empty() => 0,
+            ConfigScorePolicy::Product => {
+                let ratio: f64 = cases.iter().map(|c| c.ratio).product();
+                (ratio * group.max_score as f64).round() as u32
+            }
         };
-        total += earned;
-        full_score += group.max_score;
-        score_groups.insert(
-            *id,
+        raw.insert(*id, earned);
+    }
+
+    // 依赖无环（加载配置时已检查），按需递归求出各组的最终得分
+    fn resolve(
+        id: u32,
+        groups: &BTreeMap<u32, SubtaskItem>,
+        raw: &BTreeMap<u32, u32>,
+        resolved: &mut BTreeMap<u32, GroupScore>,
+    ) -> u32 {
+        if let Some(score) = resolved.get(&id) {
+            return score.earned;
+        }
+        let group = &groups[&id];
+        let failed_dependencies: Vec<u32> = group
+            .dependencies
+            .iter()
+            .copied()
+            .filter(|dep| groups.contains_key(dep))
+            .filter(|&dep| resolve(dep, groups, raw, resolved) < groups[&dep].max_score)
+            .collect();
+        let earned = if failed_dependencies.is_empty() {
+            raw[&id]
+        } else {
+            0
+        };
+        resolved.insert(
+            id,
             GroupScore {
                 earned,
                 full: group.max_score,
+                failed_dependencies,
             },
         );
+        earned
+    }
+
+    let mut score_groups = BTreeMap::new();
+    for &id in groups.keys() {
+        resolve(id, groups, &raw, &mut score_groups);
     }
+    let total = score_groups.values().map(|g| g.earned).sum();
+    let full_score = groups.values().map(|g| g.max_score).sum();
 
     ScoreReport {
         groups: score_groups,
@@ -120,3 +210,90 @@ fn finish_score(groups: &BTreeMap<u32, SubtaskItem>, case_scores: &[(u32, u32)])
         full_score,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(policy: ConfigScorePolicy, max_score: u32, dependencies: Vec<u32>) -> SubtaskItem {
+        SubtaskItem {
+            items: Vec::new(),
+            max_score,
+            policy,
+            dependencies,
+        }
+    }
+
+    fn case(subtask: u32, earned: u32, ratio: f64) -> CaseScore {
+        CaseScore {
+            subtask,
+            earned,
+            ratio,
+        }
+    }
+
+    #[test]
+    fn policies() {
+        let groups = BTreeMap::from([
+            (1, group(ConfigScorePolicy::Sum, 20, vec![])),
+            (2, group(ConfigScorePolicy::Min, 10, vec![])),
+            (3, group(ConfigScorePolicy::Max, 10, vec![])),
+            (4, group(ConfigScorePolicy::Product, 40, vec![])),
+        ]);
+        let cases = [
+            case(1, 10, 1.0),
+            case(1, 5, 0.5),
+            case(2, 10, 1.0),
+            case(2, 3, 0.3),
+            case(3, 10, 1.0),
+            case(3, 3, 0.3),
+            case(4, 40, 1.0),
+            case(4, 20, 0.5),
+        ];
+        let report = finish_score(&groups, &cases);
+        let earned: Vec<u32> = report.groups.values().map(|g| g.earned).collect();
+        assert_eq!(earned, [15, 3, 10, 20]);
+        assert_eq!(report.total, 48);
+        assert_eq!(report.full_score, 80);
+    }
+
+    #[test]
+    fn empty_groups_score_zero() {
+        let groups = BTreeMap::from([
+            (1, group(ConfigScorePolicy::Sum, 10, vec![])),
+            (2, group(ConfigScorePolicy::Min, 10, vec![])),
+            (3, group(ConfigScorePolicy::Max, 10, vec![])),
+            (4, group(ConfigScorePolicy::Product, 10, vec![])),
+        ]);
+        let report = finish_score(&groups, &[]);
+        assert!(report.groups.values().all(|g| g.earned == 0));
+        assert_eq!(report.total, 0);
+        assert_eq!(report.full_score, 40);
+    }
+
+    #[test]
+    fn dependencies() {
+        let groups = BTreeMap::from([
+            (1, group(ConfigScorePolicy::Sum, 10, vec![])),
+            (2, group(ConfigScorePolicy::Sum, 10, vec![1])),
+            (3, group(ConfigScorePolicy::Sum, 10, vec![2])),
+            // pretest 未覆盖的依赖不参与判断
+            (4, group(ConfigScorePolicy::Sum, 10, vec![5])),
+        ]);
+        let cases = [
+            case(1, 5, 0.5),
+            case(2, 10, 1.0),
+            case(3, 10, 1.0),
+            case(4, 10, 1.0),
+        ];
+        let report = finish_score(&groups, &cases);
+        assert_eq!(report.groups[&1].earned, 5);
+        assert_eq!(report.groups[&2].earned, 0);
+        assert_eq!(report.groups[&2].failed_dependencies, [1]);
+        // 依赖的 subtask 因依赖失败记 0 分时同样视为未满分
+        assert_eq!(report.groups[&3].earned, 0);
+        assert_eq!(report.groups[&3].failed_dependencies, [2]);
+        assert_eq!(report.groups[&4].earned, 10);
+        assert_eq!(report.total, 15);
+    }
+}
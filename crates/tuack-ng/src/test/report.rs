@@ -0,0 +1,328 @@
+use std::time::Duration;
+
+use bytesize::ByteSize;
+use clap::ValueEnum;
+use csv::Writer;
+use quick_xml::events::BytesText;
+use serde::Serializer;
+
+use crate::prelude::*;
+use crate::test::policy::GroupScore;
+use crate::test::{DisplayStatus, Target};
+use crate::utils::duration::format_duration;
+use tuack_lib::test::StageResult;
+
+/// 测试报告格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// CSV 表格
+    Csv,
+    /// 完整结构的 JSON
+    Json,
+    /// JUnit XML，供 CI 展示
+    Junit,
+}
+
+impl ReportFormat {
+    /// 报告文件的扩展名
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Csv => "csv",
+            ReportFormat::Json => "json",
+            ReportFormat::Junit => "xml",
+        }
+    }
+}
+
+// 记录测试用例结果
+#[derive(Debug, Serialize)]
+pub struct IndividualTestCaseResult {
+    pub test_case_id: u32,
+    pub status: DisplayStatus,
+    pub score: u32,
+    pub full_score: u32,
+    #[serde(rename = "cpu_time_ms", serialize_with = "serialize_millis")]
+    pub cpu_time: Option<Duration>,
+    #[serde(rename = "wall_time_ms", serialize_with = "serialize_millis")]
+    pub wall_time: Option<Duration>,
+    #[serde(rename = "memory_bytes", serialize_with = "serialize_bytes")]
+    pub memory: Option<ByteSize>,
+    /// 校验器诊断信息（完整内容，用于报告）
+    pub message: Option<String>,
+    /// 通信题各阶段的运行结果
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<StageReport>,
+}
+
+/// 通信题单个阶段的结果
+#[derive(Debug, Serialize)]
+pub struct StageReport {
+    pub name: String,
+    pub status: DisplayStatus,
+    #[serde(rename = "cpu_time_ms", serialize_with = "serialize_millis")]
+    pub cpu_time: Option<Duration>,
+    #[serde(rename = "wall_time_ms", serialize_with = "serialize_millis")]
+    pub wall_time: Option<Duration>,
+    #[serde(rename = "memory_bytes", serialize_with = "serialize_bytes")]
+    pub memory: Option<ByteSize>,
+}
+
+impl From<&StageResult> for StageReport {
+    fn from(stage: &StageResult) -> Self {
+        StageReport {
+            name: stage.name.clone(),
+            status: (&stage.status).into(),
+            cpu_time: stage.cpu_time,
+            wall_time: stage.wall_time,
+            memory: stage.memory,
+        }
+    }
+}
+
+/// 期望得分条件的判定结果
+#[derive(Debug, Serialize)]
+pub struct ExpectedOutcome {
+    pub conditions: Vec<String>,
+    pub passed: bool,
+    /// 各个不满足的条件的原因
+    pub failures: Vec<String>,
+}
+
+// 记录题目测试结果
+#[derive(Debug, Serialize)]
+pub struct ProblemTestResult {
+    pub tester_name: String,
+    pub test_case_results: Vec<IndividualTestCaseResult>,
+    pub total_score: u32,
+    pub full_score: u32,
+    /// 各 subtask 的得分，编译失败时为空
+    pub subtasks: BTreeMap<u32, GroupScore>,
+    /// 仅正式数据判定期望得分
+    pub expected: Option<ExpectedOutcome>,
+}
+
+/// 一道题目的全部测试结果
+#[derive(Debug, Serialize)]
+pub struct ProblemReport {
+    pub problem: String,
+    pub target: Target,
+    pub testers: Vec<ProblemTestResult>,
+}
+
+fn serialize_millis<S: Serializer>(value: &Option<Duration>, s: S) -> Result<S::Ok, S::Error> {
+    Serialize::serialize(&value.map(|d| d.as_micros() as f64 / 1000.0), s)
+}
+
+fn serialize_bytes<S: Serializer>(value: &Option<ByteSize>, s: S) -> Result<S::Ok, S::Error> {
+    Serialize::serialize(&value.map(|m| m.as_u64()), s)
+}
+
+/// 测试点用时（秒），优先取 CPU 时间
+fn case_seconds(case: &IndividualTestCaseResult) -> f64 {
+    case.cpu_time
+        .or(case.wall_time)
+        .map_or(0.0, |d| d.as_secs_f64())
+}
+
+fn display_or_na<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "N/A".to_string(), |v| v.to_string())
+}
+
+/// 按格式写出报告，`with_problem` 为真时 CSV 额外记录题目名
+pub fn write_report(
+    format: ReportFormat,
+    reports: &[ProblemReport],
+    path: &Path,
+    with_problem: bool,
+) -> Result<()> {
+    match format {
+        ReportFormat::Csv => write_csv(reports, path, with_problem),
+        ReportFormat::Json => {
+            let json = serde_json::json!({ "problems": reports });
+            fs::write(path, serde_json::to_string_pretty(&json)?)?;
+            Ok(())
+        }
+        ReportFormat::Junit => write_junit(reports, path),
+    }
+}
+
+// 将测试结果写入 CSV
+fn write_csv(reports: &[ProblemReport], path: &Path, with_problem: bool) -> Result<()> {
+    let mut wtr = Writer::from_path(path)?;
+
+    let header = [
+        "测试者",
+        "测试点 ID",
+        "状态",
+        "得分",
+        "满分",
+        "CPU 时间",
+        "墙钟时间",
+        "空间",
+        "信息",
+    ];
+    let problem_column = with_problem.then_some("题目");
+    wtr.write_record(problem_column.into_iter().chain(header))?;
+
+    for report in reports {
+        let problem_column = with_problem.then(|| report.problem.clone());
+        // 写入所有测试者的结果
+        for result in &report.testers {
+            // 写入每个测试用例的结果
+            for test_case_result in &result.test_case_results {
+                let message = test_case_result
+                    .message
+                    .as_deref()
+                    .unwrap_or("")
+                    .trim()
+                    .replace('\r', "\\r")
+                    .replace('\n', "\\n");
+                wtr.write_record(problem_column.iter().cloned().chain([
+                    result.tester_name.clone(),
+                    test_case_result.test_case_id.to_string(),
+                    format!("{:?}", test_case_result.status),
+                    test_case_result.score.to_string(),
+                    test_case_result.full_score.to_string(),
+                    display_or_na(test_case_result.cpu_time.map(format_duration)),
+                    display_or_na(test_case_result.wall_time.map(format_duration)),
+                    display_or_na(test_case_result.memory),
+                    message,
+                ]))?;
+            }
+
+            // 给这个测试者写入总分
+            wtr.write_record(problem_column.iter().cloned().chain([
+                result.tester_name.clone(),
+                "".to_string(),                 // 测试点 ID
+                "TOTAL".to_string(),            // 状态
+                result.total_score.to_string(), // 得分
+                result.full_score.to_string(),  // 满分
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+            ]))?;
+        }
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+// 每个测试者一个 testsuite，每个测试点与期望得分条件各一个 testcase
+fn write_junit(reports: &[ProblemReport], path: &Path) -> Result<()> {
+    let mut writer = quick_xml::Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_event(quick_xml::events::Event::Decl(
+        quick_xml::events::BytesDecl::new("1.0", Some("UTF-8"), None),
+    ))?;
+
+    let suites: Vec<(&ProblemReport, &ProblemTestResult)> = reports
+        .iter()
+        .flat_map(|report| report.testers.iter().map(move |tester| (report, tester)))
+        .collect();
+    let count = |tester: &ProblemTestResult| {
+        let cases = tester.test_case_results.len() + usize::from(tester.expected.is_some());
+        let failures = tester
+            .test_case_results
+            .iter()
+            .filter(|c| !matches!(c.status, DisplayStatus::AC | DisplayStatus::CE))
+            .count()
+            + usize::from(tester.expected.as_ref().is_some_and(|e| !e.passed));
+        let errors = tester
+            .test_case_results
+            .iter()
+            .filter(|c| matches!(c.status, DisplayStatus::CE))
+            .count();
+        (cases, failures, errors)
+    };
+    let (tests, failures, errors) = suites.iter().fold((0, 0, 0), |acc, (_, tester)| {
+        let (c, f, e) = count(tester);
+        (acc.0 + c, acc.1 + f, acc.2 + e)
+    });
+
+    writer
+        .create_element("testsuites")
+        .with_attribute(("name", "tuack-ng"))
+        .with_attribute(("tests", tests.to_string().as_str()))
+        .with_attribute(("failures", failures.to_string().as_str()))
+        .with_attribute(("errors", errors.to_string().as_str()))
+        .write_inner_content(|w| {
+            for (report, tester) in &suites {
+                let suite_name = format!("{}.{}", report.problem, tester.tester_name);
+                let (cases, failures, errors) = count(tester);
+                let time = tester
+                    .test_case_results
+                    .iter()
+                    .fold(0.0, |acc, c| acc + case_seconds(c));
+                w.create_element("testsuite")
+                    .with_attribute(("name", suite_name.as_str()))
+                    .with_attribute(("tests", cases.to_string().as_str()))
+                    .with_attribute(("failures", failures.to_string().as_str()))
+                    .with_attribute(("errors", errors.to_string().as_str()))
+                    .with_attribute(("time", format!("{:.3}", time).as_str()))
+                    .write_inner_content(|w| {
+                        for case in &tester.test_case_results {
+                            let name = match case.status {
+                                DisplayStatus::CE => "编译".to_string(),
+                                _ => format!("测试点 {}", case.test_case_id),
+                            };
+                            let time = case_seconds(case);
+                            let element = w
+                                .create_element("testcase")
+                                .with_attribute(("name", name.as_str()))
+                                .with_attribute(("classname", suite_name.as_str()))
+                                .with_attribute(("time", format!("{:.3}", time).as_str()));
+                            let tag = match case.status {
+                                DisplayStatus::AC => {
+                                    element.write_empty()?;
+                                    continue;
+                                }
+                                DisplayStatus::CE => "error",
+                                _ => "failure",
+                            };
+                            let summary = format!(
+                                "{:?}，得分 {}/{}",
+                                case.status, case.score, case.full_score
+                            );
+                            let message = case.message.as_deref().unwrap_or("").trim();
+                            element.write_inner_content(|w| {
+                                let detail = w
+                                    .create_element(tag)
+                                    .with_attribute(("type", case.status.code()))
+                                    .with_attribute(("message", summary.as_str()));
+                                if message.is_empty() {
+                                    detail.write_empty()?;
+                                } else {
+                                    detail.write_text_content(BytesText::new(message))?;
+                                }
+                                Ok(())
+                            })?;
+                        }
+
+                        if let Some(expected) = &tester.expected {
+                            let element = w
+                                .create_element("testcase")
+                                .with_attribute(("name", "期望得分"))
+                                .with_attribute(("classname", suite_name.as_str()));
+                            if expected.passed {
+                                element.write_empty()?;
+                            } else {
+                                let summary = expected.failures.join("；");
+                                element.write_inner_content(|w| {
+                                    w.create_element("failure")
+                                        .with_attribute(("type", "expected"))
+                                        .with_attribute(("message", summary.as_str()))
+                                        .write_empty()?;
+                                    Ok(())
+                                })?;
+                            }
+                        }
+                        Ok(())
+                    })?;
+            }
+            Ok(())
+        })?;
+
+    fs::write(path, writer.into_inner())?;
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use crate::prelude::*;
+use crate::test::DisplayStatus;
+use crate::test::report::ProblemReport;
+
+/// 未通过的一项测试
+#[derive(Debug)]
+pub struct Failure {
+    pub problem: String,
+    /// 题目整体未能测试时为空
+    pub tester: Option<String>,
+    pub reason: String,
+}
+
+/// 一次 `test` 命令的全部结果，跨题目与场次汇总
+#[derive(Debug, Default)]
+pub struct TestOutcome {
+    pub reports: Vec<ProblemReport>,
+    pub failures: Vec<Failure>,
+}
+
+impl TestOutcome {
+    /// 记录一道题目的结果，并收集其中编译错误与不满足期望的测试者
+    pub fn push_report(&mut self, report: ProblemReport) {
+        for tester in &report.testers {
+            let compile_error = tester
+                .test_case_results
+                .iter()
+                .any(|c| matches!(c.status, DisplayStatus::CE));
+            if compile_error {
+                self.failures.push(Failure {
+                    problem: report.problem.clone(),
+                    tester: Some(tester.tester_name.clone()),
+                    reason: "编译错误".to_string(),
+                });
+            } else if let Some(expected) = tester.expected.as_ref().filter(|e| !e.passed) {
+                self.failures.push(Failure {
+                    problem: report.problem.clone(),
+                    tester: Some(tester.tester_name.clone()),
+                    reason: expected.failures.join("；"),
+                });
+            }
+        }
+        self.reports.push(report);
+    }
+
+    /// 记录未能完成测试的题目
+    pub fn push_error(&mut self, problem: &str, reason: String) {
+        self.failures.push(Failure {
+            problem: problem.to_string(),
+            tester: None,
+            reason,
+        });
+    }
+
+    pub fn is_failed(&self) -> bool {
+        !self.failures.is_empty()
+    }
+
+    pub fn extend(&mut self, other: TestOutcome) {
+        self.reports.extend(other.reports);
+        self.failures.extend(other.failures);
+    }
+
+    /// 输出汇总表
+    pub fn print_summary(&self) {
+        let testers: usize = self.reports.iter().map(|r| r.testers.len()).sum();
+        if self.failures.is_empty() {
+            msg_info!(
+                "测试汇总：{} 道题目的 {} 个测试者均满足期望",
+                self.reports.len(),
+                testers
+            );
+            return;
+        }
+
+        msg_error!("测试汇总：{} 项未通过", self.failures.len());
+        for failure in &self.failures {
+            msg_item!(
+                "FAIL".red().bold(),
+                "{} | {} | {}",
+                failure.problem.magenta(),
+                failure.tester.as_deref().unwrap_or("-").cyan(),
+                failure.reason
+            );
+        }
+    }
+}
@@ -8,6 +8,7 @@ use crate::prelude::*;
 use tuack_lib::data::Data;
 use tuack_lib::utils::testlib::{Validator, ValidatorResult};
 use tuack_utils::validators::cpp::CppValidator;
+use tuack_utils::validators::general::GeneralValidator;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum Target {
@@ -15,6 +16,8 @@ pub enum Target {
     Data,
     /// 样例数据
     Sample,
+    /// 预测试数据
+    Pretest,
 }
 
 #[derive(Args, Debug)]
@@ -68,10 +71,29 @@ pub fn compile_validator(
     compile_pb.set_message(format!("编译 {} 题目的 Validator", problem_config.name));
 
     let result = (|| -> Result<Box<dyn Validator>> {
-        let mut cpp_validator = CppValidator::new(&source_path, &IndexMap::new(), "val", deps)
-            .context("Validator 初始化失败")?;
-        cpp_validator.prepare().context("Validator 编译失败")?;
-        Ok(Box::new(cpp_validator) as Box<dyn Validator>)
+        let mut validator: Box<dyn Validator> = match source_path
+            .extension()
+            .context("Validator 无后缀名")?
+            .to_string_lossy()
+            .as_ref()
+        {
+            "cpp" => Box::new(
+                CppValidator::new(&source_path, &IndexMap::new(), "val", deps)
+                    .context("Validator 初始化失败")?,
+            ),
+            _ => Box::new(
+                GeneralValidator::new(
+                    &source_path,
+                    &IndexMap::new(),
+                    "val",
+                    deps,
+                    &gctx().languages,
+                )
+                .context("Validator 初始化失败")?,
+            ),
+        };
+        validator.prepare().context("Validator 编译失败")?;
+        Ok(validator)
     })();
 
     compile_pb.finish_and_clear();
@@ -86,6 +108,7 @@ async fn validate_problem(
 ) -> Result<()> {
     let data_items: Vec<FsTestData<'_>> = match target {
         Target::Data => tuack_utils::data::problem_test_data(problem_config),
+        Target::Pretest => tuack_utils::data::problem_pretest_data(problem_config),
         Target::Sample => tuack_utils::data::problem_sample_data(problem_config),
     };
     let data_items =
@@ -264,6 +287,7 @@ impl From<crate::dmk::Target> for Target {
         match value {
             crate::dmk::Target::Data => Target::Data,
             crate::dmk::Target::Sample => Target::Sample,
+            crate::dmk::Target::Pretest => Target::Pretest,
         }
     }
 }
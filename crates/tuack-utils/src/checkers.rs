@@ -1,3 +1,6 @@
 pub mod cpp;
+pub mod general;
 pub mod helper;
+pub mod interactor;
+pub mod manager;
 pub mod prebuilt;
@@ -4,7 +4,8 @@ use tempfile::TempDir;
 use crate::prelude::*;
 use tuack_lib::data::AsyncReader;
 use tuack_lib::utils::testlib::Checker;
-use crate::checkers::helper::{JudgeResult, parse_result, write_temp};
+use crate::checkers::helper::{JudgeResult, checker_limits, run_checker};
+use tuack_lib::utils::compiler::ResourceLimits;
 use async_trait::async_trait;
 
 pub struct CppChecker {
@@ -14,6 +15,7 @@ pub struct CppChecker {
     program_name: String,
     binary_path: Option<PathBuf>,
     dependencies: IndexMap<String, Vec<u8>>,
+    limits: ResourceLimits,
 }
 
 impl CppChecker {
@@ -41,8 +43,15 @@ impl CppChecker {
             program_name,
             binary_path: None,
             dependencies,
+            limits: checker_limits(None, None),
         })
     }
+
+    /// 设置运行 Checker 时的资源限制
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
 }
 
 #[async_trait]
@@ -97,27 +106,6 @@ impl Checker for CppChecker {
         answer: &mut dyn AsyncReader,
     ) -> Result<(JudgeResult, String)> {
         let binary = self.binary_path.as_ref().context("Checker 未编译")?;
-
-        let input_path = write_temp(input, "tuack-ng-checker-in-").await?;
-        let output_path = write_temp(output, "tuack-ng-checker-out-").await?;
-        let answer_path = write_temp(answer, "tuack-ng-checker-ans-").await?;
-
-        let res_path = tempfile::NamedTempFile::with_prefix("tuack-ng-checker-res-")?;
-
-        let _status = Command::new(binary)
-            .arg(input_path.path())
-            .arg(output_path.path())
-            .arg(answer_path.path())
-            .arg(res_path.path())
-            .arg("-appes")
-            .stderr(Stdio::null())
-            .stdout(Stdio::null())
-            .status()?;
-
-        let res_content = fs::read_to_string(res_path.path()).context("Checker 未生成报告文件")?;
-        let (result, message) =
-            parse_result(&res_content).map_err(|e| anyhow!("无法解析 Checker 结果：{}", e))?;
-
-        Ok((result, message))
+        run_checker(binary, self.limits.clone(), input, output, answer).await
     }
 }
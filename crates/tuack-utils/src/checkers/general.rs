@@ -0,0 +1,61 @@
+use crate::checkers::helper::{JudgeResult, checker_limits, run_checker_command};
+use crate::compilers::program::GeneralProgram;
+use crate::prelude::*;
+use async_trait::async_trait;
+use tuack_config::lang::Language;
+use tuack_lib::data::AsyncReader;
+use tuack_lib::utils::compiler::ResourceLimits;
+use tuack_lib::utils::testlib::Checker;
+
+/// 按 `langs.json` 中的模板编译、运行的 Checker，用于 C++ 以外的语言
+///
+/// 调用约定与 testlib 相同：`<input> <output> <answer> <report> -appes`
+pub struct GeneralChecker {
+    program: GeneralProgram,
+    limits: ResourceLimits,
+}
+
+impl GeneralChecker {
+    pub fn new(
+        source: impl Into<PathBuf>,
+        compile_args: &IndexMap<String, String>,
+        program_name: impl Into<String>,
+        dependencies: IndexMap<String, Vec<u8>>,
+        languages: &IndexMap<String, Language>,
+    ) -> Result<Self> {
+        Ok(GeneralChecker {
+            program: GeneralProgram::new(
+                source,
+                compile_args,
+                program_name,
+                dependencies,
+                languages,
+                "tuack-ng-checker-",
+            )?,
+            limits: checker_limits(None, None),
+        })
+    }
+
+    /// 设置运行 Checker 时的资源限制
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+#[async_trait]
+impl Checker for GeneralChecker {
+    fn prepare(&mut self) -> Result<()> {
+        self.program.prepare("Checker")
+    }
+
+    async fn validate(
+        &self,
+        input: &mut dyn AsyncReader,
+        output: &mut dyn AsyncReader,
+        answer: &mut dyn AsyncReader,
+    ) -> Result<(JudgeResult, String)> {
+        let cmd = self.program.command("Checker")?;
+        run_checker_command(cmd, self.limits.clone(), input, output, answer).await
+    }
+}
@@ -1,10 +1,19 @@
+use std::process::{Command, Stdio};
+use std::time::Duration;
 use tempfile::NamedTempFile;
 
 use crate::prelude::*;
+use crate::process::ProcessSupervisor;
 use tuack_lib::data::AsyncReader;
+use tuack_lib::utils::compiler::{ResourceLimits, RunStatus};
 pub use tuack_lib::utils::testlib::JudgeResult;
 use quick_xml::de::from_str;
 
+/// Checker 缺省时间限制
+pub const DEFAULT_CHECKER_TIME_LIMIT: Duration = Duration::from_secs(10);
+/// Checker 缺省空间限制（1 GiB）
+pub const DEFAULT_CHECKER_MEMORY_LIMIT: u64 = 1 << 30;
+
 #[derive(Debug, Deserialize, PartialEq)]
 struct XmlResult {
     #[serde(rename = "@outcome")]
@@ -43,6 +52,120 @@ pub fn parse_result(xml_str: &str) -> Result<(JudgeResult, String)> {
     Ok((result, message))
 }
 
+/// 按 testlib 退出码判定结果：0 AC、1 WA、2 PE、3 FAIL、7 得分（分数取自 stderr）
+pub fn parse_exit_code(code: i32, stderr: &str) -> Result<(JudgeResult, String)> {
+    let message = stderr.trim().to_string();
+    let result = match code {
+        0 => JudgeResult::Accepted,
+        1 => JudgeResult::WrongAnswer,
+        2 => JudgeResult::PresentationError,
+        3 => JudgeResult::Fail,
+        7 => {
+            // testlib 输出形如 `points 50 message`
+            let score = message
+                .trim_start_matches("points")
+                .split_whitespace()
+                .next()
+                .map(str::to_string);
+            JudgeResult::Score(parse_score_value(&score)?)
+        }
+        other => bail!("异常退出，退出码 {}", other),
+    };
+    Ok((result, message))
+}
+
+/// Checker 的资源限制，缺省使用 [`DEFAULT_CHECKER_TIME_LIMIT`] 与 [`DEFAULT_CHECKER_MEMORY_LIMIT`]
+pub fn checker_limits(time_limit: Option<Duration>, memory_limit: Option<u64>) -> ResourceLimits {
+    ResourceLimits::new(
+        time_limit.unwrap_or(DEFAULT_CHECKER_TIME_LIMIT),
+        memory_limit.unwrap_or(DEFAULT_CHECKER_MEMORY_LIMIT),
+    )
+}
+
+/// 在资源限制下运行 testlib checker：优先读取 XML 报告，报告缺失或无法解析时按退出码判定。
+///
+/// Checker 超时或超内存返回错误，由调用方记为 UKE。
+pub async fn run_checker(
+    binary: &Path,
+    limits: ResourceLimits,
+    input: &mut dyn AsyncReader,
+    output: &mut dyn AsyncReader,
+    answer: &mut dyn AsyncReader,
+) -> Result<(JudgeResult, String)> {
+    run_checker_command(Command::new(binary), limits, input, output, answer).await
+}
+
+/// 同 [`run_checker`]，`cmd` 为不含参数的 checker 启动命令（如解释器加脚本路径）
+pub async fn run_checker_command(
+    mut cmd: Command,
+    limits: ResourceLimits,
+    input: &mut dyn AsyncReader,
+    output: &mut dyn AsyncReader,
+    answer: &mut dyn AsyncReader,
+) -> Result<(JudgeResult, String)> {
+    let input_path = write_temp(input, "tuack-ng-checker-in-").await?;
+    let output_path = write_temp(output, "tuack-ng-checker-out-").await?;
+    let answer_path = write_temp(answer, "tuack-ng-checker-ans-").await?;
+
+    let res_path = NamedTempFile::with_prefix("tuack-ng-checker-res-")?;
+    let err_path = NamedTempFile::with_prefix("tuack-ng-checker-err-")?;
+
+    cmd.arg(input_path.path())
+        .arg(output_path.path())
+        .arg(answer_path.path())
+        .arg(res_path.path())
+        .arg("-appes")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(err_path.reopen()?);
+
+    let time_limit = limits.time_limit;
+    let mut child = tokio::process::Command::from(cmd).spawn()?;
+    let outcome = ProcessSupervisor::new(limits).supervise(&mut child).await?;
+    collect_verdict(
+        "Checker",
+        outcome.status,
+        time_limit,
+        res_path.path(),
+        err_path.path(),
+    )
+}
+
+/// 由 testlib 程序（checker 或交互器）的结束状态取得结果：优先读取 XML 报告，否则按退出码判定。
+///
+/// 超时或超内存返回错误，`name` 用于错误信息。
+pub fn collect_verdict(
+    name: &str,
+    status: RunStatus,
+    time_limit: Option<Duration>,
+    report: &Path,
+    stderr: &Path,
+) -> Result<(JudgeResult, String)> {
+    let code = match status {
+        RunStatus::Success => 0,
+        RunStatus::NonZeroExit(code) => code,
+        RunStatus::TimeLimitExceeded => bail!(
+            "{} 运行超时（{:.1} 秒）",
+            name,
+            time_limit.unwrap_or_default().as_secs_f64()
+        ),
+        RunStatus::MemoryLimitExceeded => bail!("{} 内存超限", name),
+        RunStatus::InternalError(e) => return Err(e.context(format!("{} 运行失败", name))),
+    };
+
+    let parsed = fs::read_to_string(report)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| parse_result(&content));
+    match parsed {
+        Ok(result) => Ok(result),
+        Err(e) => {
+            debug!("{} 报告不可用（{}），按退出码 {} 判定", name, e, code);
+            let stderr = fs::read_to_string(stderr).unwrap_or_default();
+            parse_exit_code(code, &stderr).with_context(|| format!("{} 未给出有效结果", name))
+        }
+    }
+}
+
 /// 将输入流流式写入临时文件，供 SPJ 程序按路径读取。
 pub async fn write_temp(reader: &mut dyn AsyncReader, prefix: &str) -> Result<NamedTempFile> {
     let tmp = NamedTempFile::with_prefix(prefix)?;
@@ -0,0 +1,114 @@
+use std::process::{Command, Stdio};
+use tempfile::NamedTempFile;
+
+use crate::checkers::helper::{JudgeResult, collect_verdict, write_temp};
+use crate::compilers::program::GeneralProgram;
+use crate::prelude::*;
+use crate::process::{ProcessOutcome, ProcessSupervisor, execute_command};
+use tuack_config::lang::Language;
+use tuack_lib::data::AsyncReader;
+use tuack_lib::utils::compiler::{ExecBackend, Interactor, ResourceLimits};
+
+/// testlib 交互器：按 `langs.json` 中对应语言的编译模板编译，供运行器以交互器模式对接
+pub struct CppInteractor {
+    program: GeneralProgram,
+}
+
+impl CppInteractor {
+    /// `compile_args` 中没有该语言的编译参数时使用 `-O2 -std=c++17`
+    pub fn new(
+        source: impl Into<PathBuf>,
+        compile_args: &IndexMap<String, String>,
+        dependencies: IndexMap<String, Vec<u8>>,
+        languages: &IndexMap<String, Language>,
+    ) -> Result<Self> {
+        let source = source.into();
+        let ext = source
+            .extension()
+            .context("没有后缀名")?
+            .to_string_lossy()
+            .into_owned();
+        let mut compile_args = compile_args.clone();
+        compile_args
+            .entry(ext)
+            .or_insert_with(|| "-O2 -std=c++17".to_string());
+        Ok(CppInteractor {
+            program: GeneralProgram::new(
+                source,
+                &compile_args,
+                "interactor",
+                dependencies,
+                languages,
+                "tuack-ng-interactor-",
+            )?,
+        })
+    }
+
+    pub fn prepare(&mut self) -> Result<()> {
+        self.program.prepare("交互器")
+    }
+
+    /// 以 `limits` 运行的交互器，需在 `prepare` 之后调用
+    pub fn interactor(&self, limits: ResourceLimits) -> Result<Interactor> {
+        let binary = self
+            .program
+            .dir()
+            .join(format!("interactor{}", std::env::consts::EXE_SUFFIX));
+        if !binary.exists() {
+            bail!("交互器未编译");
+        }
+        Ok(Interactor { binary, limits })
+    }
+}
+
+/// 运行与交互器对接的程序：`cmd` 的标准输入输出通过管道分别连到交互器的标准输出输入。
+///
+/// 交互器以 `<input> <output> <answer> <report> -appes` 调用，返回程序的运行结果与交互器给出的结果。
+pub async fn execute_interactive(
+    mut cmd: Command,
+    limits: ResourceLimits,
+    backend: ExecBackend,
+    workdir: &Path,
+    interactor: &Interactor,
+    input: &mut dyn AsyncReader,
+    answer: &mut dyn AsyncReader,
+) -> Result<(ProcessOutcome, Result<(JudgeResult, String)>)> {
+    let input_path = write_temp(input, "tuack-ng-interactor-in-").await?;
+    let answer_path = write_temp(answer, "tuack-ng-interactor-ans-").await?;
+    let output_path = NamedTempFile::with_prefix("tuack-ng-interactor-out-")?;
+    let res_path = NamedTempFile::with_prefix("tuack-ng-interactor-res-")?;
+    let err_path = NamedTempFile::with_prefix("tuack-ng-interactor-err-")?;
+
+    let (program_stdin, interactor_stdout) = std::io::pipe()?;
+    let (interactor_stdin, program_stdout) = std::io::pipe()?;
+    cmd.stdin(Stdio::from(program_stdin))
+        .stdout(Stdio::from(program_stdout));
+
+    let mut interactor_cmd = Command::new(&interactor.binary);
+    interactor_cmd
+        .arg(input_path.path())
+        .arg(output_path.path())
+        .arg(answer_path.path())
+        .arg(res_path.path())
+        .arg("-appes")
+        .stdin(Stdio::from(interactor_stdin))
+        .stdout(Stdio::from(interactor_stdout))
+        .stderr(err_path.reopen()?);
+    // 管道的另一端在 spawn 后随命令一同释放，任一方退出时对方都能读到 EOF
+    let mut child = tokio::process::Command::from(interactor_cmd).spawn()?;
+
+    let (program, interactor_outcome) = tokio::join!(
+        execute_command(cmd, limits, backend, workdir),
+        ProcessSupervisor::new(interactor.limits.clone()).supervise(&mut child),
+    );
+    let verdict = interactor_outcome.and_then(|outcome| {
+        collect_verdict(
+            "交互器",
+            outcome.status,
+            interactor.limits.time_limit,
+            res_path.path(),
+            err_path.path(),
+        )
+    });
+    Ok((program?, verdict))
+}
@@ -0,0 +1,185 @@
+use std::process::{Command, Stdio};
+use tempfile::{NamedTempFile, TempDir};
+
+use crate::checkers::helper::{JudgeResult, checker_limits, collect_verdict, write_temp};
+use crate::prelude::*;
+use crate::process::ProcessSupervisor;
+use tuack_lib::data::AsyncReader;
+use tuack_lib::utils::compiler::{ResourceLimits, RunStatus};
+use tuack_lib::utils::testlib::Manager;
+
+/// 通信题 manager：编译 C++ 源文件，负责生成各阶段的输入并给出最终结果。
+///
+/// 生成阶段输入时以 `stage <name> <input> <outputs...>` 调用，标准输出即该阶段的输入；
+/// 判定时以 `judge <input> <answer> <report> <outputs...>` 调用，报告格式同 testlib checker。
+pub struct CppManager {
+    tmp_dir: TempDir,
+    source: PathBuf,
+    compile_args: String,
+    dependencies: IndexMap<String, Vec<u8>>,
+    binary_path: Option<PathBuf>,
+    limits: ResourceLimits,
+}
+
+impl CppManager {
+    pub fn new(
+        source: impl Into<PathBuf>,
+        compile_args: &IndexMap<String, String>,
+        dependencies: IndexMap<String, Vec<u8>>,
+    ) -> Result<Self> {
+        let source = source.into();
+        let ext = source
+            .extension()
+            .context("没有后缀名")?
+            .to_string_lossy()
+            .into_owned();
+        Ok(CppManager {
+            tmp_dir: TempDir::with_prefix("tuack-ng-manager-")?,
+            compile_args: compile_args
+                .get(&ext)
+                .cloned()
+                .unwrap_or_else(|| "-O2 -std=c++17".to_string()),
+            source,
+            dependencies,
+            binary_path: None,
+            limits: checker_limits(None, None),
+        })
+    }
+
+    /// 设置 manager 每次运行的资源限制
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    fn binary(&self) -> Result<&Path> {
+        self.binary_path.as_deref().context("manager 未编译")
+    }
+
+    /// 各阶段输出写入临时文件，顺序与阶段一致
+    fn write_outputs(outputs: &[Vec<u8>]) -> Result<Vec<NamedTempFile>> {
+        outputs
+            .iter()
+            .map(|bytes| {
+                let tmp = NamedTempFile::with_prefix("tuack-ng-manager-out-")?;
+                fs::write(tmp.path(), bytes)?;
+                Ok(tmp)
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Manager for CppManager {
+    fn prepare(&mut self) -> Result<()> {
+        let source_target = self
+            .tmp_dir
+            .path()
+            .join("manager")
+            .with_extension(self.source.extension().unwrap());
+        fs::copy(&self.source, &source_target)?;
+
+        for (name, content) in &self.dependencies {
+            fs::write(self.tmp_dir.path().join(name), content)?;
+        }
+
+        let binary_path = self
+            .tmp_dir
+            .path()
+            .join("manager")
+            .with_extension(std::env::consts::EXE_EXTENSION);
+        let mut cmd = Command::new("g++");
+        cmd.arg("-o").arg(&binary_path).arg(&source_target);
+        cmd.args(shellwords::split(&self.compile_args)?);
+
+        let output = cmd.stdout(Stdio::null()).stderr(Stdio::piped()).output()?;
+        if !output.status.success() {
+            bail!(
+                "manager 编译错误：{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        fs::remove_file(&source_target)?;
+        self.binary_path = Some(binary_path);
+        Ok(())
+    }
+
+    async fn stage_input(
+        &self,
+        stage: &str,
+        input: &mut dyn AsyncReader,
+        outputs: &[Vec<u8>],
+    ) -> Result<Box<dyn AsyncReader>> {
+        let input_path = write_temp(input, "tuack-ng-manager-in-").await?;
+        let output_paths = Self::write_outputs(outputs)?;
+        let stage_path = NamedTempFile::with_prefix("tuack-ng-manager-stage-")?;
+        let err_path = NamedTempFile::with_prefix("tuack-ng-manager-err-")?;
+
+        let mut cmd = Command::new(self.binary()?);
+        cmd.arg("stage")
+            .arg(stage)
+            .arg(input_path.path())
+            .args(output_paths.iter().map(|p| p.path()))
+            .stdin(Stdio::null())
+            .stdout(stage_path.reopen()?)
+            .stderr(err_path.reopen()?);
+
+        let mut child = tokio::process::Command::from(cmd).spawn()?;
+        let outcome = ProcessSupervisor::new(self.limits.clone())
+            .supervise(&mut child)
+            .await?;
+        match outcome.status {
+            RunStatus::Success => {}
+            RunStatus::NonZeroExit(code) => bail!(
+                "manager 异常退出，退出码 {}：{}",
+                code,
+                fs::read_to_string(err_path.path())?.trim()
+            ),
+            RunStatus::TimeLimitExceeded => bail!(
+                "manager 运行超时（{:.1} 秒）",
+                self.limits.time_limit.unwrap_or_default().as_secs_f64()
+            ),
+            RunStatus::MemoryLimitExceeded => bail!("manager 内存超限"),
+            RunStatus::InternalError(e) => return Err(e.context("manager 运行失败")),
+        }
+
+        let bytes = tokio::fs::read(stage_path.path()).await?;
+        Ok(Box::new(std::io::Cursor::new(bytes)))
+    }
+
+    async fn judge(
+        &self,
+        input: &mut dyn AsyncReader,
+        answer: &mut dyn AsyncReader,
+        outputs: &[Vec<u8>],
+    ) -> Result<(JudgeResult, String)> {
+        let input_path = write_temp(input, "tuack-ng-manager-in-").await?;
+        let answer_path = write_temp(answer, "tuack-ng-manager-ans-").await?;
+        let output_paths = Self::write_outputs(outputs)?;
+        let res_path = NamedTempFile::with_prefix("tuack-ng-manager-res-")?;
+        let err_path = NamedTempFile::with_prefix("tuack-ng-manager-err-")?;
+
+        let mut cmd = Command::new(self.binary()?);
+        cmd.arg("judge")
+            .arg(input_path.path())
+            .arg(answer_path.path())
+            .arg(res_path.path())
+            .args(output_paths.iter().map(|p| p.path()))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(err_path.reopen()?);
+
+        let mut child = tokio::process::Command::from(cmd).spawn()?;
+        let outcome = ProcessSupervisor::new(self.limits.clone())
+            .supervise(&mut child)
+            .await?;
+        collect_verdict(
+            "manager",
+            outcome.status,
+            self.limits.time_limit,
+            res_path.path(),
+            err_path.path(),
+        )
+    }
+}
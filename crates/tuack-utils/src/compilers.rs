@@ -1,3 +1,4 @@
 pub mod cpp;
 pub mod general;
 pub mod generator;
+pub mod program;
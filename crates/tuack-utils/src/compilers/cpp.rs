@@ -5,9 +5,12 @@ use tokio::process::Command as TokioCommand;
 
 use crate::prelude::*;
 use tuack_lib::data::AsyncReader;
-use tuack_lib::utils::compiler::{IoMode, ResourceLimits, RunResult, RunnerManifest};
+use tuack_lib::utils::compiler::{
+    ExecBackend, Interactor, IoMode, ResourceLimits, RunResult, RunnerManifest,
+};
+use crate::checkers::interactor::execute_interactive;
 use crate::command::string_to_command;
-use crate::process::ProcessSupervisor;
+use crate::process::execute_command;
 use async_trait::async_trait;
 
 pub struct CppRunner {
@@ -21,6 +24,9 @@ pub struct CppRunner {
     limits: Option<ResourceLimits>,
     input: Option<Box<dyn AsyncReader>>,
     io_mode: IoMode,
+    backend: ExecBackend,
+    interactor: Option<Interactor>,
+    answer: Option<Box<dyn AsyncReader>>,
 }
 
 impl CppRunner {
@@ -51,6 +57,9 @@ impl CppRunner {
             limits: None,
             input: None,
             io_mode: IoMode::Stdio,
+            backend: ExecBackend::Supervisor,
+            interactor: None,
+            answer: None,
         })
     }
 
@@ -171,6 +180,30 @@ impl Runner for CppRunner {
         Ok(())
     }
 
+    fn fork(&self) -> Result<Box<dyn Runner>> {
+        let tmp_dir = TempDir::with_prefix("tuack-ng-runner-")?;
+        let exe_name = format!("{}{}", self.program_name, std::env::consts::EXE_SUFFIX);
+        fs::copy(
+            self.tmp_dir.path().join(&exe_name),
+            tmp_dir.path().join(&exe_name),
+        )?;
+        Ok(Box::new(CppRunner {
+            tmp_dir,
+            source: self.source.clone(),
+            compile_args: self.compile_args.clone(),
+            program_name: self.program_name.clone(),
+            interactive: self.interactive,
+            grader_path: self.grader_path.clone(),
+            header_path: self.header_path.clone(),
+            limits: None,
+            input: None,
+            io_mode: self.io_mode.clone(),
+            backend: self.backend,
+            interactor: self.interactor.clone(),
+            answer: None,
+        }))
+    }
+
     fn set_limits(&mut self, limits: ResourceLimits) {
         self.limits = Some(limits);
     }
@@ -183,6 +216,10 @@ impl Runner for CppRunner {
         self.io_mode = io_mode;
     }
 
+    fn set_backend(&mut self, backend: ExecBackend) {
+        self.backend = backend;
+    }
+
     fn set_interactive(&mut self, grader_file: &Path, header_file: &Path) -> Result<()> {
         self.interactive = true;
         self.grader_path = Some(grader_file.to_owned());
@@ -190,6 +227,14 @@ impl Runner for CppRunner {
         Ok(())
     }
 
+    fn set_interactor(&mut self, interactor: Interactor) {
+        self.interactor = Some(interactor);
+    }
+
+    fn set_answer(&mut self, answer: Box<dyn AsyncReader>) {
+        self.answer = Some(answer);
+    }
+
     async fn execute(&mut self) -> Result<RunResult> {
         let limits = self.limits.take().unwrap_or(ResourceLimits::unlimited());
 
@@ -212,6 +257,8 @@ impl Runner for CppRunner {
 
         // 根据 IO 模式设置 stdin/stdout
         match &self.io_mode {
+            // 交互器模式下输入交给交互器，标准输入输出在对接时设置
+            _ if self.interactor.is_some() => {}
             IoMode::Stdio => {
                 let stdin_path = self.tmp_dir.path().join("pipe_stdin");
                 let stdout_path = self.tmp_dir.path().join("pipe_stdout");
@@ -237,15 +284,33 @@ impl Runner for CppRunner {
         let stderr_file = std::fs::File::create(&stderr_path)?;
         cmd.stderr(Stdio::from(stderr_file));
 
-        let mut tokio_cmd = TokioCommand::from(cmd);
-        let mut child = tokio_cmd.spawn()?;
-
-        let (status, time, memory) = ProcessSupervisor::new(limits).supervise(&mut child).await?;
+        let (outcome, verdict) = match &self.interactor {
+            Some(interactor) => {
+                let mut answer = self.answer.take().context("交互器模式需要设置答案")?;
+                let (outcome, verdict) = execute_interactive(
+                    cmd,
+                    limits,
+                    self.backend,
+                    self.tmp_dir.path(),
+                    interactor,
+                    &mut input,
+                    &mut answer,
+                )
+                .await?;
+                (outcome, Some(verdict))
+            }
+            None => (
+                execute_command(cmd, limits, self.backend, self.tmp_dir.path()).await?,
+                None,
+            ),
+        };
 
         // 读取 stderr
         let stderr = tokio::fs::read(stderr_path).await?;
 
-        let output: Option<Box<dyn AsyncReader>> = {
+        let output: Option<Box<dyn AsyncReader>> = if verdict.is_some() {
+            None
+        } else {
             let output_path = match &self.io_mode {
                 IoMode::Stdio => self.tmp_dir.path().join("pipe_stdout"),
                 IoMode::File { output_name, .. } => self.tmp_dir.path().join(output_name),
@@ -258,11 +323,13 @@ impl Runner for CppRunner {
         };
 
         Ok(RunResult {
-            status,
-            time,
-            memory,
+            status: outcome.status,
+            cpu_time: outcome.cpu_time,
+            wall_time: outcome.wall_time,
+            memory: outcome.memory,
             output,
             stderr,
+            verdict,
         })
     }
 
@@ -6,11 +6,13 @@ use tokio::process::Command as TokioCommand;
 use tuack_config::lang::Language;
 use crate::prelude::*;
 use tuack_lib::data::AsyncReader;
-use tuack_lib::utils::compiler::{IoMode, ResourceLimits, RunResult, RunnerManifest};
-use crate::command::string_to_command;
-use crate::process::ProcessSupervisor;
+use tuack_lib::utils::compiler::{
+    ExecBackend, Interactor, IoMode, ResourceLimits, RunResult, RunnerManifest,
+};
+use crate::checkers::interactor::execute_interactive;
+use crate::compilers::program::{compile_command, run_command};
+use crate::process::execute_command;
 use async_trait::async_trait;
-use strfmt::strfmt;
 
 pub struct GeneralRunner {
     tmp_dir: TempDir,
@@ -21,6 +23,9 @@ pub struct GeneralRunner {
     limits: Option<ResourceLimits>,
     input: Option<Box<dyn AsyncReader>>,
     io_mode: IoMode,
+    backend: ExecBackend,
+    interactor: Option<Interactor>,
+    answer: Option<Box<dyn AsyncReader>>,
 }
 
 impl GeneralRunner {
@@ -53,96 +58,44 @@ impl GeneralRunner {
             limits: None,
             input: None,
             io_mode: IoMode::Stdio,
+            backend: ExecBackend::Supervisor,
+            interactor: None,
+            answer: None,
         })
     }
 
     fn get_compile_command(&self) -> Result<Option<StdCommand>> {
-        if let Some(ref compile) = self.language.compiler {
-            let compile_cmd = strfmt(
-                &compile.run,
-                &HashMap::from([
-                    ("executable".to_string(), compile.executable.clone()),
-                    (
-                        "output_path".to_string(),
-                        self.tmp_dir
-                            .path()
-                            .to_string_lossy()
-                            .to_string()
-                            .replace("\\", "\\\\")
-                            .replace(" ", "\\ "),
-                    ),
-                    (
-                        "program_name".to_string(),
-                        self.program_name
-                            .clone()
-                            .replace("\\", "\\\\")
-                            .replace(" ", "\\ "),
-                    ),
-                    ("args".to_string(), self.compile_args.clone()),
-                    (
-                        "input_path".to_string(),
-                        self.tmp_dir
-                            .path()
-                            .join(&self.program_name)
-                            .with_extension(self.source.extension().unwrap())
-                            .to_string_lossy()
-                            .to_string()
-                            .replace("\\", "\\\\")
-                            .replace(" ", "\\ "),
-                    ),
-                    (
-                        "exe_suffix".to_string(),
-                        std::env::consts::EXE_SUFFIX.to_string(),
-                    ),
-                ]),
-            )?;
-            Ok(Some(string_to_command(compile_cmd.as_str())?))
-        } else {
-            Ok(None)
-        }
+        compile_command(
+            &self.language,
+            self.tmp_dir.path(),
+            &self.program_name,
+            &self.source.extension().unwrap().to_string_lossy(),
+            &self.compile_args,
+        )
     }
 
     fn get_run_base_command(&self) -> Result<StdCommand> {
-        if let Some(ref runner) = self.language.runner {
-            let run_cmd = strfmt(
-                &runner.run,
-                &HashMap::from([
-                    ("executable".to_string(), runner.executable.clone()),
-                    (
-                        "input_path".to_string(),
-                        self.tmp_dir
-                            .path()
-                            .to_string_lossy()
-                            .to_string()
-                            .replace("\\", "\\\\")
-                            .replace(" ", "\\ "),
-                    ),
-                    (
-                        "program_name".to_string(),
-                        self.program_name
-                            .clone()
-                            .replace("\\", "\\\\")
-                            .replace(" ", "\\ "),
-                    ),
-                    (
-                        "exe_suffix".to_string(),
-                        std::env::consts::EXE_SUFFIX.to_string(),
-                    ),
-                ]),
-            )?;
-            Ok(string_to_command(run_cmd.as_str())?)
+        run_command(&self.language, self.tmp_dir.path(), &self.program_name)
+    }
+}
+
+/// 递归复制工作目录中的编译产物，跳过运行时生成的管道文件
+fn copy_artifacts(src: &Path, dst: &Path) -> Result<()> {
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with("pipe_") {
+            continue;
+        }
+        let target = dst.join(&name);
+        if entry.file_type()?.is_dir() {
+            fs::create_dir_all(&target)?;
+            copy_artifacts(&entry.path(), &target)?;
         } else {
-            let program_path = self.tmp_dir.path().join(format!(
-                "{}{}",
-                self.program_name,
-                std::env::consts::EXE_SUFFIX
-            ));
-            if !program_path.exists() {
-                bail!("可执行文件不存在：{}", program_path.display());
-            }
-            Ok(StdCommand::new(program_path))
+            fs::copy(entry.path(), &target)?;
         }
     }
+    Ok(())
 }
 
 #[async_trait]
@@ -223,6 +176,25 @@ impl Runner for GeneralRunner {
         Ok(())
     }
 
+    fn fork(&self) -> Result<Box<dyn Runner>> {
+        let tmp_dir = TempDir::with_prefix("tuack-ng-runner-")?;
+        // 编译产物的形式因语言而异（单个可执行文件、字节码目录等），整体复制工作目录
+        copy_artifacts(self.tmp_dir.path(), tmp_dir.path())?;
+        Ok(Box::new(GeneralRunner {
+            tmp_dir,
+            source: self.source.clone(),
+            compile_args: self.compile_args.clone(),
+            language: self.language.clone(),
+            program_name: self.program_name.clone(),
+            limits: None,
+            input: None,
+            io_mode: self.io_mode.clone(),
+            backend: self.backend,
+            interactor: self.interactor.clone(),
+            answer: None,
+        }))
+    }
+
     fn set_limits(&mut self, limits: ResourceLimits) {
         self.limits = Some(limits);
     }
@@ -235,10 +207,22 @@ impl Runner for GeneralRunner {
         self.io_mode = io_mode;
     }
 
+    fn set_backend(&mut self, backend: ExecBackend) {
+        self.backend = backend;
+    }
+
     fn set_interactive(&mut self, _grader_file: &Path, _header_file: &Path) -> Result<()> {
         unreachable!("通用运行器不支持交互");
     }
 
+    fn set_interactor(&mut self, interactor: Interactor) {
+        self.interactor = Some(interactor);
+    }
+
+    fn set_answer(&mut self, answer: Box<dyn AsyncReader>) {
+        self.answer = Some(answer);
+    }
+
     async fn execute(&mut self) -> Result<RunResult> {
         let limits = self.limits.take().unwrap_or(ResourceLimits::unlimited());
 
@@ -251,6 +235,8 @@ impl Runner for GeneralRunner {
         cmd.current_dir(&self.tmp_dir);
 
         match &self.io_mode {
+            // 交互器模式下输入交给交互器，标准输入输出在对接时设置
+            _ if self.interactor.is_some() => {}
             IoMode::Stdio => {
                 let stdin_path = self.tmp_dir.path().join("pipe_stdin");
                 let stdout_path = self.tmp_dir.path().join("pipe_stdout");
@@ -276,16 +262,34 @@ impl Runner for GeneralRunner {
         let stderr_file = std::fs::File::create(&stderr_path)?;
         cmd.stderr(Stdio::from(stderr_file));
 
-        let mut tokio_cmd = TokioCommand::from(cmd);
-        let mut child = tokio_cmd.spawn()?;
-
-        let (status, time, memory) = ProcessSupervisor::new(limits).supervise(&mut child).await?;
+        let (outcome, verdict) = match &self.interactor {
+            Some(interactor) => {
+                let mut answer = self.answer.take().context("交互器模式需要设置答案")?;
+                let (outcome, verdict) = execute_interactive(
+                    cmd,
+                    limits,
+                    self.backend,
+                    self.tmp_dir.path(),
+                    interactor,
+                    &mut input,
+                    &mut answer,
+                )
+                .await?;
+                (outcome, Some(verdict))
+            }
+            None => (
+                execute_command(cmd, limits, self.backend, self.tmp_dir.path()).await?,
+                None,
+            ),
+        };
 
         // 读取 stderr
         let stderr = tokio::fs::read(stderr_path).await?;
 
         // 输出流：只有输出文件不存在时视为无输出；权限等其他错误传播
-        let output: Option<Box<dyn AsyncReader>> = {
+        let output: Option<Box<dyn AsyncReader>> = if verdict.is_some() {
+            None
+        } else {
             let output_path = match &self.io_mode {
                 IoMode::Stdio => self.tmp_dir.path().join("pipe_stdout"),
                 IoMode::File { output_name, .. } => self.tmp_dir.path().join(output_name),
@@ -298,11 +302,13 @@ impl Runner for GeneralRunner {
         };
 
         Ok(RunResult {
-            status,
-            time,
-            memory,
+            status: outcome.status,
+            cpu_time: outcome.cpu_time,
+            wall_time: outcome.wall_time,
+            memory: outcome.memory,
             output,
             stderr,
+            verdict,
         })
     }
 
@@ -1,7 +1,10 @@
 use std::process::{Command as StdCommand, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tempfile::TempDir;
 
+use crate::compilers::program::GeneralProgram;
 use crate::prelude::*;
+use tuack_config::lang::Language;
 use tuack_lib::data::AsyncReader;
 use tuack_lib::utils::testlib::{Arg, Generator};
 use async_trait::async_trait;
@@ -13,6 +16,8 @@ pub struct CppGenerator {
     compile_args: String,
     binary_path: Option<PathBuf>,
     dependencies: IndexMap<String, Vec<u8>>,
+    /// 已启动的运行次数，用于区分并行运行的输出文件
+    runs: AtomicUsize,
 }
 
 impl CppGenerator {
@@ -37,6 +42,7 @@ impl CppGenerator {
                 .unwrap_or_else(|| "-O2 -std=c++17".to_string()),
             binary_path: None,
             dependencies,
+            runs: AtomicUsize::new(0),
         })
     }
 }
@@ -86,47 +92,108 @@ impl Generator for CppGenerator {
     }
 
     async fn run(&self, args: IndexMap<String, Arg>, seed: u64) -> Result<Box<dyn AsyncReader>> {
+        self.run_argv(generator_args(args), seed).await
+    }
+
+    async fn run_argv(&self, argv: Vec<String>, seed: u64) -> Result<Box<dyn AsyncReader>> {
         let binary = self
             .binary_path
             .as_ref()
             .context("生成器未编译，请先调用 prepare()")?;
 
-        let mut cmd_args: Vec<String> = Vec::new();
-
-        for (key, value) in args {
-            let val_str = match value {
-                Arg::Integer(v) => v.to_string(),
-                Arg::Float(v) => v.to_string(),
-                Arg::Str(v) => v,
-                Arg::Bool(true) => "true".to_string(),
-                Arg::Bool(false) => "false".to_string(),
-            };
-            cmd_args.push(format!("-{}={}", key, val_str));
-        }
+        let mut cmd = StdCommand::new(binary);
+        cmd.args(argv).arg(format!("-seed={}", seed));
+        run_generator(cmd, self.tmp_dir.path(), &self.runs).await
+    }
+}
 
-        cmd_args.push(format!("-seed={}", seed).to_string());
-
-        // stdout/stderr 重定向到临时文件，避免整块读入内存
-        let out_path = self.tmp_dir.path().join(format!("gen-{seed}.out"));
-        let err_path = self.tmp_dir.path().join(format!("gen-{seed}.err"));
-        let out_file = std::fs::File::create(&out_path)?;
-        let err_file = std::fs::File::create(&err_path)?;
-
-        let status = Command::new(binary)
-            .args(&cmd_args)
-            .stdout(Stdio::from(out_file))
-            .stderr(Stdio::from(err_file))
-            .status()
-            .await?;
-
-        if !status.success() {
-            let err = tokio::fs::read_to_string(&err_path)
-                .await
-                .unwrap_or_default();
-            bail!("生成器运行失败：{}", err);
-        }
+/// 按 testlib 的约定组装生成器参数：`-key=value`
+pub fn generator_args(args: IndexMap<String, Arg>) -> Vec<String> {
+    let mut cmd_args: Vec<String> = Vec::new();
+
+    for (key, value) in args {
+        let val_str = match value {
+            Arg::Integer(v) => v.to_string(),
+            Arg::Float(v) => v.to_string(),
+            Arg::Str(v) => v,
+            Arg::Bool(true) => "true".to_string(),
+            Arg::Bool(false) => "false".to_string(),
+        };
+        cmd_args.push(format!("-{}={}", key, val_str));
+    }
+    cmd_args
+}
+
+/// 运行生成器命令，输出写入 `dir` 下按运行次数编号的临时文件
+async fn run_generator(
+    cmd: StdCommand,
+    dir: &Path,
+    runs: &AtomicUsize,
+) -> Result<Box<dyn AsyncReader>> {
+    // stdout/stderr 重定向到临时文件，避免整块读入内存
+    let run = runs.fetch_add(1, Ordering::Relaxed);
+    let out_path = dir.join(format!("gen-{run}.out"));
+    let err_path = dir.join(format!("gen-{run}.err"));
+    let out_file = std::fs::File::create(&out_path)?;
+    let err_file = std::fs::File::create(&err_path)?;
+
+    let status = Command::from(cmd)
+        .stdout(Stdio::from(out_file))
+        .stderr(Stdio::from(err_file))
+        .status()
+        .await?;
+
+    if !status.success() {
+        let err = tokio::fs::read_to_string(&err_path)
+            .await
+            .unwrap_or_default();
+        bail!("生成器运行失败：{}", err);
+    }
+
+    let f = tokio::fs::File::open(&out_path).await?;
+    Ok(Box::new(f))
+}
+
+/// 按 `langs.json` 中的模板编译、运行的生成器，用于 C++ 以外的语言
+pub struct GeneralGenerator {
+    program: GeneralProgram,
+    runs: AtomicUsize,
+}
+
+impl GeneralGenerator {
+    pub fn new(
+        source: impl Into<PathBuf>,
+        compile_args: &IndexMap<String, String>,
+        dependencies: IndexMap<String, Vec<u8>>,
+        languages: &IndexMap<String, Language>,
+    ) -> Result<Self> {
+        Ok(GeneralGenerator {
+            program: GeneralProgram::new(
+                source,
+                compile_args,
+                "gen",
+                dependencies,
+                languages,
+                "tuack-ng-generator-",
+            )?,
+            runs: AtomicUsize::new(0),
+        })
+    }
+}
+
+#[async_trait]
+impl Generator for GeneralGenerator {
+    fn prepare(&mut self) -> Result<()> {
+        self.program.prepare("生成器")
+    }
+
+    async fn run(&self, args: IndexMap<String, Arg>, seed: u64) -> Result<Box<dyn AsyncReader>> {
+        self.run_argv(generator_args(args), seed).await
+    }
 
-        let f = tokio::fs::File::open(&out_path).await?;
-        Ok(Box::new(f))
+    async fn run_argv(&self, argv: Vec<String>, seed: u64) -> Result<Box<dyn AsyncReader>> {
+        let mut cmd = self.program.command("生成器")?;
+        cmd.args(argv).arg(format!("-seed={}", seed));
+        run_generator(cmd, self.program.dir(), &self.runs).await
     }
 }
@@ -0,0 +1,174 @@
+use std::process::{Command as StdCommand, Stdio};
+use tempfile::TempDir;
+
+use crate::command::string_to_command;
+use crate::prelude::*;
+use strfmt::strfmt;
+use tuack_config::lang::Language;
+
+/// 转义路径中的反斜杠与空格，以便模板展开后按 shell 规则拆分
+fn escape(path: &str) -> String {
+    path.replace("\\", "\\\\").replace(" ", "\\ ")
+}
+
+/// 按语言的编译模板生成编译命令，无需编译的语言返回 `None`
+///
+/// 源文件需已复制为 `<dir>/<program_name>.<ext>`，编译产物写入 `dir`
+pub fn compile_command(
+    language: &Language,
+    dir: &Path,
+    program_name: &str,
+    ext: &str,
+    args: &str,
+) -> Result<Option<StdCommand>> {
+    let Some(compile) = &language.compiler else {
+        return Ok(None);
+    };
+    let compile_cmd = strfmt(
+        &compile.run,
+        &HashMap::from([
+            ("executable".to_string(), compile.executable.clone()),
+            ("output_path".to_string(), escape(&dir.to_string_lossy())),
+            ("program_name".to_string(), escape(program_name)),
+            ("args".to_string(), args.to_string()),
+            (
+                "input_path".to_string(),
+                escape(&dir.join(program_name).with_extension(ext).to_string_lossy()),
+            ),
+            (
+                "exe_suffix".to_string(),
+                std::env::consts::EXE_SUFFIX.to_string(),
+            ),
+        ]),
+    )?;
+    Ok(Some(string_to_command(compile_cmd.as_str())?))
+}
+
+/// 按语言的运行模板生成运行命令；没有运行模板时直接执行 `dir` 中的可执行文件
+pub fn run_command(language: &Language, dir: &Path, program_name: &str) -> Result<StdCommand> {
+    if let Some(runner) = &language.runner {
+        let run_cmd = strfmt(
+            &runner.run,
+            &HashMap::from([
+                ("executable".to_string(), runner.executable.clone()),
+                ("input_path".to_string(), escape(&dir.to_string_lossy())),
+                ("program_name".to_string(), escape(program_name)),
+                (
+                    "exe_suffix".to_string(),
+                    std::env::consts::EXE_SUFFIX.to_string(),
+                ),
+            ]),
+        )?;
+        Ok(string_to_command(run_cmd.as_str())?)
+    } else {
+        let program_path = dir.join(format!("{}{}", program_name, std::env::consts::EXE_SUFFIX));
+        if !program_path.exists() {
+            bail!("可执行文件不存在：{}", program_path.display());
+        }
+        Ok(StdCommand::new(program_path))
+    }
+}
+
+/// 按 `langs.json` 的模板编译、运行的辅助程序，供生成器、Validator、Checker 共用
+pub struct GeneralProgram {
+    tmp_dir: TempDir,
+    source: PathBuf,
+    ext: String,
+    compile_args: String,
+    language: Language,
+    program_name: String,
+    dependencies: IndexMap<String, Vec<u8>>,
+    prepared: bool,
+}
+
+impl GeneralProgram {
+    pub fn new(
+        source: impl Into<PathBuf>,
+        compile_args: &IndexMap<String, String>,
+        program_name: impl Into<String>,
+        dependencies: IndexMap<String, Vec<u8>>,
+        languages: &IndexMap<String, Language>,
+        prefix: &str,
+    ) -> Result<Self> {
+        let source = source.into();
+        let ext = source
+            .extension()
+            .context("没有后缀名")?
+            .to_string_lossy()
+            .into_owned();
+        let language = languages
+            .get(&ext)
+            .with_context(|| format!("未知格式文件：.{}", ext))?
+            .to_owned();
+        Ok(GeneralProgram {
+            tmp_dir: TempDir::with_prefix(prefix)?,
+            source,
+            compile_args: compile_args.get(&ext).cloned().unwrap_or_default(),
+            ext,
+            language,
+            program_name: program_name.into(),
+            dependencies,
+            prepared: false,
+        })
+    }
+
+    /// 程序所在的临时目录
+    pub fn dir(&self) -> &Path {
+        self.tmp_dir.path()
+    }
+
+    /// 复制源文件与依赖并按需编译，`name` 用于错误信息
+    pub fn prepare(&mut self, name: &str) -> Result<()> {
+        if !self.tmp_dir.path().exists() {
+            fs::create_dir_all(self.tmp_dir.path())?;
+        }
+
+        for (dep, content) in &self.dependencies {
+            fs::write(self.tmp_dir.path().join(dep), content)?;
+        }
+
+        let source_target = self
+            .tmp_dir
+            .path()
+            .join(&self.program_name)
+            .with_extension(&self.ext);
+        fs::copy(&self.source, &source_target)?;
+
+        if let Some(mut cmd) = compile_command(
+            &self.language,
+            self.tmp_dir.path(),
+            &self.program_name,
+            &self.ext,
+            &self.compile_args,
+        )? {
+            debug!("{cmd:#?}");
+            let output = cmd
+                .current_dir(self.tmp_dir.path())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .output()
+                .with_context(|| format!("无法启动 {} 的编译器", name))?;
+            if !output.status.success() {
+                bail!(
+                    "{} 编译错误：{}",
+                    name,
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+            fs::remove_file(&source_target)?;
+        }
+
+        self.prepared = true;
+        Ok(())
+    }
+
+    /// 运行命令，工作目录为程序所在的临时目录
+    pub fn command(&self, name: &str) -> Result<StdCommand> {
+        if !self.prepared {
+            bail!("{} 未编译，请先调用 prepare()", name);
+        }
+        let mut cmd = run_command(&self.language, self.tmp_dir.path(), &self.program_name)?;
+        cmd.current_dir(self.tmp_dir.path());
+        Ok(cmd)
+    }
+}
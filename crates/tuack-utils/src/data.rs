@@ -1,8 +1,10 @@
-use std::io;
+use std::io::{self, Read};
 use std::path::PathBuf;
 
 use async_trait::async_trait;
+use bytesize::ByteSize;
 use tokio::fs::File;
+use zip::ZipArchive;
 
 use tuack_config::{DmkConfig, ExpandedDataItem, ExpandedSampleItem, ProblemConfig};
 use crate::prelude::*;
@@ -19,6 +21,16 @@ pub fn problem_test_data(problem: &ProblemConfig) -> Vec<FsTestData<'_>> {
         .collect()
 }
 
+/// 构造预测试数据的 `FsTestData` 列表（从 `pre/` 读取）。
+pub fn problem_pretest_data(problem: &ProblemConfig) -> Vec<FsTestData<'_>> {
+    problem
+        .runtime
+        .pretest
+        .iter()
+        .map(|item| FsTestData::from_data(problem.path.join("pre"), item))
+        .collect()
+}
+
 /// 构造样例数据的 `FsTestData` 列表（从 `sample/` 读取）。
 pub fn problem_sample_data(problem: &ProblemConfig) -> Vec<FsTestData<'_>> {
     problem
@@ -90,6 +102,14 @@ impl<'a> FsTestData<'a> {
         matches!(self.dmk(), DmkConfig::Output | DmkConfig::On)
     }
 
+    /// 生成脚本写入的生成命令（仅正式数据与 pretest）。
+    pub fn command(&self) -> Option<&str> {
+        match &self.item {
+            TestItemRef::Data(item) => item.command.as_deref(),
+            TestItemRef::Sample(_) => None,
+        }
+    }
+
     /// 输入文件的完整路径。
     pub fn input_path(&self) -> PathBuf {
         self.base_dir.join(self.input_name())
@@ -116,6 +136,22 @@ impl<'a> FsTestData<'a> {
         }
     }
 
+    /// 该测试点单独的时间限制（秒），样例为 `None`，即使用题目的时间限制。
+    pub fn time_limit(&self) -> Option<f64> {
+        match &self.item {
+            TestItemRef::Data(item) => Some(item.time_limit),
+            TestItemRef::Sample(_) => None,
+        }
+    }
+
+    /// 该测试点单独的空间限制，样例为 `None`。
+    pub fn memory_limit(&self) -> Option<ByteSize> {
+        match &self.item {
+            TestItemRef::Data(item) => Some(item.memory_limit),
+            TestItemRef::Sample(_) => None,
+        }
+    }
+
     /// 该测试点编号。
     pub fn id(&self) -> u32 {
         match &self.item {
@@ -159,3 +195,49 @@ impl DmkData for FsTestData<'_> {
         Ok(())
     }
 }
+
+/// 提交答案题的一份提交：目录或 zip，按文件名（不含目录）查找各测试点的输出。
+pub enum AnswerSet {
+    Dir(PathBuf),
+    /// zip 在打开时整体读入内存
+    Zip(IndexMap<String, Vec<u8>>),
+}
+
+impl AnswerSet {
+    pub fn open(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            return Ok(AnswerSet::Dir(path.to_path_buf()));
+        }
+
+        let file = fs::File::open(path).with_context(|| format!("打开 {} 失败", path.display()))?;
+        let mut archive = ZipArchive::new(file).context("读取 zip 失败")?;
+        let mut files = IndexMap::new();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            if !entry.is_file() {
+                continue;
+            }
+            let Some(name) = entry.name().rsplit('/').next().map(str::to_string) else {
+                continue;
+            };
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            files.entry(name).or_insert(content);
+        }
+        Ok(AnswerSet::Zip(files))
+    }
+
+    /// 读取名为 `name` 的输出，不存在时为 `None`
+    pub async fn output(&self, name: &str) -> Result<Option<Box<dyn AsyncReader>>> {
+        match self {
+            AnswerSet::Dir(root) => match File::open(root.join(name)).await {
+                Ok(f) => Ok(Some(Box::new(f))),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            },
+            AnswerSet::Zip(files) => Ok(files
+                .get(name)
+                .map(|c| Box::new(io::Cursor::new(c.clone())) as Box<dyn AsyncReader>)),
+        }
+    }
+}
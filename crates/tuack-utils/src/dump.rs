@@ -1,2 +1,5 @@
 pub mod arbiter;
+pub mod hydro;
 pub mod lemon;
+pub mod polygon;
+pub mod syzoj;
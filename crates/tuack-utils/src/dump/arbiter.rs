@@ -143,6 +143,24 @@ impl Dumper for ArbiterDumper {
         for (probnum, prob) in doc.problems.iter().enumerate() {
             let probnum = probnum + 1;
             info!("处理题目：{}", prob.name);
+            if !prob.pretest.is_empty() {
+                warnings.push(format!(
+                    "{} 的 pretest 在该格式中不受支持，已忽略",
+                    prob.name
+                ));
+            }
+            if prob.has_case_limits() {
+                warnings.push(format!(
+                    "{} 的测试点单独限制在该格式中不受支持，已使用题目的限制",
+                    prob.name
+                ));
+            }
+            if prob.has_dependencies() {
+                warnings.push(format!(
+                    "{} 的 subtask 依赖在该格式中不受支持，已忽略",
+                    prob.name
+                ));
+            }
 
             let score_per_case = if prob.data.is_empty() {
                 0u32
@@ -205,6 +223,20 @@ impl Dumper for ArbiterDumper {
                                 "题目 {} 是交互型，Arbiter 可能不支持。",
                                 prob.name
                             ));
+                            if let Some(interactor) = &prob.interactor {
+                                warnings.push(format!(
+                                    "题目 {} 的交互器 {} 未导出，请手动配置",
+                                    prob.name,
+                                    interactor.display()
+                                ));
+                            }
+                            "SOURCE".into()
+                        }
+                        ProblemType::Communication => {
+                            warnings.push(format!(
+                                "题目 {} 是通信型，Arbiter 可能不支持。",
+                                prob.name
+                            ));
                             "SOURCE".into()
                         }
                     },
@@ -0,0 +1,326 @@
+use bytesize::ByteSize;
+use std::io::Write;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+use crate::prelude::*;
+use crate::ren::renderers::rewrite_images;
+use tuack_lib::data::AsyncReader;
+use tuack_lib::dump::{DumpDocument, DumpProblem, Dumper, ScorePolicy};
+use tuack_lib::ren::ProblemType;
+use tuack_lib::utils::output::OutputFile;
+use tuack_ng_parser::printers::render_markdown;
+use tuack_ng_parser::transform::Transform;
+
+#[derive(Serialize)]
+struct HydroProblem {
+    pid: String,
+    owner: u32,
+    title: String,
+    tag: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct HydroCase {
+    input: String,
+    output: String,
+    /// 与题目限制不同时单独给出
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory: Option<String>,
+}
+
+#[derive(Serialize)]
+struct HydroSubtask {
+    id: u32,
+    score: u32,
+    #[serde(rename = "type")]
+    policy: &'static str,
+    cases: Vec<HydroCase>,
+    /// 依赖的 subtask
+    #[serde(rename = "if", skip_serializing_if = "Vec::is_empty")]
+    dependencies: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct HydroConfig {
+    #[serde(rename = "type")]
+    problem_type: &'static str,
+    time: String,
+    memory: String,
+    checker_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checker: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interactor: Option<String>,
+    subtasks: Vec<HydroSubtask>,
+}
+
+/// 取逻辑路径去掉首段目录（`data/`、`down/`）后的部分，`/` 替换为 `_` 拍平为单个文件名
+fn flat_name(path: &Path) -> String {
+    let mut components = path.components();
+    components.next();
+    components
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// 题面图片在 additional_file 中的文件名
+fn image_name(rel: &str) -> String {
+    format!("img_{}", rel.replace('/', "_"))
+}
+
+/// 将流按块写入 zip 条目，避免大数据整体进内存
+async fn write_entry(
+    zip: &mut ZipWriter<fs::File>,
+    name: &str,
+    mut reader: Box<dyn AsyncReader>,
+) -> Result<()> {
+    zip.start_file(name, SimpleFileOptions::default())
+        .with_context(|| format!("写入压缩包条目失败：{}", name))?;
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        zip.write_all(&buf[..n])?;
+    }
+    Ok(())
+}
+
+fn write_text(zip: &mut ZipWriter<fs::File>, name: &str, content: &str) -> Result<()> {
+    zip.start_file(name, SimpleFileOptions::default())
+        .with_context(|| format!("写入压缩包条目失败：{}", name))?;
+    zip.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+fn format_time(time_limit: Duration) -> String {
+    format!("{}ms", time_limit.as_millis())
+}
+
+fn format_memory(memory_limit: ByteSize) -> String {
+    format!("{}m", memory_limit.as_mib().ceil() as u64)
+}
+
+fn build_config(prob: &DumpProblem, warnings: &mut Vec<String>) -> Result<HydroConfig> {
+    let problem_type = match prob.problem_type {
+        ProblemType::Program => "default",
+        ProblemType::Output => "submit_answer",
+        ProblemType::Interactive if prob.interactor.is_some() => "interactive",
+        ProblemType::Interactive => {
+            warnings.push(format!(
+                "Hydro 不支持 grader 形式的交互题，{} 按传统题导出，请手动配置",
+                prob.name
+            ));
+            "default"
+        }
+        ProblemType::Communication => {
+            warnings.push(format!(
+                "{} 是通信题，按传统题导出，请手动配置 manager",
+                prob.name
+            ));
+            "default"
+        }
+    };
+
+    let mut subtasks = Vec::new();
+    for (id, task) in &prob.subtasks {
+        subtasks.push(HydroSubtask {
+            id: *id,
+            score: task.max_score,
+            policy: match task.policy {
+                ScorePolicy::Sum => "sum",
+                ScorePolicy::Min => "min",
+                ScorePolicy::Max => "max",
+                ScorePolicy::Product => {
+                    warnings.push(format!(
+                        "{} 的 product 评分方法在 Hydro 中不受支持，subtask {} 已按 min 导出",
+                        prob.name, id
+                    ));
+                    "min"
+                }
+            },
+            cases: task
+                .items
+                .iter()
+                .map(|&idx| {
+                    let case = &prob.data[idx];
+                    HydroCase {
+                        input: flat_name(&case.input),
+                        output: flat_name(&case.output),
+                        time: (case.time_limit != prob.time_limit)
+                            .then(|| format_time(case.time_limit)),
+                        memory: (case.memory_limit != prob.memory_limit)
+                            .then(|| format_memory(case.memory_limit)),
+                    }
+                })
+                .collect(),
+            dependencies: task.dependencies.clone(),
+        });
+    }
+
+    Ok(HydroConfig {
+        problem_type,
+        time: format_time(prob.time_limit),
+        memory: format_memory(prob.memory_limit),
+        checker_type: if prob.checker.is_some() {
+            "testlib"
+        } else {
+            "default"
+        },
+        checker: prob.checker.as_ref().map(|_| "chk.cpp".to_string()),
+        interactor: prob
+            .interactor
+            .as_ref()
+            .map(|_| "interactor.cpp".to_string()),
+        subtasks,
+    })
+}
+
+/// Hydro 题目包导出器：每题一个可直接导入的 zip
+pub struct HydroDumper {
+    tmp_dir: PathBuf,
+}
+
+impl HydroDumper {
+    pub fn new(tmp_dir: PathBuf) -> Self {
+        Self { tmp_dir }
+    }
+
+    async fn dump_problem(
+        &self,
+        doc: &DumpDocument,
+        prob: &DumpProblem,
+        warnings: &mut Vec<String>,
+    ) -> Result<PathBuf> {
+        let zip_path = self.tmp_dir.join(format!("{}.zip", prob.name));
+        let mut zip = ZipWriter::new(fs::File::create(&zip_path)?);
+        let root = &prob.name;
+        if !prob.pretest.is_empty() {
+            warnings.push(format!(
+                "{} 的 pretest 在该格式中不受支持，已忽略",
+                prob.name
+            ));
+        }
+
+        let meta = HydroProblem {
+            pid: prob.name.clone(),
+            owner: 1,
+            title: prob.title.clone(),
+            tag: Vec::new(),
+        };
+        write_text(
+            &mut zip,
+            &format!("{root}/problem.yaml"),
+            &serde_yaml::to_string(&meta)?,
+        )?;
+
+        // 题面：图片统一放入 additional_file，以 file:// 引用
+        match &prob.statement {
+            Some(ast) => {
+                let (mut ast, images) = rewrite_images(ast.clone(), prob.idx)?;
+                let prefix = format!("img/{}/", prob.idx);
+                ast.transform_image_urls(|url| match url.strip_prefix(&prefix) {
+                    Some(rel) => format!("file://{}", image_name(rel)),
+                    None => url.to_string(),
+                });
+                write_text(
+                    &mut zip,
+                    &format!("{root}/problem_zh.md"),
+                    &render_markdown(&ast),
+                )?;
+
+                let mut seen = std::collections::HashSet::new();
+                for (url, target) in &images {
+                    if !seen.insert(target.clone()) {
+                        continue;
+                    }
+                    let rel = target.to_string_lossy();
+                    let name = image_name(rel.strip_prefix(&prefix).unwrap_or(&rel));
+                    write_entry(
+                        &mut zip,
+                        &format!("{root}/additional_file/{name}"),
+                        doc.assets.load(prob.idx, url).await?,
+                    )
+                    .await?;
+                }
+            }
+            None => warnings.push(format!("{} 没有题面，导出的题目包不含题面", prob.name)),
+        }
+
+        for case in &prob.data {
+            for path in [&case.input, &case.output] {
+                write_entry(
+                    &mut zip,
+                    &format!("{root}/testdata/{}", flat_name(path)),
+                    doc.assets.load(prob.idx, path).await?,
+                )
+                .await?;
+            }
+        }
+
+        if let Some(checker) = &prob.checker {
+            write_entry(
+                &mut zip,
+                &format!("{root}/testdata/chk.cpp"),
+                doc.assets.load(prob.idx, checker).await?,
+            )
+            .await?;
+        }
+        if let Some(interactor) = &prob.interactor {
+            write_entry(
+                &mut zip,
+                &format!("{root}/testdata/interactor.cpp"),
+                doc.assets.load(prob.idx, interactor).await?,
+            )
+            .await?;
+        }
+
+        let config = build_config(prob, warnings)?;
+        write_text(
+            &mut zip,
+            &format!("{root}/testdata/config.yaml"),
+            &serde_yaml::to_string(&config)?,
+        )?;
+
+        for file in &prob.extra_down {
+            write_entry(
+                &mut zip,
+                &format!("{root}/additional_file/{}", flat_name(&file.path)),
+                doc.assets.load(prob.idx, &file.path).await?,
+            )
+            .await?;
+        }
+
+        zip.finish().context("写入压缩包失败")?;
+        Ok(zip_path)
+    }
+}
+
+#[async_trait]
+impl Dumper for HydroDumper {
+    async fn dump(&self, doc: &DumpDocument) -> Result<(Vec<OutputFile>, Vec<String>)> {
+        let mut files = Vec::new();
+        let mut warnings = Vec::new();
+
+        for prob in &doc.problems {
+            info!("打包题目：{}", prob.name);
+            let zip_path = self
+                .dump_problem(doc, prob, &mut warnings)
+                .await
+                .with_context(|| format!("打包题目 {} 失败", prob.name))?;
+            files.push(OutputFile::File {
+                path: PathBuf::from(format!("hydro/{}.zip", prob.name)),
+                bytes: Box::new(tokio::fs::File::open(&zip_path).await?),
+            });
+        }
+
+        Ok((files, warnings))
+    }
+}
@@ -72,6 +72,18 @@ impl Dumper for LemonDumper {
         let mut warnings = Vec::new();
 
         for prob in &doc.problems {
+            if !prob.pretest.is_empty() {
+                warnings.push(format!(
+                    "{} 的 pretest 在该格式中不受支持，已忽略",
+                    prob.name
+                ));
+            }
+            if prob.has_dependencies() {
+                warnings.push(format!(
+                    "{} 的 subtask 依赖在该格式中不受支持，已忽略",
+                    prob.name
+                ));
+            }
             for case in &prob.data {
                 files.push(OutputFile::File {
                     path: PathBuf::from(format!(
@@ -90,8 +102,23 @@ impl Dumper for LemonDumper {
             }
 
             let mut cases: Vec<LemonCase> = Vec::new();
-            let time_limit = (prob.time_limit.as_secs_f64() * 1000.0) as u32;
-            let memory_limit = prob.memory_limit.as_mib() as u32;
+            // 每个 Lemon 测试点取其中各数据点限制的最大值
+            let limits = |items: &[usize]| {
+                let time_limit = items
+                    .iter()
+                    .map(|&idx| prob.data[idx].time_limit)
+                    .max()
+                    .unwrap_or(prob.time_limit);
+                let memory_limit = items
+                    .iter()
+                    .map(|&idx| prob.data[idx].memory_limit)
+                    .max()
+                    .unwrap_or(prob.memory_limit);
+                (
+                    (time_limit.as_secs_f64() * 1000.0) as u32,
+                    memory_limit.as_mib() as u32,
+                )
+            };
 
             for task in prob.subtasks.values() {
                 let input_files: Vec<String> = task
@@ -109,6 +136,7 @@ impl Dumper for LemonDumper {
                     ScorePolicy::Sum => {
                         for (i, &idx) in task.items.iter().enumerate() {
                             let case = &prob.data[idx];
+                            let (time_limit, memory_limit) = limits(&[idx]);
                             cases.push(LemonCase {
                                 full_score: case.score,
                                 time_limit,
@@ -119,6 +147,7 @@ impl Dumper for LemonDumper {
                         }
                     }
                     ScorePolicy::Min => {
+                        let (time_limit, memory_limit) = limits(&task.items);
                         cases.push(LemonCase {
                             full_score: task.max_score,
                             time_limit,
@@ -128,6 +157,7 @@ impl Dumper for LemonDumper {
                         });
                     }
                     ScorePolicy::Max => bail!("lemon 不支持 max 评分方法"),
+                    ScorePolicy::Product => bail!("lemon 不支持 product 评分方法"),
                 }
             }
 
@@ -172,6 +202,7 @@ impl Dumper for LemonDumper {
                 ProblemType::Program => 0,
                 ProblemType::Output => 1,
                 ProblemType::Interactive => bail!("lemon 不支持交互题"),
+                ProblemType::Communication => bail!("lemon 不支持通信题"),
             };
 
             let prob_json = LemonProblem {
@@ -0,0 +1,322 @@
+use crate::polygon::*;
+use crate::prelude::*;
+use crate::ren::renderers::rewrite_images;
+use tuack_lib::dump::{DumpDocument, DumpProblem, Dumper, ScorePolicy};
+use tuack_lib::ren::ProblemType;
+use tuack_lib::utils::output::OutputFile;
+use tuack_ng_parser::printers::render_markdown;
+
+const INPUT_PATTERN: &str = "tests/%02d";
+const ANSWER_PATTERN: &str = "tests/%02d.a";
+const PRETEST_INPUT_PATTERN: &str = "pretests/%02d";
+const PRETEST_ANSWER_PATTERN: &str = "pretests/%02d.a";
+const SAMPLE_GROUP: &str = "samples";
+
+fn file_name(path: &Path) -> Result<String> {
+    Ok(path
+        .file_name()
+        .with_context(|| format!("无效的文件路径：{}", path.display()))?
+        .to_string_lossy()
+        .into_owned())
+}
+
+/// Polygon 题目包导出器：每题一个目录，内含 `problem.xml`、测试数据与源文件
+pub struct PolygonDumper;
+
+impl PolygonDumper {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 构造测试集：正式测试集 `tests` 中样例在前（0 分，归入 samples 组），随后按 subtask 顺序排列数据；
+    /// 预测试集 `pretests` 只含 pretest
+    /// 返回测试集与按测试编号排列的 `(输入, 输出)` 逻辑路径
+    fn build_testset(
+        prob: &DumpProblem,
+        pretest: bool,
+        warnings: &mut Vec<String>,
+    ) -> (Testset, Vec<(PathBuf, PathBuf)>) {
+        let mut tests = Vec::new();
+        let mut files = Vec::new();
+        let mut groups = Vec::new();
+
+        let (name, input_pattern, answer_pattern, cases, subtasks) = if pretest {
+            (
+                "pretests",
+                PRETEST_INPUT_PATTERN,
+                PRETEST_ANSWER_PATTERN,
+                &prob.pretest,
+                &prob.pretest_subtasks,
+            )
+        } else {
+            (
+                "tests",
+                INPUT_PATTERN,
+                ANSWER_PATTERN,
+                &prob.data,
+                &prob.subtasks,
+            )
+        };
+
+        if !pretest && !prob.samples.is_empty() {
+            groups.push(Group {
+                name: SAMPLE_GROUP.to_string(),
+                points: Some(0.0),
+                points_policy: "each-test".to_string(),
+                feedback_policy: "complete".to_string(),
+                dependencies: None,
+            });
+        }
+        for sample in prob.samples.iter().filter(|_| !pretest) {
+            tests.push(Test {
+                method: "manual".to_string(),
+                sample: Some(true),
+                points: Some(0.0),
+                group: Some(SAMPLE_GROUP.to_string()),
+                ..Default::default()
+            });
+            files.push((sample.input.clone(), sample.output.clone()));
+        }
+
+        for (id, task) in subtasks {
+            // Polygon 只有逐点计分与整组计分两种方式，max 与 product 退化为最接近的一种
+            let group_scored = match task.policy {
+                ScorePolicy::Sum => false,
+                ScorePolicy::Min => true,
+                ScorePolicy::Max => {
+                    warnings.push(format!(
+                        "{} 的 max 评分方法在 Polygon 中不受支持，{} 的 subtask {} 已按 each-test 导出",
+                        prob.name, name, id
+                    ));
+                    false
+                }
+                ScorePolicy::Product => {
+                    warnings.push(format!(
+                        "{} 的 product 评分方法在 Polygon 中不受支持，{} 的 subtask {} 已按 complete-group 导出",
+                        prob.name, name, id
+                    ));
+                    true
+                }
+            };
+            let points_policy = if group_scored {
+                "complete-group"
+            } else {
+                "each-test"
+            };
+            // pretest 只含部分 subtask，依赖其余 subtask 时略去
+            let dependency: Vec<Dependency> = task
+                .dependencies
+                .iter()
+                .filter(|dep| subtasks.contains_key(dep))
+                .map(|dep| Dependency {
+                    group: dep.to_string(),
+                })
+                .collect();
+            groups.push(Group {
+                name: id.to_string(),
+                points: Some(task.max_score as f64),
+                points_policy: points_policy.to_string(),
+                feedback_policy: "icpc".to_string(),
+                dependencies: (!dependency.is_empty()).then_some(Dependencies { dependency }),
+            });
+            for (i, &idx) in task.items.iter().enumerate() {
+                let case = &cases[idx];
+                // 整组通过才得分时，组分值计在首个测试上
+                let points = match (group_scored, i) {
+                    (true, 0) => task.max_score,
+                    (true, _) => 0,
+                    (false, _) => case.score,
+                };
+                tests.push(Test {
+                    method: "manual".to_string(),
+                    points: Some(points as f64),
+                    group: Some(id.to_string()),
+                    ..Default::default()
+                });
+                files.push((case.input.clone(), case.output.clone()));
+            }
+        }
+
+        let testset = Testset {
+            name: name.to_string(),
+            time_limit: prob.time_limit.as_millis() as u64,
+            memory_limit: prob.memory_limit.as_u64(),
+            test_count: tests.len(),
+            input_path_pattern: input_pattern.to_string(),
+            answer_path_pattern: answer_pattern.to_string(),
+            tests: Tests { test: tests },
+            groups: Some(Groups { group: groups }),
+        };
+        (testset, files)
+    }
+}
+
+impl Default for PolygonDumper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Dumper for PolygonDumper {
+    async fn dump(&self, doc: &DumpDocument) -> Result<(Vec<OutputFile>, Vec<String>)> {
+        let mut files = Vec::new();
+        let mut warnings = Vec::new();
+
+        for prob in &doc.problems {
+            let root = format!("polygon/{}", prob.name);
+            let mut xml = PolygonProblem {
+                revision: 1,
+                short_name: prob.name.clone(),
+                names: Names {
+                    name: vec![Name {
+                        language: "chinese".to_string(),
+                        value: prob.title.clone(),
+                    }],
+                },
+                ..Default::default()
+            };
+
+            for pretest in [false, true] {
+                if pretest && prob.pretest.is_empty() {
+                    continue;
+                }
+                let (testset, test_files) = Self::build_testset(prob, pretest, &mut warnings);
+                for (i, (input, output)) in test_files.iter().enumerate() {
+                    files.push(OutputFile::File {
+                        path: PathBuf::from(format!(
+                            "{}/{}",
+                            root,
+                            expand_pattern(&testset.input_path_pattern, i + 1)
+                        )),
+                        bytes: doc.assets.load(prob.idx, input).await?,
+                    });
+                    files.push(OutputFile::File {
+                        path: PathBuf::from(format!(
+                            "{}/{}",
+                            root,
+                            expand_pattern(&testset.answer_path_pattern, i + 1)
+                        )),
+                        bytes: doc.assets.load(prob.idx, output).await?,
+                    });
+                }
+                xml.judging.testset.push(testset);
+            }
+
+            match &prob.checker {
+                Some(checker) => {
+                    files.push(OutputFile::File {
+                        path: PathBuf::from(format!("{}/files/check.cpp", root)),
+                        bytes: doc.assets.load(prob.idx, checker).await?,
+                    });
+                    xml.assets.checker = Some(Checker {
+                        name: None,
+                        kind: "testlib".to_string(),
+                        source: Source::cpp("files/check.cpp".to_string()),
+                    });
+                }
+                None => warnings.push(format!(
+                    "{} 没有自定义 checker，导入 Polygon 后需要选择标准 checker",
+                    prob.name
+                )),
+            }
+
+            if let Some(interactor) = &prob.interactor {
+                files.push(OutputFile::File {
+                    path: PathBuf::from(format!("{}/files/interactor.cpp", root)),
+                    bytes: doc.assets.load(prob.idx, interactor).await?,
+                });
+                xml.assets.interactor = Some(Interactor {
+                    source: Source::cpp("files/interactor.cpp".to_string()),
+                });
+            }
+
+            if let Some(validator) = &prob.validator {
+                let path = format!("files/{}", file_name(validator)?);
+                files.push(OutputFile::File {
+                    path: PathBuf::from(format!("{}/{}", root, path)),
+                    bytes: doc.assets.load(prob.idx, validator).await?,
+                });
+                xml.assets.validators = Some(Validators {
+                    validator: vec![Validator {
+                        source: Source::cpp(path),
+                    }],
+                });
+            }
+
+            if let Some(generator) = &prob.generator {
+                let path = format!("files/{}", file_name(generator)?);
+                files.push(OutputFile::File {
+                    path: PathBuf::from(format!("{}/{}", root, path)),
+                    bytes: doc.assets.load(prob.idx, generator).await?,
+                });
+                xml.files.executables = Some(Executables {
+                    executable: vec![Executable {
+                        source: Source::cpp(path),
+                    }],
+                });
+            }
+
+            match prob.problem_type {
+                ProblemType::Program => {}
+                ProblemType::Output => warnings.push(format!(
+                    "{} 是提交答案题，需要在 Polygon 中手动调整",
+                    prob.name
+                )),
+                ProblemType::Interactive if prob.interactor.is_some() => {}
+                ProblemType::Interactive => warnings.push(format!(
+                    "Polygon 不支持 grader 形式的交互题，{} 需要手动编写交互器",
+                    prob.name
+                )),
+                ProblemType::Communication => warnings.push(format!(
+                    "{} 是通信题，需要在 Polygon 中手动配置 manager",
+                    prob.name
+                )),
+            }
+
+            if prob.has_case_limits() {
+                warnings.push(format!(
+                    "{} 的测试点单独限制在该格式中不受支持，已使用题目的限制",
+                    prob.name
+                ));
+            }
+
+            // 题面：Markdown 与图片置于 statements/chinese，图片相对路径保持不变
+            if let Some(ast) = &prob.statement {
+                let (_, images) = rewrite_images(ast.clone(), prob.idx)?;
+                let statement_dir = format!("{}/statements/chinese", root);
+                files.push(OutputFile::File {
+                    path: PathBuf::from(format!("{}/problem.md", statement_dir)),
+                    bytes: Box::new(std::io::Cursor::new(render_markdown(ast).into_bytes())),
+                });
+                let prefix = PathBuf::from(format!("img/{}", prob.idx));
+                let mut seen = std::collections::HashSet::new();
+                for (url, target) in &images {
+                    if !seen.insert(target.clone()) {
+                        continue;
+                    }
+                    let rel = target.strip_prefix(&prefix)?;
+                    files.push(OutputFile::File {
+                        path: PathBuf::from(&statement_dir).join("img").join(rel),
+                        bytes: doc.assets.load(prob.idx, url).await?,
+                    });
+                }
+                xml.statements.statement.push(Statement {
+                    charset: "UTF-8".to_string(),
+                    language: "chinese".to_string(),
+                    path: "statements/chinese/problem.md".to_string(),
+                    mime: "text/markdown".to_string(),
+                });
+            }
+
+            files.push(OutputFile::File {
+                path: PathBuf::from(format!("{}/problem.xml", root)),
+                bytes: Box::new(std::io::Cursor::new(to_xml(&xml)?.into_bytes())),
+            });
+        }
+
+        warnings.push("题面以 Markdown 格式导出，需要在 Polygon 中手动转换为 LaTeX".to_string());
+
+        Ok((files, warnings))
+    }
+}
@@ -0,0 +1,220 @@
+use crate::prelude::*;
+use tuack_lib::dump::{DumpDocument, DumpProblem, Dumper, ScorePolicy};
+use tuack_lib::ren::ProblemType;
+use tuack_lib::utils::output::OutputFile;
+
+#[derive(Serialize)]
+struct SyzojSubtask {
+    score: u32,
+    #[serde(rename = "type")]
+    policy: &'static str,
+    cases: Vec<u32>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyzojSource {
+    language: &'static str,
+    file_name: String,
+}
+
+#[derive(Serialize)]
+struct SyzojExtraFile {
+    name: String,
+    dest: String,
+}
+
+#[derive(Serialize)]
+struct SyzojExtraSource {
+    language: &'static str,
+    files: Vec<SyzojExtraFile>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SyzojData {
+    subtasks: Vec<SyzojSubtask>,
+    input_file: &'static str,
+    output_file: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    special_judge: Option<SyzojSource>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interactor: Option<SyzojSource>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    extra_source_files: Vec<SyzojExtraSource>,
+}
+
+/// 逻辑路径的文件名部分
+fn file_name(path: &Path) -> Result<String> {
+    Ok(path
+        .file_name()
+        .with_context(|| format!("无效的文件路径：{}", path.display()))?
+        .to_string_lossy()
+        .into_owned())
+}
+
+/// SYZOJ / LibreOJ 导出器：每题一个目录，内含 `data.yml` 与拍平后的数据
+pub struct SyzojDumper;
+
+impl SyzojDumper {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn build_data(prob: &DumpProblem, warnings: &mut Vec<String>) -> Result<SyzojData> {
+        let mut subtasks = Vec::new();
+        for task in prob.subtasks.values() {
+            subtasks.push(SyzojSubtask {
+                score: task.max_score,
+                policy: match task.policy {
+                    ScorePolicy::Sum => "sum",
+                    ScorePolicy::Min => "min",
+                    ScorePolicy::Max => {
+                        warnings.push(format!(
+                            "{} 的 max 评分方法在 SYZOJ 中不受支持，已按 min 导出",
+                            prob.name
+                        ));
+                        "min"
+                    }
+                    ScorePolicy::Product => "mul",
+                },
+                cases: task.items.iter().map(|&idx| prob.data[idx].id).collect(),
+            });
+        }
+
+        let special_judge = prob.checker.as_ref().map(|_| SyzojSource {
+            language: "cpp",
+            file_name: "spj.cpp".to_string(),
+        });
+        // 交互器方式由交互器与选手程序对接；grader 方式的交互库随选手程序一同编译
+        let interactor = prob.interactor.as_ref().map(|_| SyzojSource {
+            language: "cpp",
+            file_name: "interactor.cpp".to_string(),
+        });
+        let mut extra_source_files = Vec::new();
+        if let Some(interactive) = &prob.interactive {
+            let mut files = Vec::new();
+            for path in [&interactive.grader, &interactive.header] {
+                let name = file_name(path)?;
+                files.push(SyzojExtraFile {
+                    name: name.clone(),
+                    dest: name,
+                });
+            }
+            extra_source_files.push(SyzojExtraSource {
+                language: "cpp",
+                files,
+            });
+        }
+
+        match prob.problem_type {
+            ProblemType::Program => {}
+            ProblemType::Output => warnings.push(format!(
+                "{} 是提交答案题，需要在 SYZOJ 中手动设置题目类型",
+                prob.name
+            )),
+            ProblemType::Interactive => {
+                if interactor.is_none() && extra_source_files.is_empty() {
+                    warnings.push(format!(
+                        "{} 是交互题但没有交互器或交互库，请手动配置",
+                        prob.name
+                    ));
+                }
+            }
+            ProblemType::Communication => warnings.push(format!(
+                "{} 是通信题，SYZOJ 不支持，已按传统题导出",
+                prob.name
+            )),
+        }
+
+        Ok(SyzojData {
+            subtasks,
+            input_file: "#.in",
+            output_file: "#.ans",
+            special_judge,
+            interactor,
+            extra_source_files,
+        })
+    }
+}
+
+impl Default for SyzojDumper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Dumper for SyzojDumper {
+    async fn dump(&self, doc: &DumpDocument) -> Result<(Vec<OutputFile>, Vec<String>)> {
+        let mut files = Vec::new();
+        let mut warnings = Vec::new();
+
+        for prob in &doc.problems {
+            if !prob.pretest.is_empty() {
+                warnings.push(format!(
+                    "{} 的 pretest 在该格式中不受支持，已忽略",
+                    prob.name
+                ));
+            }
+            if prob.has_case_limits() {
+                warnings.push(format!(
+                    "{} 的测试点单独限制在该格式中不受支持，已使用题目的限制",
+                    prob.name
+                ));
+            }
+            if prob.has_dependencies() {
+                warnings.push(format!(
+                    "{} 的 subtask 依赖在该格式中不受支持，已忽略",
+                    prob.name
+                ));
+            }
+            let root = format!("syzoj/{}", prob.name);
+
+            for case in &prob.data {
+                files.push(OutputFile::File {
+                    path: PathBuf::from(format!("{}/{}.in", root, case.id)),
+                    bytes: doc.assets.load(prob.idx, &case.input).await?,
+                });
+                files.push(OutputFile::File {
+                    path: PathBuf::from(format!("{}/{}.ans", root, case.id)),
+                    bytes: doc.assets.load(prob.idx, &case.output).await?,
+                });
+            }
+
+            if let Some(checker) = &prob.checker {
+                files.push(OutputFile::File {
+                    path: PathBuf::from(format!("{}/spj.cpp", root)),
+                    bytes: doc.assets.load(prob.idx, checker).await?,
+                });
+            }
+
+            if let Some(interactor) = &prob.interactor {
+                files.push(OutputFile::File {
+                    path: PathBuf::from(format!("{}/interactor.cpp", root)),
+                    bytes: doc.assets.load(prob.idx, interactor).await?,
+                });
+            }
+
+            if let Some(interactive) = &prob.interactive {
+                for path in [&interactive.grader, &interactive.header] {
+                    files.push(OutputFile::File {
+                        path: PathBuf::from(format!("{}/{}", root, file_name(path)?)),
+                        bytes: doc.assets.load(prob.idx, path).await?,
+                    });
+                }
+            }
+
+            let data = Self::build_data(prob, &mut warnings)
+                .with_context(|| format!("导出题目 {} 失败", prob.name))?;
+            files.push(OutputFile::File {
+                path: PathBuf::from(format!("{}/data.yml", root)),
+                bytes: Box::new(std::io::Cursor::new(
+                    serde_yaml::to_string(&data)?.into_bytes(),
+                )),
+            });
+        }
+
+        Ok((files, warnings))
+    }
+}
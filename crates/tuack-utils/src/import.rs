@@ -0,0 +1,112 @@
+//! 导入：将其他格式的题目包转换为 tuack-ng 题目文件夹。
+//!
+//! 导入器读取题目包（目录或 zip），复制数据与源文件到目标题目目录，并就地修改
+//! 由调用方从脚手架载入的 `ProblemConfig`，保存与登记由调用方负责。
+
+pub mod polygon;
+pub mod tuack;
+
+use std::io::Read;
+use zip::ZipArchive;
+
+use crate::prelude::*;
+
+/// 题目包：目录或 zip，路径均为相对包根、以 `/` 分隔
+pub enum Package {
+    Dir(PathBuf),
+    Zip {
+        archive: ZipArchive<fs::File>,
+        /// 包根在 zip 内的前缀（如 `aplusb/`，位于根部时为空）
+        prefix: String,
+    },
+}
+
+impl Package {
+    /// 打开题目包，以 `marker` 文件所在位置作为包根
+    pub fn open(path: &Path, marker: &str) -> Result<Self> {
+        if path.is_dir() {
+            if !path.join(marker).exists() {
+                bail!("{} 中没有找到 {}", path.display(), marker);
+            }
+            return Ok(Package::Dir(path.to_path_buf()));
+        }
+
+        let file = fs::File::open(path).with_context(|| format!("打开 {} 失败", path.display()))?;
+        let archive = ZipArchive::new(file).context("读取 zip 失败")?;
+        let prefix = archive
+            .file_names()
+            .filter_map(|name| {
+                let prefix = name.strip_suffix(marker)?;
+                (prefix.is_empty() || prefix.ends_with('/')).then(|| prefix.to_string())
+            })
+            .min_by_key(|prefix| prefix.len())
+            .with_context(|| format!("压缩包中没有找到 {}", marker))?;
+        Ok(Package::Zip { archive, prefix })
+    }
+
+    pub fn exists(&self, rel: &str) -> bool {
+        match self {
+            Package::Dir(root) => root.join(rel).is_file(),
+            Package::Zip { archive, prefix } => archive
+                .index_for_name(&format!("{}{}", prefix, rel))
+                .is_some(),
+        }
+    }
+
+    pub fn read(&mut self, rel: &str) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        match self {
+            Package::Dir(root) => {
+                buf = fs::read(root.join(rel)).with_context(|| format!("读取 {} 失败", rel))?;
+            }
+            Package::Zip { archive, prefix } => {
+                archive
+                    .by_name(&format!("{}{}", prefix, rel))
+                    .with_context(|| format!("读取 {} 失败", rel))?
+                    .read_to_end(&mut buf)?;
+            }
+        }
+        Ok(buf)
+    }
+
+    pub fn read_to_string(&mut self, rel: &str) -> Result<String> {
+        String::from_utf8(self.read(rel)?).with_context(|| format!("{} 不是有效的 UTF-8", rel))
+    }
+
+    /// 复制包内文件到 `dest`，自动创建父目录
+    pub fn copy_to(&mut self, rel: &str, dest: &Path) -> Result<()> {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        match self {
+            Package::Dir(root) => {
+                fs::copy(root.join(rel), dest).with_context(|| format!("复制 {} 失败", rel))?;
+            }
+            Package::Zip { archive, prefix } => {
+                let mut entry = archive
+                    .by_name(&format!("{}{}", prefix, rel))
+                    .with_context(|| format!("读取 {} 失败", rel))?;
+                let mut out = fs::File::create(dest)?;
+                std::io::copy(&mut entry, &mut out)
+                    .with_context(|| format!("复制 {} 失败", rel))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 递归复制目录，目标目录不存在时创建
+pub(crate) fn copy_dir(src: &Path, dst: &Path) -> Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)
+                .with_context(|| format!("复制 {} 失败", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
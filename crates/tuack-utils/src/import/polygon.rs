@@ -0,0 +1,645 @@
+use bytesize::ByteSize;
+use regex::{Captures, Regex};
+
+use super::Package;
+use crate::polygon::{PolygonProblem, Source, Test, Testset, expand_pattern, from_xml};
+use crate::prelude::*;
+use tuack_config::{
+    CheckerConfig, CheckerConfigPair, DataItem, DmkConfig, GeneratorConfig, GeneratorConfigPair,
+    InteractiveConfig, InteractiveMode, ProblemType, ScorePolicy, SingleDataItem, ValidatorConfig,
+    ValidatorConfigPair,
+};
+
+/// 题面章节：`(statement-sections 中的文件名, 标题)`
+const STATEMENT_SECTIONS: &[(&str, &str)] = &[
+    ("legend.tex", "题目描述"),
+    ("input.tex", "输入格式"),
+    ("output.tex", "输出格式"),
+];
+
+/// 可选的题面章节，内容为空时省略
+const OPTIONAL_SECTIONS: &[(&str, &str)] =
+    &[("interaction.tex", "交互方式"), ("notes.tex", "提示")];
+
+/// Polygon 题目包中的 testlib
+const TESTLIB: &str = "files/testlib.h";
+
+/// Polygon 分组换算后的 subtask
+struct GroupInfo {
+    subtask: u32,
+    policy: ScorePolicy,
+    /// 整组得分（`complete-group` 时每个数据点的分值）
+    points: f64,
+}
+
+/// Polygon 题目包导入器
+pub struct PolygonImporter {
+    package: Package,
+    problem: PolygonProblem,
+}
+
+impl PolygonImporter {
+    /// 打开题目包（目录或 zip）并解析 `problem.xml`
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut package = Package::open(path, "problem.xml")?;
+        let problem = from_xml(&package.read_to_string("problem.xml")?)?;
+        Ok(Self { package, problem })
+    }
+
+    /// 包中的题目短名
+    pub fn short_name(&self) -> &str {
+        &self.problem.short_name
+    }
+
+    /// 复制数据与源文件到 `dest`，并写入 `config`；返回导入警告
+    pub fn import(&mut self, dest: &Path, config: &mut ProblemConfig) -> Result<Vec<String>> {
+        let mut warnings = Vec::new();
+        let testset = self
+            .problem
+            .main_testset()
+            .context("problem.xml 中没有测试集")?
+            .clone();
+
+        if let Some(title) = self.problem.title() {
+            config.title = title.to_string();
+        }
+        config.problem_type = ProblemType::Program;
+        config.time_limit = testset.time_limit as f64 / 1000.0;
+        config.memory_limit = ByteSize::b(testset.memory_limit);
+        // 数据直接来自题目包，生成器参数无法迁移
+        config.dmk = DmkConfig::Skip;
+
+        if !self.problem.judging.input_file.is_empty()
+            || !self.problem.judging.output_file.is_empty()
+        {
+            warnings.push("Polygon 题目使用了文件输入输出，请手动检查配置".to_string());
+        }
+
+        self.import_tests(dest, config, &testset, &mut warnings)?;
+        self.import_sources(dest, config, &testset, &mut warnings)?;
+        self.import_statement(dest, config, &mut warnings)?;
+
+        Ok(warnings)
+    }
+
+    /// 将分组映射为 subtask：数字组名直接作为编号，其余依次编号；只含样例的 0 分组忽略
+    fn map_groups(testset: &Testset) -> HashMap<String, GroupInfo> {
+        let groups = match &testset.groups {
+            Some(groups) => &groups.group,
+            None => return HashMap::new(),
+        };
+        let mut next_id = groups
+            .iter()
+            .filter_map(|g| g.name.parse::<u32>().ok())
+            .max()
+            .map_or(0, |id| id + 1);
+
+        let mut map = HashMap::new();
+        for group in groups {
+            let tests: Vec<&Test> = testset
+                .tests
+                .test
+                .iter()
+                .filter(|t| t.group.as_deref() == Some(group.name.as_str()))
+                .collect();
+            let test_points: f64 = tests.iter().filter_map(|t| t.points).sum();
+            let points = group.points.unwrap_or(test_points);
+            if points == 0.0 && tests.iter().all(|t| t.sample == Some(true)) {
+                continue;
+            }
+
+            let subtask = group.name.parse::<u32>().unwrap_or_else(|_| {
+                next_id += 1;
+                next_id - 1
+            });
+            let policy = match group.points_policy.as_str() {
+                "complete-group" => ScorePolicy::Min,
+                _ => ScorePolicy::Sum,
+            };
+            map.insert(
+                group.name.clone(),
+                GroupInfo {
+                    subtask,
+                    policy,
+                    points,
+                },
+            );
+        }
+        map
+    }
+
+    fn copy_test(&mut self, rel: &str, dest: &Path, warnings: &mut Vec<String>) -> Result<()> {
+        if self.package.exists(rel) {
+            self.package.copy_to(rel, dest)
+        } else {
+            warnings.push(format!("题目包中缺少 {}，需要手动补充", rel));
+            Ok(())
+        }
+    }
+
+    fn import_tests(
+        &mut self,
+        dest: &Path,
+        config: &mut ProblemConfig,
+        testset: &Testset,
+        warnings: &mut Vec<String>,
+    ) -> Result<()> {
+        let groups = Self::map_groups(testset);
+
+        config.samples.clear();
+        config.data.clear();
+        config.subtasks.clear();
+
+        for (i, test) in testset.tests.test.iter().enumerate() {
+            let input = expand_pattern(&testset.input_path_pattern, i + 1);
+            let answer = expand_pattern(&testset.answer_path_pattern, i + 1);
+            let group = test.group.as_ref().and_then(|g| groups.get(g));
+
+            if test.sample == Some(true) {
+                let id = config.samples.len() as u32 + 1;
+                self.copy_test(
+                    &input,
+                    &dest.join("sample").join(format!("{}.in", id)),
+                    warnings,
+                )?;
+                self.copy_test(
+                    &answer,
+                    &dest.join("sample").join(format!("{}.ans", id)),
+                    warnings,
+                )?;
+                config.samples.push(SampleItem {
+                    id,
+                    input: None,
+                    output: None,
+                    args: IndexMap::new(),
+                    dmk: None,
+                });
+                // 不计分的样例只作为样例导入
+                if group.is_none() && test.points.unwrap_or(0.0) == 0.0 {
+                    continue;
+                }
+            }
+
+            let id = config.data.len() as u32 + 1;
+            self.copy_test(
+                &input,
+                &dest.join("data").join(format!("{}.in", id)),
+                warnings,
+            )?;
+            self.copy_test(
+                &answer,
+                &dest.join("data").join(format!("{}.ans", id)),
+                warnings,
+            )?;
+
+            let (subtask, score) = match group {
+                Some(group) => {
+                    config.subtasks.insert(group.subtask, group.policy.into());
+                    let score = match group.policy {
+                        ScorePolicy::Min => group.points,
+                        _ => test.points.unwrap_or(0.0),
+                    };
+                    (group.subtask, score)
+                }
+                None => {
+                    config.subtasks.insert(0, ScorePolicy::Sum.into());
+                    (0, test.points.unwrap_or(0.0))
+                }
+            };
+            if score.fract() != 0.0 {
+                warnings.push(format!("测试点 {} 的分值 {} 不是整数，已取整", id, score));
+            }
+
+            config.data.push(DataItem::Single(SingleDataItem {
+                id,
+                score: score.round() as u32,
+                subtask,
+                input: None,
+                output: None,
+                orig_args: IndexMap::new(),
+                dmk: None,
+                command: None,
+                time_limit: None,
+                memory_limit: None,
+            }));
+        }
+
+        // 分组依赖转为 subtask 依赖，依赖被忽略的分组时丢弃
+        if let Some(testset_groups) = &testset.groups {
+            for group in &testset_groups.group {
+                let (Some(info), Some(deps)) = (groups.get(&group.name), &group.dependencies)
+                else {
+                    continue;
+                };
+                let Some(subtask) = config.subtasks.get_mut(&info.subtask) else {
+                    continue;
+                };
+                for dep in &deps.dependency {
+                    match groups.get(&dep.group) {
+                        Some(dep) => subtask.dependencies.push(dep.subtask),
+                        None => warnings.push(format!(
+                            "分组 {} 依赖的分组 {} 未导入，已忽略该依赖",
+                            group.name, dep.group
+                        )),
+                    }
+                }
+            }
+        }
+
+        // 未设置分值（如 ICPC 题目）时平均分配 100 分
+        let total: u32 = config
+            .data
+            .iter()
+            .map(|d| match d {
+                DataItem::Single(item) => item.score,
+                DataItem::Bundle(item) => item.score,
+            })
+            .sum();
+        if total == 0 && !config.data.is_empty() {
+            let count = config.data.len() as u32;
+            for (i, item) in config.data.iter_mut().enumerate() {
+                if let DataItem::Single(item) = item {
+                    item.score = 100 / count + u32::from((i as u32) < 100 % count);
+                }
+            }
+            warnings.push("题目包未设置分值，已平均分配 100 分".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// 复制源文件到题目根目录，返回相对题目目录的文件名
+    fn copy_source(&mut self, source: &Source, dest: &Path) -> Result<Option<String>> {
+        if !self.package.exists(&source.path) {
+            return Ok(None);
+        }
+        let name = Path::new(&source.path)
+            .file_name()
+            .with_context(|| format!("无效的文件路径：{}", source.path))?
+            .to_string_lossy()
+            .into_owned();
+        self.package.copy_to(&source.path, &dest.join(&name))?;
+        Ok(Some(name))
+    }
+
+    /// 题目包带有 testlib 时复制到题目根目录，返回作为源文件依赖的文件名
+    fn copy_testlib(&mut self, dest: &Path) -> Result<Vec<String>> {
+        if !self.package.exists(TESTLIB) {
+            return Ok(Vec::new());
+        }
+        self.package.copy_to(TESTLIB, &dest.join("testlib.h"))?;
+        Ok(vec!["testlib.h".to_string()])
+    }
+
+    fn import_sources(
+        &mut self,
+        dest: &Path,
+        config: &mut ProblemConfig,
+        testset: &Testset,
+        warnings: &mut Vec<String>,
+    ) -> Result<()> {
+        let assets = self.problem.assets.clone();
+        let deps = self.copy_testlib(dest)?;
+
+        config.checker = None;
+        if let Some(checker) = &assets.checker {
+            match self.copy_source(&checker.source, dest)? {
+                Some(source) => {
+                    config.checker = Some(CheckerConfigPair {
+                        data: CheckerConfig {
+                            source,
+                            deps: deps.clone(),
+                            time_limit: None,
+                            memory_limit: None,
+                        },
+                        sample: None,
+                    })
+                }
+                None => warnings.push(format!(
+                    "题目包中缺少 checker 源文件 {}，将使用默认 checker",
+                    checker.source.path
+                )),
+            }
+        }
+
+        // 只能配置一个 validator：全部复制到题目目录，使用第一个
+        config.validator = None;
+        let mut extra_validators = Vec::new();
+        for validator in assets.validators.iter().flat_map(|v| &v.validator) {
+            match self.copy_source(&validator.source, dest)? {
+                Some(source) if config.validator.is_none() => {
+                    config.validator = Some(ValidatorConfigPair {
+                        data: ValidatorConfig {
+                            source,
+                            deps: deps.clone(),
+                        },
+                        sample: None,
+                    })
+                }
+                Some(source) => extra_validators.push(source),
+                None => warnings.push(format!(
+                    "题目包中缺少 validator 源文件 {}",
+                    validator.source.path
+                )),
+            }
+        }
+        if let (Some(validator), false) = (&config.validator, extra_validators.is_empty()) {
+            warnings.push(format!(
+                "题目包有多个 validator，只使用了 {}；{} 已复制到题目目录，需要手动合并",
+                validator.data.source,
+                extra_validators.join("、")
+            ));
+        }
+
+        config.interactive = None;
+        if let Some(interactor) = &assets.interactor {
+            match self.copy_source(&interactor.source, dest)? {
+                Some(source) => {
+                    config.problem_type = ProblemType::Interactive;
+                    config.interactive = Some(InteractiveConfig {
+                        mode: InteractiveMode::Interactor,
+                        grader: String::new(),
+                        header: String::new(),
+                        sample_grader: None,
+                        dmk_grader: None,
+                        interactor: Some(source),
+                        interactor_deps: deps.clone(),
+                    });
+                }
+                None => warnings.push(format!(
+                    "题目包中缺少交互器源文件 {}，已按传统题导入",
+                    interactor.source.path
+                )),
+            }
+        }
+
+        // 生成器：取生成测试命令中引用的可执行文件
+        config.generator = None;
+        let generator_name = testset
+            .tests
+            .test
+            .iter()
+            .filter(|t| t.method == "generated")
+            .find_map(|t| t.cmd.as_deref()?.split_whitespace().next())
+            .map(str::to_string);
+        let executables = self.problem.files.executables.clone().unwrap_or_default();
+        if let Some(name) = generator_name {
+            let executable = executables.executable.iter().find(|e| {
+                Path::new(&e.source.path)
+                    .file_stem()
+                    .is_some_and(|stem| stem.to_string_lossy() == name)
+            });
+            match executable {
+                Some(executable) => {
+                    if let Some(source) = self.copy_source(&executable.source, dest)? {
+                        config.generator = Some(GeneratorConfigPair {
+                            data: GeneratorConfig {
+                                source,
+                                deps: deps.clone(),
+                                validate: false,
+                                script: None,
+                            },
+                            sample: None,
+                        });
+                        warnings
+                            .push("生成器命令行参数未导入，如需使用 dmk 请手动配置".to_string());
+                    }
+                }
+                None => warnings.push(format!("题目包中缺少生成器 {}", name)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 由 statement-sections 转换拼出 statement.md；样例由模板展开
+    fn import_statement(
+        &mut self,
+        dest: &Path,
+        config: &ProblemConfig,
+        warnings: &mut Vec<String>,
+    ) -> Result<()> {
+        let language = ["chinese", "english"].into_iter().find(|lang| {
+            self.package
+                .exists(&format!("statement-sections/{}/legend.tex", lang))
+        });
+        let Some(language) = language else {
+            warnings.push("题目包中没有题面章节，保留默认题面".to_string());
+            return Ok(());
+        };
+
+        // 转换后的章节内容；LaTeX 无法完全转换时记入警告
+        let mut read_section = |name: &str| -> Result<String> {
+            let rel = format!("statement-sections/{}/{}", language, name);
+            if !self.package.exists(&rel) {
+                return Ok(String::new());
+            }
+            let (section, unsupported) = latex_to_markdown(&self.package.read_to_string(&rel)?);
+            if !unsupported.is_empty() {
+                warnings.push(format!(
+                    "题面章节 {} 中有无法转换的 LaTeX（{}），请手动修改 statement.md 的对应部分",
+                    rel,
+                    unsupported.join("、")
+                ));
+            }
+            Ok(section)
+        };
+
+        let mut content = String::new();
+        for (file, title) in STATEMENT_SECTIONS {
+            content.push_str(&format!("## {}\n\n", title));
+            let section = read_section(file)?;
+            if !section.is_empty() {
+                content.push_str(&format!("{}\n\n", section));
+            }
+        }
+        for sample in &config.samples {
+            content.push_str(&format!("{{{{ sample.text({}) }}}}\n\n", sample.id));
+        }
+        for (file, title) in OPTIONAL_SECTIONS {
+            let section = read_section(file)?;
+            if !section.is_empty() {
+                content.push_str(&format!("## {}\n\n{}\n\n", title, section));
+            }
+        }
+        content.push_str(&format!(
+            "## 数据范围\n\n{}\n",
+            read_section("scoring.tex")?
+        ));
+
+        fs::write(dest.join("statement.md"), content)?;
+        Ok(())
+    }
+}
+
+/// 将 Polygon 题面章节中常见的 LaTeX 转为 Markdown，数学公式原样保留。
+///
+/// 返回转换结果与无法转换的命令（原样保留在结果中）。
+fn latex_to_markdown(tex: &str) -> (String, Vec<String>) {
+    let comment = Regex::new(r"(?m)(^|[^\\])%.*$").unwrap();
+    let math = Regex::new(r"(?s)\$\$.*?\$\$|\$(?:\\.|[^$\\])*\$").unwrap();
+    let placeholder = Regex::new(r"\x00(\d+)\x00").unwrap();
+    let env = Regex::new(r"\\begin\{([A-Za-z*]+)\}|\\end\{([A-Za-z*]+)\}|\n?\\item\b\s*").unwrap();
+    let style =
+        Regex::new(r"\\(textbf|textit|emph|texttt|underline|(?:sub)*section\*?)\{([^{}]*)\}")
+            .unwrap();
+    let word = Regex::new(
+        r"\\(ldots|dots|par|newline|noindent|bigskip|medskip|smallskip|quad|qquad)\b\s*",
+    )
+    .unwrap();
+    let command = Regex::new(r"\\[A-Za-z]+").unwrap();
+
+    let mut unsupported = Vec::new();
+    let mut unsupport = |command: &str| {
+        let command = command.trim_end().to_string();
+        if !unsupported.contains(&command) {
+            unsupported.push(command);
+        }
+    };
+
+    // 去掉注释与缩进（缩进在 Markdown 中会变成代码块），公式换成占位符以免被转换
+    let tex = comment.replace_all(tex, "$1");
+    let tex = tex.lines().map(str::trim).collect::<Vec<_>>().join("\n");
+    let mut formulas = Vec::new();
+    let text = math.replace_all(&tex, |caps: &Captures| {
+        formulas.push(caps[0].to_string());
+        format!("\x00{}\x00", formulas.len() - 1)
+    });
+
+    // 当前所在的列表环境，`true` 为有序列表
+    let mut lists = Vec::new();
+    let mut text = env
+        .replace_all(&text, |caps: &Captures| {
+            if let Some(name) = caps.get(1) {
+                match name.as_str() {
+                    "itemize" | "enumerate" => {
+                        lists.push(name.as_str() == "enumerate");
+                        "\n\n".to_string()
+                    }
+                    "center" | "flushleft" => String::new(),
+                    _ => {
+                        unsupport(&caps[0]);
+                        caps[0].to_string()
+                    }
+                }
+            } else if let Some(name) = caps.get(2) {
+                match name.as_str() {
+                    "itemize" | "enumerate" => {
+                        lists.pop();
+                        "\n\n".to_string()
+                    }
+                    "center" | "flushleft" => String::new(),
+                    _ => caps[0].to_string(),
+                }
+            } else {
+                let indent = "   ".repeat(lists.len().saturating_sub(1));
+                match lists.last() {
+                    Some(true) => format!("\n{}1. ", indent),
+                    Some(false) => format!("\n{}- ", indent),
+                    None => {
+                        unsupport(&caps[0]);
+                        caps[0].to_string()
+                    }
+                }
+            }
+        })
+        .into_owned();
+
+    // 由内向外替换嵌套的格式命令
+    loop {
+        let replaced = style.replace_all(&text, |caps: &Captures| {
+            let content = &caps[2];
+            match &caps[1] {
+                "textbf" => format!("**{}**", content),
+                "textit" | "emph" => format!("*{}*", content),
+                "texttt" => format!("`{}`", content),
+                "underline" => content.to_string(),
+                _ => format!("\n\n### {}\n\n", content),
+            }
+        });
+        if replaced == text {
+            break;
+        }
+        text = replaced.into_owned();
+    }
+
+    let mut text = word
+        .replace_all(&text, |caps: &Captures| match &caps[1] {
+            "ldots" | "dots" => "…",
+            "noindent" => "",
+            "quad" | "qquad" => " ",
+            _ => "\n\n",
+        })
+        .into_owned();
+    for (from, to) in [
+        ("\\\\", "\n\n"),
+        ("\\%", "%"),
+        ("\\&", "&"),
+        ("\\#", "#"),
+        ("\\_", "_"),
+        ("\\,", " "),
+        ("---", "—"),
+        ("--", "–"),
+        ("``", "“"),
+        ("''", "”"),
+        ("<<", "«"),
+        (">>", "»"),
+        ("~", " "),
+    ] {
+        text = text.replace(from, to);
+    }
+    for m in command.find_iter(&text) {
+        if !matches!(m.as_str(), "\\begin" | "\\end") {
+            unsupport(m.as_str());
+        }
+    }
+
+    let text = placeholder.replace_all(&text, |caps: &Captures| {
+        formulas[caps[1].parse::<usize>().unwrap()].clone()
+    });
+    let text = text
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = Regex::new(r"\n{3,}")
+        .unwrap()
+        .replace_all(&text, "\n\n")
+        .trim()
+        .to_string();
+    (text, unsupported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::latex_to_markdown;
+
+    #[test]
+    fn converts_text_and_keeps_math() {
+        let tex = "给定 $n$ 个数 \\textbf{$a_i$ 与 \\emph{b}}~--- 求和。% 注释\n\n\\begin{itemize}\n  \\item 第一项 $$\\sum a_i$$\n  \\item 100\\% 的数据\n\\end{itemize}\n结束\\ldots";
+        let (markdown, unsupported) = latex_to_markdown(tex);
+        assert_eq!(
+            markdown,
+            "给定 $n$ 个数 **$a_i$ 与 *b*** — 求和。\n\n- 第一项 $$\\sum a_i$$\n- 100% 的数据\n\n结束…"
+        );
+        assert!(unsupported.is_empty());
+    }
+
+    #[test]
+    fn nested_lists() {
+        let tex = "\\begin{enumerate}\n\\item a\n\\begin{itemize}\n\\item b\n\\end{itemize}\n\\item c\n\\end{enumerate}";
+        let (markdown, _) = latex_to_markdown(tex);
+        assert_eq!(markdown, "1. a\n\n   - b\n\n1. c");
+    }
+
+    #[test]
+    fn reports_unsupported_commands() {
+        let tex = "\\begin{tabular}{|c|}\n1 \\\\ \\hline\n\\end{tabular}\n\\includegraphics{a.png}";
+        let (markdown, unsupported) = latex_to_markdown(tex);
+        assert!(markdown.contains("\\includegraphics{a.png}"));
+        assert_eq!(
+            unsupported,
+            ["\\begin{tabular}", "\\hline", "\\includegraphics"]
+        );
+    }
+}
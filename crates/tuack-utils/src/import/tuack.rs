@@ -0,0 +1,551 @@
+use bytesize::ByteSize;
+use regex::{Captures, Regex};
+use serde_json::Value;
+
+use super::copy_dir;
+use crate::prelude::*;
+use tuack_config::{
+    BundleDataItem, CheckerConfig, CheckerConfigPair, DataItem, DmkConfig, ProblemType,
+    ScorePolicy, SingleDataItem,
+};
+use tuack_lib::utils::testlib::Arg;
+
+/// 原 Tuack 工程的层级
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuackFolder {
+    Contest,
+    Day,
+    Problem,
+}
+
+/// 原 Tuack（配置版本 1～2）工程导入器，每个实例对应一个层级的目录
+pub struct TuackImporter {
+    root: PathBuf,
+    conf: Value,
+    folder: TuackFolder,
+}
+
+/// 取多语言标题：字符串原样返回，对象优先取 `zh-cn`
+fn title_of(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) => Some(s.clone()),
+        Value::Object(map) => map
+            .get("zh-cn")
+            .or_else(|| map.values().next())
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+/// 转换参数表，无法表示的参数跳过并警告
+fn convert_args(value: Option<&Value>, warnings: &mut Vec<String>) -> IndexMap<String, Arg> {
+    let mut args = IndexMap::new();
+    if let Some(Value::Object(map)) = value {
+        for (key, val) in map {
+            match serde_json::from_value::<Arg>(val.clone()) {
+                Ok(arg) => {
+                    args.insert(key.clone(), arg);
+                }
+                Err(_) => warnings.push(format!("参数 {} 的值 {} 无法导入，已跳过", key, val)),
+            }
+        }
+    }
+    args
+}
+
+/// 测试点组单独给出的时间、空间限制，无法识别的空间限制忽略并警告
+fn item_limits(item: &Value, warnings: &mut Vec<String>) -> (Option<f64>, Option<ByteSize>) {
+    let time_limit = item.get("time limit").and_then(|v| v.as_f64());
+    let memory_limit = item
+        .get("memory limit")
+        .and_then(|v| v.as_str())
+        .and_then(|memory| match memory.parse::<ByteSize>() {
+            Ok(m) => Some(m),
+            Err(e) => {
+                warnings.push(format!("无法识别的空间限制 {}：{}，已忽略", memory, e));
+                None
+            }
+        });
+    (time_limit, memory_limit)
+}
+
+/// 解析测试点列表：兼容 `cases`/`id` 字段，以及直接给出数量的旧写法
+fn case_groups(value: Option<&Value>) -> Vec<(Vec<String>, &Value)> {
+    static EMPTY: Value = Value::Null;
+    match value {
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                let cases = match item.get("cases").or_else(|| item.get("id")) {
+                    Some(Value::Array(cases)) => cases.iter().map(case_name).collect(),
+                    Some(case) => vec![case_name(case)],
+                    None => Vec::new(),
+                };
+                (cases, item)
+            })
+            .collect(),
+        Some(Value::Number(n)) => (1..=n.as_u64().unwrap_or(0))
+            .map(|id| (vec![id.to_string()], &EMPTY))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn case_name(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+impl TuackImporter {
+    /// 打开原 Tuack 工程目录（contest/day/problem 任一层级）
+    pub fn open(path: &Path) -> Result<Self> {
+        let conf_path = path.join("conf.json");
+        let content = fs::read_to_string(&conf_path)
+            .with_context(|| format!("读取 {} 失败", conf_path.display()))?;
+        let conf: Value = serde_json::from_str(&content)
+            .with_context(|| format!("解析 {} 失败", conf_path.display()))?;
+
+        let version = conf.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+        if version >= 3 {
+            bail!(
+                "{} 已经是 Tuack-NG 配置文件（版本 {}），无需导入",
+                conf_path.display(),
+                version
+            );
+        }
+
+        let folder = match conf.get("folder").and_then(|v| v.as_str()) {
+            Some("contest") => TuackFolder::Contest,
+            Some("day") => TuackFolder::Day,
+            Some("problem") => TuackFolder::Problem,
+            Some(other) => bail!("不支持导入 {} 层级", other),
+            None => bail!("配置文件缺少 `folder` 字段"),
+        };
+
+        Ok(Self {
+            root: path.to_path_buf(),
+            conf,
+            folder,
+        })
+    }
+
+    pub fn folder(&self) -> TuackFolder {
+        self.folder
+    }
+
+    /// 目录名（原配置中的 name，缺省为目录名）
+    pub fn name(&self) -> String {
+        self.conf
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| {
+                self.root
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+            })
+            .unwrap_or_default()
+    }
+
+    /// 子目录导入器
+    pub fn children(&self) -> Result<Vec<TuackImporter>> {
+        let subdirs = match self.conf.get("subdir") {
+            Some(Value::Array(dirs)) => dirs.iter().filter_map(|d| d.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        subdirs
+            .into_iter()
+            .map(|dir| TuackImporter::open(&self.root.join(dir)))
+            .collect()
+    }
+
+    pub fn import_contest(&self, config: &mut ContestConfig) -> Vec<String> {
+        if let Some(title) = title_of(self.conf.get("title")) {
+            config.short_title = title.clone();
+            config.title = title;
+        }
+        if let Some(title) = title_of(self.conf.get("short title")) {
+            config.short_title = title;
+        }
+        config.name = self.name();
+        Vec::new()
+    }
+
+    pub fn import_day(&self, config: &mut ContestDayConfig) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if let Some(title) = title_of(self.conf.get("title")) {
+            config.title = title;
+        }
+        config.name = self.name();
+        if let Some(Value::Object(compile)) = self.conf.get("compile") {
+            config.compile = compile
+                .iter()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect();
+        }
+        for (key, field) in [
+            ("start time", &mut config.start_time),
+            ("end time", &mut config.end_time),
+        ] {
+            if let Some(value) = self.conf.get(key) {
+                match serde_json::from_value::<[u32; 6]>(value.clone()) {
+                    Ok(time) => *field = Some(time),
+                    Err(_) => warnings.push(format!("无法识别的 {}：{}", key, value)),
+                }
+            }
+        }
+        warnings
+    }
+
+    /// 复制题目文件到 `dest` 并写入 `config`；返回导入警告
+    pub fn import_problem(&self, dest: &Path, config: &mut ProblemConfig) -> Result<Vec<String>> {
+        let mut warnings = Vec::new();
+
+        config.name = self.name();
+        if let Some(title) = title_of(self.conf.get("title").or(self.conf.get("cnname"))) {
+            config.title = title;
+        }
+        config.problem_type = match self.conf.get("type").and_then(|v| v.as_str()) {
+            Some("output") => ProblemType::Output,
+            Some("program") | None => ProblemType::Program,
+            Some(other) => {
+                warnings.push(format!("题目类型 {} 无法导入，已按传统题处理", other));
+                ProblemType::Program
+            }
+        };
+        if let Some(time) = self.conf.get("time limit").and_then(|v| v.as_f64()) {
+            config.time_limit = time;
+        }
+        if let Some(memory) = self.conf.get("memory limit").and_then(|v| v.as_str()) {
+            config.memory_limit = memory
+                .parse::<ByteSize>()
+                .map_err(|e| anyhow!("无法识别的空间限制 {}：{}", memory, e))?;
+        }
+        config.args = convert_args(self.conf.get("args"), &mut warnings);
+        // 原 Tuack 的数据已经生成好，直接使用
+        config.dmk = DmkConfig::Skip;
+
+        self.import_samples(dest, config, &mut warnings)?;
+        self.import_data(dest, config, &mut warnings)?;
+
+        // 原 Tuack 将自定义 checker 放在 data/chk/chk.cpp
+        config.checker = self
+            .root
+            .join("data/chk/chk.cpp")
+            .exists()
+            .then(|| CheckerConfigPair {
+                data: CheckerConfig {
+                    source: "data/chk/chk.cpp".to_string(),
+                    deps: Vec::new(),
+                    time_limit: None,
+                    memory_limit: None,
+                },
+                sample: None,
+            });
+
+        self.import_pretest(dest, config, &mut warnings)?;
+        if self.conf.get("users").is_some() {
+            warnings.push(
+                "users 配置未导入，可复制题解后使用 `tuack-ng gen code` 自动检测".to_string(),
+            );
+        }
+
+        for dir in ["tables", "resources"] {
+            let src = self.root.join(dir);
+            if src.is_dir() {
+                // 原 Tuack 的图片位于 resources，Tuack-NG 位于 img
+                let target = if dir == "resources" { "img" } else { dir };
+                copy_dir(&src, &dest.join(target))?;
+            }
+        }
+
+        self.import_statement(dest, config, &mut warnings)?;
+
+        Ok(warnings)
+    }
+
+    fn import_samples(
+        &self,
+        dest: &Path,
+        config: &mut ProblemConfig,
+        warnings: &mut Vec<String>,
+    ) -> Result<()> {
+        config.samples.clear();
+        let down = self.root.join("down");
+        let mut sample_files = Vec::new();
+
+        for (cases, item) in case_groups(self.conf.get("samples")) {
+            let args = convert_args(item.get("args"), warnings);
+            for case in cases {
+                let id = match case.parse::<u32>() {
+                    Ok(id) => id,
+                    Err(_) => config.samples.len() as u32 + 1,
+                };
+                let (input, output) = (format!("{}.in", case), format!("{}.ans", case));
+                for file in [&input, &output] {
+                    let src = down.join(file);
+                    if src.exists() {
+                        fs::create_dir_all(dest.join("sample"))?;
+                        fs::copy(&src, dest.join("sample").join(file))?;
+                    } else {
+                        warnings.push(format!("样例文件 down/{} 不存在", file));
+                    }
+                }
+                let named = id.to_string() != case;
+                config.samples.push(SampleItem {
+                    id,
+                    input: named.then(|| input.clone()),
+                    output: named.then(|| output.clone()),
+                    args: args.clone(),
+                    dmk: None,
+                });
+                sample_files.push(input);
+                sample_files.push(output);
+            }
+        }
+
+        // down 中其余文件作为附加下发文件保留
+        if down.is_dir() {
+            for entry in fs::read_dir(&down)? {
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if sample_files.contains(&name) {
+                    continue;
+                }
+                let target = dest.join("down").join(&name);
+                if entry.file_type()?.is_dir() {
+                    copy_dir(&entry.path(), &target)?;
+                } else {
+                    fs::create_dir_all(dest.join("down"))?;
+                    fs::copy(entry.path(), target)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `packed` 时每个数据组为一个整组得分的 subtask，否则全部归入 subtask 0 求和
+    fn import_data(
+        &self,
+        dest: &Path,
+        config: &mut ProblemConfig,
+        warnings: &mut Vec<String>,
+    ) -> Result<()> {
+        config.data.clear();
+        config.subtasks.clear();
+
+        let data_dir = self.root.join("data");
+        if data_dir.is_dir() {
+            copy_dir(&data_dir, &dest.join("data"))?;
+        }
+
+        let packed = self
+            .conf
+            .get("packed")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let mut next_id = 1;
+
+        for (idx, (cases, item)) in case_groups(self.conf.get("data")).into_iter().enumerate() {
+            let score = item.get("score").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            if score.fract() != 0.0 {
+                warnings.push(format!("分值 {} 不是整数，已取整", score));
+            }
+            let score = score.round() as u32;
+            let args = convert_args(item.get("args"), warnings);
+            let (time_limit, memory_limit) = item_limits(item, warnings);
+            let subtask = if packed { idx as u32 + 1 } else { 0 };
+            config.subtasks.insert(
+                subtask,
+                if packed {
+                    ScorePolicy::Min
+                } else {
+                    ScorePolicy::Sum
+                }
+                .into(),
+            );
+
+            let ids: Option<Vec<i32>> = cases.iter().map(|c| c.parse::<i32>().ok()).collect();
+            match ids {
+                Some(ids) if ids.len() > 1 => {
+                    next_id = next_id.max(*ids.iter().max().unwrap() as u32 + 1);
+                    config.data.push(DataItem::Bundle(BundleDataItem {
+                        id: ids,
+                        score,
+                        subtask,
+                        orig_args: args,
+                        dmk: None,
+                        command: None,
+                        time_limit,
+                        memory_limit,
+                    }));
+                }
+                _ => {
+                    for case in cases {
+                        let (id, named) = match case.parse::<u32>() {
+                            Ok(id) => (id, false),
+                            Err(_) => (next_id, true),
+                        };
+                        next_id = next_id.max(id + 1);
+                        config.data.push(DataItem::Single(SingleDataItem {
+                            id,
+                            score,
+                            subtask,
+                            input: named.then(|| format!("{}.in", case)),
+                            output: named.then(|| format!("{}.ans", case)),
+                            orig_args: args.clone(),
+                            dmk: None,
+                            command: None,
+                            time_limit,
+                            memory_limit,
+                        }));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// pretest 逐点导入，归入第一个 subtask 并沿用其评分策略
+    fn import_pretest(
+        &self,
+        dest: &Path,
+        config: &mut ProblemConfig,
+        warnings: &mut Vec<String>,
+    ) -> Result<()> {
+        config.pretest.clear();
+        if self.conf.get("pre").is_none() {
+            return Ok(());
+        }
+
+        let pre_dir = self.root.join("pre");
+        if pre_dir.is_dir() {
+            copy_dir(&pre_dir, &dest.join("pre"))?;
+        }
+
+        let subtask = config.subtasks.keys().next().copied().unwrap_or(0);
+        config.subtasks.entry(subtask).or_default();
+        if subtask != 0 {
+            warnings.push(format!(
+                "pretest 已全部归入 subtask {}，请按需调整",
+                subtask
+            ));
+        }
+
+        let mut next_id = 1;
+        for (cases, item) in case_groups(self.conf.get("pre")) {
+            let score = item.get("score").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let args = convert_args(item.get("args"), warnings);
+            let (time_limit, memory_limit) = item_limits(item, warnings);
+            for case in cases {
+                let (id, named) = match case.parse::<u32>() {
+                    Ok(id) => (id, false),
+                    Err(_) => (next_id, true),
+                };
+                next_id = next_id.max(id + 1);
+                config.pretest.push(DataItem::Single(SingleDataItem {
+                    id,
+                    score: score.round() as u32,
+                    subtask,
+                    input: named.then(|| format!("{}.in", case)),
+                    output: named.then(|| format!("{}.ans", case)),
+                    orig_args: args.clone(),
+                    dmk: None,
+                    command: None,
+                    time_limit,
+                    memory_limit,
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    fn import_statement(
+        &self,
+        dest: &Path,
+        config: &ProblemConfig,
+        warnings: &mut Vec<String>,
+    ) -> Result<()> {
+        let statement_dir = self.root.join("statement");
+        let source = ["zh-cn.md", "zh_cn.md"]
+            .iter()
+            .map(|name| statement_dir.join(name))
+            .find(|p| p.exists());
+        let Some(source) = source else {
+            warnings.push("没有找到 statement/zh-cn.md，保留默认题面".to_string());
+            return Ok(());
+        };
+
+        let (content, mut rewrite_warnings) =
+            rewrite_statement(&fs::read_to_string(&source)?, &config.name);
+        warnings.append(&mut rewrite_warnings);
+        fs::write(dest.join("statement.md"), content)?;
+        Ok(())
+    }
+}
+
+/// 将原 Tuack 题面中的 Jinja 调用改写为 Tuack-NG `render_template` 支持的形式；
+/// 无法改写的调用原样保留并给出警告
+pub fn rewrite_statement(content: &str, problem_name: &str) -> (String, Vec<String>) {
+    let expr = Regex::new(r"\{\{-?\s*(.*?)\s*-?\}\}").unwrap();
+    let call = Regex::new(r#"^(\w+(?:\.\w+)*)\((.*)\)$"#).unwrap();
+    let quoted = Regex::new(r#"^['"](.*?)['"]"#).unwrap();
+    let prob = Regex::new(r"\bprob\b").unwrap();
+
+    let mut warnings = Vec::new();
+    let mut sample_id = 0;
+
+    let result = expr.replace_all(content, |caps: &Captures| {
+        let inner = caps[1].trim();
+        let (func, args) = match call.captures(inner) {
+            Some(c) => (c[1].to_string(), c[2].trim().to_string()),
+            None => (String::new(), String::new()),
+        };
+        let first_arg = quoted
+            .captures(&args)
+            .map(|c| c[1].to_string())
+            .unwrap_or_default();
+
+        match func.as_str() {
+            "s" | "self.s" if first_arg == "input file" => "{{ s.input_file() }}".to_string(),
+            "s" | "self.s" if first_arg == "output file" => "{{ s.output_file() }}".to_string(),
+            "self.sample_text" | "sample_text" => {
+                sample_id += 1;
+                format!("{{{{ sample.text({}) }}}}", sample_id)
+            }
+            "self.sample_file" | "sample_file" => {
+                sample_id += 1;
+                format!("{{{{ sample.file({}) }}}}", sample_id)
+            }
+            "self.title_sample_description" | "title_sample_description" => {
+                format!("## 样例 {} 解释", sample_id.max(1))
+            }
+            "img" | "self.img" => format!("![](img/{})", first_arg),
+            "down_file" | "self.down_file" => {
+                format!("见选手目录下的 _{}/{}_。", problem_name, first_arg)
+            }
+            "tbl" | "self.tbl" => {
+                warnings.push(format!(
+                    "Python 表格 {} 需要改写为 Lua 表格，并使用 s.table 引用",
+                    first_arg
+                ));
+                format!(
+                    "<!-- TODO: 原 Tuack 表格 tables/{}.py 需改写为 Lua 表格 -->",
+                    first_arg
+                )
+            }
+            _ => {
+                let rewritten = prob.replace_all(inner, "problem").into_owned();
+                if rewritten.contains("self.") || rewritten.starts_with("s(") {
+                    warnings.push(format!("无法自动改写的模板调用：{{{{ {} }}}}", inner));
+                }
+                format!("{{{{ {} }}}}", rewritten)
+            }
+        }
+    });
+
+    (result.into_owned(), warnings)
+}
@@ -5,7 +5,10 @@ pub mod compilers;
 pub mod data;
 pub mod doc;
 pub mod dump;
+pub mod import;
+pub mod polygon;
 pub mod prelude;
 pub mod process;
 pub mod ren;
+pub mod sandbox;
 pub mod validators;
@@ -0,0 +1,264 @@
+//! Codeforces Polygon 题目包 `problem.xml` 的数据模型，导出与导入共用。
+//!
+//! 只覆盖 tuack-ng 关心的字段，其余元素在反序列化时忽略。
+
+use crate::prelude::*;
+
+/// `problem.xml` 根元素
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename = "problem")]
+pub struct PolygonProblem {
+    #[serde(rename = "@revision", default)]
+    pub revision: u32,
+    #[serde(rename = "@short-name")]
+    pub short_name: String,
+    #[serde(default)]
+    pub names: Names,
+    #[serde(default)]
+    pub statements: Statements,
+    pub judging: Judging,
+    #[serde(default)]
+    pub files: Files,
+    #[serde(default)]
+    pub assets: Assets,
+}
+
+impl PolygonProblem {
+    /// 按语言优先级取题目标题
+    pub fn title(&self) -> Option<&str> {
+        self.names
+            .name
+            .iter()
+            .find(|n| n.language == "chinese")
+            .or_else(|| self.names.name.first())
+            .map(|n| n.value.as_str())
+    }
+
+    /// 主测试集（名为 `tests`，缺省取第一个）
+    pub fn main_testset(&self) -> Option<&Testset> {
+        self.judging
+            .testset
+            .iter()
+            .find(|t| t.name == "tests")
+            .or_else(|| self.judging.testset.first())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Names {
+    #[serde(default)]
+    pub name: Vec<Name>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Name {
+    #[serde(rename = "@language")]
+    pub language: String,
+    #[serde(rename = "@value")]
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Statements {
+    #[serde(default)]
+    pub statement: Vec<Statement>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Statement {
+    #[serde(rename = "@charset", default)]
+    pub charset: String,
+    #[serde(rename = "@language")]
+    pub language: String,
+    #[serde(rename = "@path")]
+    pub path: String,
+    #[serde(rename = "@type")]
+    pub mime: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Judging {
+    #[serde(rename = "@input-file", default)]
+    pub input_file: String,
+    #[serde(rename = "@output-file", default)]
+    pub output_file: String,
+    #[serde(default)]
+    pub testset: Vec<Testset>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Testset {
+    #[serde(rename = "@name")]
+    pub name: String,
+    /// 毫秒
+    #[serde(rename = "time-limit")]
+    pub time_limit: u64,
+    /// 字节
+    #[serde(rename = "memory-limit")]
+    pub memory_limit: u64,
+    #[serde(rename = "test-count")]
+    pub test_count: usize,
+    /// printf 风格路径模板，如 `tests/%02d`
+    #[serde(rename = "input-path-pattern")]
+    pub input_path_pattern: String,
+    #[serde(rename = "answer-path-pattern")]
+    pub answer_path_pattern: String,
+    #[serde(default)]
+    pub tests: Tests,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Groups>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Tests {
+    #[serde(default)]
+    pub test: Vec<Test>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Test {
+    #[serde(rename = "@method", default)]
+    pub method: String,
+    #[serde(rename = "@cmd", default, skip_serializing_if = "Option::is_none")]
+    pub cmd: Option<String>,
+    #[serde(rename = "@sample", default, skip_serializing_if = "Option::is_none")]
+    pub sample: Option<bool>,
+    #[serde(rename = "@points", default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<f64>,
+    #[serde(rename = "@group", default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Groups {
+    #[serde(default)]
+    pub group: Vec<Group>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Group {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "@points", default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<f64>,
+    /// `each-test` 或 `complete-group`
+    #[serde(rename = "@points-policy", default)]
+    pub points_policy: String,
+    #[serde(rename = "@feedback-policy", default)]
+    pub feedback_policy: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<Dependencies>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Dependencies {
+    #[serde(default)]
+    pub dependency: Vec<Dependency>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency {
+    #[serde(rename = "@group")]
+    pub group: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Files {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executables: Option<Executables>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Executables {
+    #[serde(default)]
+    pub executable: Vec<Executable>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Executable {
+    pub source: Source,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Source {
+    #[serde(rename = "@path")]
+    pub path: String,
+    #[serde(rename = "@type", default)]
+    pub lang: String,
+}
+
+impl Source {
+    /// C++ 源文件（Polygon 语言标识）
+    pub fn cpp(path: String) -> Self {
+        Self {
+            path,
+            lang: "cpp.g++17".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Assets {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checker: Option<Checker>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interactor: Option<Interactor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validators: Option<Validators>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checker {
+    #[serde(rename = "@name", default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "@type", default)]
+    pub kind: String,
+    pub source: Source,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interactor {
+    pub source: Source,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Validators {
+    #[serde(default)]
+    pub validator: Vec<Validator>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Validator {
+    pub source: Source,
+}
+
+/// 展开 printf 风格的 `%d`/`%0Nd` 路径模板
+pub fn expand_pattern(pattern: &str, index: usize) -> String {
+    let re = regex::Regex::new(r"%(0?)(\d*)d").unwrap();
+    re.replace(pattern, |caps: &regex::Captures| {
+        let width: usize = caps[2].parse().unwrap_or(0);
+        if &caps[1] == "0" {
+            format!("{:0width$}", index, width = width)
+        } else {
+            format!("{:width$}", index, width = width)
+        }
+    })
+    .into_owned()
+}
+
+/// 解析 `problem.xml`
+pub fn from_xml(xml: &str) -> Result<PolygonProblem> {
+    quick_xml::de::from_str(xml).context("解析 problem.xml 失败")
+}
+
+/// 序列化为 `problem.xml`
+pub fn to_xml(problem: &PolygonProblem) -> Result<String> {
+    let mut body = String::new();
+    let mut ser = quick_xml::se::Serializer::new(&mut body);
+    ser.indent(' ', 2);
+    Serialize::serialize(problem, ser).context("生成 problem.xml 失败")?;
+    Ok(format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\" standalone=\"no\"?>\n{}\n",
+        body
+    ))
+}
@@ -1,12 +1,143 @@
 use std::cmp::max;
+use std::process::{Command as StdCommand, ExitStatus};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::process::{Child, Command as TokioCommand};
 use tokio::time::{Instant, sleep};
 
 use sysinfo::{Pid, ProcessesToUpdate, System};
 
 use crate::prelude::*;
-use tuack_lib::utils::compiler::{ResourceLimits, RunStatus};
+use crate::sandbox::Sandbox;
+use tuack_lib::utils::compiler::{ExecBackend, ResourceLimits, RunStatus};
+
+/// 进程运行结果（不含输出流）。
+pub struct ProcessOutcome {
+    pub status: RunStatus,
+    /// 用户态 + 内核态 CPU 时间，TLE/MLE 或平台不支持时为 `None`。
+    pub cpu_time: Option<Duration>,
+    /// 墙钟时间，TLE/MLE 时为 `None`。
+    pub wall_time: Option<Duration>,
+    /// 峰值内存（字节）。
+    pub memory: Option<u64>,
+}
+
+/// 按执行后端运行命令。
+///
+/// `workdir` 为程序的工作目录，沙箱中仅该目录可写。
+pub async fn execute_command(
+    cmd: StdCommand,
+    limits: ResourceLimits,
+    backend: ExecBackend,
+    workdir: &Path,
+) -> Result<ProcessOutcome> {
+    match backend {
+        ExecBackend::Supervisor => {
+            let mut child = TokioCommand::from(cmd).spawn()?;
+            ProcessSupervisor::new(limits).supervise(&mut child).await
+        }
+        ExecBackend::Sandbox => Sandbox::new(limits, workdir).run(cmd).await,
+    }
+}
+
+/// 监控线程发现超限后，再宽限一段时间才结束进程，避免采样误差导致提前结束
+const KILL_GRACE: Duration = Duration::from_millis(200);
+
+/// 在不回收子进程的前提下等待其退出，并读取其 CPU 时间
+///
+/// 子进程只由 tokio 的 [`Child`] 回收，结束进程与等待退出共用同一个回收者。
+#[cfg(target_os = "linux")]
+struct ExitWatch {
+    pid: libc::pid_t,
+    /// 进程退出时可读的 pidfd，内核不支持时轮询
+    pidfd: Option<tokio::io::unix::AsyncFd<std::os::fd::OwnedFd>>,
+}
+
+#[cfg(target_os = "linux")]
+impl ExitWatch {
+    fn new(pid: u32) -> Self {
+        use std::os::fd::{FromRawFd, OwnedFd};
+
+        // SAFETY: pidfd_open 只读取参数，成功时返回新的文件描述符
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+        let pidfd = (fd >= 0)
+            .then(|| unsafe { OwnedFd::from_raw_fd(fd as i32) })
+            .and_then(|fd| tokio::io::unix::AsyncFd::new(fd).ok());
+        Self {
+            pid: pid as libc::pid_t,
+            pidfd,
+        }
+    }
+
+    /// 进程已退出时返回其 CPU 时间（user + sys，含已回收的子进程），不回收进程
+    fn peek(&self) -> Result<Option<Duration>> {
+        // SAFETY: siginfo 与 rusage 为纯数据结构，全零是合法值
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        loop {
+            // glibc 的 waitid 不提供 rusage 参数，直接调用系统调用
+            let ret = unsafe {
+                libc::syscall(
+                    libc::SYS_waitid,
+                    libc::P_PID,
+                    self.pid,
+                    &mut info as *mut libc::siginfo_t,
+                    libc::WEXITED | libc::WNOWAIT | libc::WNOHANG,
+                    &mut usage as *mut libc::rusage,
+                )
+            };
+            if ret >= 0 {
+                break;
+            }
+            let err = std::io::Error::last_os_error();
+            if err.kind() != std::io::ErrorKind::Interrupted {
+                return Err(err.into());
+            }
+        }
+        // WNOHANG 下进程尚未退出时 si_pid 为 0
+        if unsafe { info.si_pid() } == 0 {
+            return Ok(None);
+        }
+        let timeval = |tv: libc::timeval| {
+            Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+        };
+        Ok(Some(timeval(usage.ru_utime) + timeval(usage.ru_stime)))
+    }
+
+    /// 等待进程退出（不回收），返回其 CPU 时间
+    async fn exited(&self) -> Result<Option<Duration>> {
+        loop {
+            match &self.pidfd {
+                Some(pidfd) => {
+                    let mut guard = pidfd.readable().await?;
+                    if let Some(cpu_time) = self.peek()? {
+                        return Ok(Some(cpu_time));
+                    }
+                    guard.clear_ready();
+                }
+                None => {
+                    if let Some(cpu_time) = self.peek()? {
+                        return Ok(Some(cpu_time));
+                    }
+                    sleep(Duration::from_millis(5)).await;
+                }
+            }
+        }
+    }
+}
+
+/// 等待子进程退出，返回退出状态与 CPU 时间；回收由 tokio 完成。
+#[cfg(target_os = "linux")]
+async fn wait_exit(child: &mut Child, watch: &ExitWatch) -> Result<(ExitStatus, Option<Duration>)> {
+    let cpu_time = watch.exited().await?;
+    Ok((child.wait().await?, cpu_time))
+}
+
+/// 等待子进程退出；该平台无法取得 CPU 时间。
+#[cfg(not(target_os = "linux"))]
+async fn wait_exit(child: &mut Child) -> Result<(ExitStatus, Option<Duration>)> {
+    Ok((child.wait().await?, None))
+}
 
 /// 已 spawn 子进程的 TLE/MLE 监控器。
 pub struct ProcessSupervisor {
@@ -18,17 +149,20 @@ impl ProcessSupervisor {
         Self { limits }
     }
 
-    /// 监控子进程，返回结束状态、用时和峰值内存。
-    pub async fn supervise(
-        self,
-        child: &mut tokio::process::Child,
-    ) -> Result<(RunStatus, Option<Duration>, Option<u64>)> {
+    /// 监控子进程，返回结束状态、CPU 时间、墙钟时间和峰值内存。
+    pub async fn supervise(self, child: &mut Child) -> Result<ProcessOutcome> {
         let pid = child.id().context("无法获取进程 PID")?;
         let start = Instant::now();
-        let time_limit = self.limits.time_limit.unwrap_or(Duration::MAX);
-        let memory_limit = self.limits.memory_limit.unwrap_or(u64::MAX);
+        let limits = &self.limits;
+        let memory_limit = limits.memory_limit.unwrap_or(u64::MAX);
         let peak_memory = Arc::new(Mutex::new(0u64));
         let monitoring_peak = Arc::clone(&peak_memory);
+        #[cfg(target_os = "linux")]
+        let watch = ExitWatch::new(pid);
+        #[cfg(target_os = "linux")]
+        let exit = wait_exit(child, &watch);
+        #[cfg(not(target_os = "linux"))]
+        let exit = wait_exit(child);
 
         let result = tokio::select! {
             biased;
@@ -38,59 +172,96 @@ impl ProcessSupervisor {
                 let sys_pid = Pid::from_u32(pid);
                 loop {
                     sleep(Duration::from_millis(20)).await;
-                    if start.elapsed() > time_limit.saturating_add(Duration::from_millis(200)) {
-                        return;
-                    }
                     sys.refresh_processes(ProcessesToUpdate::Some(&[sys_pid]), false);
-                    if let Some(process) = sys.process(sys_pid) {
-                        let memory = process.memory();
+                    // 进程已退出，等待 `wait_exit` 返回
+                    let Some(process) = sys.process(sys_pid) else {
+                        continue;
+                    };
+                    let memory = process.memory();
+                    {
                         let mut peak = monitoring_peak.lock().unwrap();
                         *peak = max(*peak, memory);
-                        if memory > memory_limit {
-                            return;
-                        }
-                    } else {
+                    }
+                    if memory > memory_limit {
+                        return;
+                    }
+                    let cpu_time = Duration::from_millis(process.accumulated_cpu_time());
+                    if limits.exceeds_time(
+                        Some(cpu_time.saturating_sub(KILL_GRACE)),
+                        start.elapsed().saturating_sub(KILL_GRACE),
+                    ) {
                         return;
                     }
                 }
             } => {
                 let _ = child.kill().await;
                 let final_peak = *peak_memory.lock().unwrap();
-                if start.elapsed() > time_limit {
-                    info!("进程超时：{}", start.elapsed().as_secs_f64());
-                    (RunStatus::TimeLimitExceeded, None, Some(final_peak))
-                } else {
+                if final_peak > memory_limit {
                     info!("进程内存超限");
-                    (RunStatus::MemoryLimitExceeded, None, Some(final_peak))
+                    ProcessOutcome {
+                        status: RunStatus::MemoryLimitExceeded,
+                        cpu_time: None,
+                        wall_time: None,
+                        memory: Some(final_peak),
+                    }
+                } else {
+                    info!("进程超时：{}", start.elapsed().as_secs_f64());
+                    ProcessOutcome {
+                        status: RunStatus::TimeLimitExceeded,
+                        cpu_time: None,
+                        wall_time: None,
+                        memory: Some(final_peak),
+                    }
                 }
             }
 
-            exit_status = child.wait() => {
+            exit = exit => {
                 let elapsed = start.elapsed();
                 let final_peak = *peak_memory.lock().unwrap();
 
+                let (exit_status, cpu_time) = match exit {
+                    Ok(exit) => exit,
+                    Err(e) => {
+                        return Ok(ProcessOutcome {
+                            status: RunStatus::InternalError(e),
+                            cpu_time: None,
+                            wall_time: None,
+                            memory: None,
+                        });
+                    }
+                };
+
                 // 判断是否超时
-                if elapsed > time_limit {
-                    return Ok((RunStatus::TimeLimitExceeded, None, Some(final_peak)));
+                if limits.exceeds_time(cpu_time, elapsed) {
+                    return Ok(ProcessOutcome {
+                        status: RunStatus::TimeLimitExceeded,
+                        cpu_time: None,
+                        wall_time: None,
+                        memory: Some(final_peak),
+                    });
                 }
 
                 // 判断是否内存超限
                 if final_peak > memory_limit {
-                    return Ok((RunStatus::MemoryLimitExceeded, None, Some(final_peak)));
+                    return Ok(ProcessOutcome {
+                        status: RunStatus::MemoryLimitExceeded,
+                        cpu_time: None,
+                        wall_time: None,
+                        memory: Some(final_peak),
+                    });
                 }
 
-                info!("进程结束，耗时：{:?}, 峰值内存：{}, 退出码：{:?}",
-                    elapsed, final_peak, exit_status.as_ref().ok().and_then(|s| s.code()));
-                match exit_status {
-                    Ok(status) if status.success() => {
-                        (RunStatus::Success, Some(elapsed), Some(final_peak))
-                    }
-                    Ok(status) => {
-                        (RunStatus::NonZeroExit(status.code().unwrap_or(-1)), Some(elapsed), Some(final_peak))
-                    }
-                    Err(e) => {
-                        (RunStatus::InternalError(e.into()), None, None)
-                    }
+                info!("进程结束，CPU 时间：{:?}，墙钟时间：{:?}, 峰值内存：{}, 退出码：{:?}",
+                    cpu_time, elapsed, final_peak, exit_status.code());
+                ProcessOutcome {
+                    status: if exit_status.success() {
+                        RunStatus::Success
+                    } else {
+                        RunStatus::NonZeroExit(exit_status.code().unwrap_or(-1))
+                    },
+                    cpu_time,
+                    wall_time: Some(elapsed),
+                    memory: Some(final_peak),
                 }
             }
         };
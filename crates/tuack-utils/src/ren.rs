@@ -1,3 +1,4 @@
+pub mod html;
 pub mod lua;
 pub mod manifest;
 pub mod markdown;
@@ -0,0 +1,435 @@
+use crate::prelude::*;
+use crate::ren::manifest::TemplateManifest;
+use crate::ren::renderers::{rewrite_images, unwrap_template};
+use crate::ren::template::{Label, Locale};
+use std::collections::HashSet;
+use tuack_lib::ren::{DateInfo, Problem, ProblemMeta, ProblemType, RenderDocument, Renderer};
+use tuack_lib::utils::output::OutputFile;
+use tuack_ng_parser::ast::Document;
+use tuack_ng_parser::printers::html::escape_html;
+use tuack_ng_parser::printers::render_html;
+use tuack_ng_parser::transform::Transform;
+
+/// 模板未提供 `style.css` 时使用的默认样式
+const DEFAULT_STYLE: &str = r#"body {
+  max-width: 960px;
+  margin: 0 auto;
+  padding: 1em 2em;
+  font-family: "Noto Serif CJK SC", "Source Han Serif SC", serif;
+  line-height: 1.6;
+}
+nav { margin-bottom: 1em; }
+table { border-collapse: collapse; margin: 1em auto; }
+th, td { border: 1px solid #888; padding: 0.3em 0.8em; }
+pre { background: #f5f5f5; padding: 0.8em; overflow-x: auto; }
+code { font-family: "Noto Sans Mono CJK SC", monospace; }
+img { max-width: 100%; }
+figure { text-align: center; }
+.math-display { overflow-x: auto; }
+.meta td:first-child { font-weight: bold; }
+"#;
+
+/// 模板中插入每个页面 `<head>` 的片段，不作为产物输出
+const HEAD_FILE: &str = "head.html";
+
+/// 在服务端由 KaTeX 将公式渲染为 MathML，页面无需加载脚本、样式或字体即可显示公式
+fn render_math(mut ast: Document) -> Result<Document> {
+    let opts = |display: bool| {
+        katex::Opts::builder()
+            .display_mode(display)
+            .output_type(katex::OutputType::Mathml)
+            .build()
+    };
+    let (inline, display) = (opts(false)?, opts(true)?);
+    let mut errors = Vec::new();
+    ast.transform_latex_to_html(|latex, is_display| {
+        let (tag, class, opts) = if is_display {
+            ("div", "math math-display", &display)
+        } else {
+            ("span", "math math-inline", &inline)
+        };
+        match katex::render_with_opts(latex, opts) {
+            Ok(mathml) => format!("<{tag} class=\"{class}\">{mathml}</{tag}>"),
+            Err(e) => {
+                errors.push(format!("公式 {} 无法渲染：{}", latex.trim(), e));
+                escape_html(latex)
+            }
+        }
+    });
+    if !errors.is_empty() {
+        bail!("{}", errors.join("\n"));
+    }
+    Ok(ast)
+}
+
+/// HTML 渲染器：每个比赛日输出一个静态站点，首页为题目列表，每题一页
+pub struct HtmlRenderer {
+    template_dir: PathBuf,
+    /// 模板附带的静态文件（相对模板根）
+    template_files: Vec<String>,
+}
+
+impl HtmlRenderer {
+    /// 解压模板附带的静态文件（可为空）到 `tmp_root`
+    pub fn new(
+        tmp_root: PathBuf,
+        manifest: &TemplateManifest,
+        assets_dirs: &[PathBuf],
+    ) -> Result<Self> {
+        unwrap_template(manifest, &tmp_root, assets_dirs)?;
+        Ok(Self {
+            template_dir: tmp_root,
+            template_files: manifest.filelist.keys().cloned().collect(),
+        })
+    }
+
+    fn page(&self, lang: &str, head: &str, title: &str, body: &str) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\" />\n\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\" />\n\
+             <title>{}</title>\n<link rel=\"stylesheet\" href=\"style.css\" />\n{}</head>\n\
+             <body>\n{}</body>\n</html>\n",
+            escape_html(lang),
+            escape_html(title),
+            head,
+            body
+        )
+    }
+
+    fn format_date(date: &DateInfo) -> String {
+        let [y, mo, d, h, mi, _] = date.start;
+        let [ey, emo, ed, eh, emi, _] = date.end;
+        if (y, mo, d) == (ey, emo, ed) {
+            format!("{y}-{mo:02}-{d:02} {h:02}:{mi:02} ~ {eh:02}:{emi:02}")
+        } else {
+            format!("{y}-{mo:02}-{d:02} {h:02}:{mi:02} ~ {ey}-{emo:02}-{ed:02} {eh:02}:{emi:02}")
+        }
+    }
+
+    /// 时间限制，有测试点单独设置了更大的限制时给出范围
+    fn time_limit(meta: &ProblemMeta, locale: Locale) -> String {
+        let seconds = locale.label(Label::Seconds);
+        if meta.max_time_limit > meta.time_limit {
+            format!(
+                "{:.1} ~ {:.1} {}",
+                meta.time_limit.as_secs_f64(),
+                meta.max_time_limit.as_secs_f64(),
+                seconds
+            )
+        } else {
+            format!("{:.1} {}", meta.time_limit.as_secs_f64(), seconds)
+        }
+    }
+
+    fn memory_limit(meta: &ProblemMeta) -> String {
+        if meta.max_memory_limit > meta.memory_limit {
+            format!("{} ~ {}", meta.memory_limit, meta.max_memory_limit)
+        } else {
+            meta.memory_limit.to_string()
+        }
+    }
+
+    /// 首页：比赛信息、题目一览与注意事项
+    fn index_page(&self, doc: &RenderDocument, head: &str) -> String {
+        let config = &doc.config;
+        let locale = Locale::new(&config.lang);
+        let mut body = format!("<h1>{}</h1>\n", escape_html(&config.title));
+        if !config.dayname.is_empty() {
+            body.push_str(&format!("<h2>{}</h2>\n", escape_html(&config.dayname)));
+        }
+        if let Some(date) = &config.date {
+            body.push_str(&format!(
+                "<p class=\"date\">{}</p>\n",
+                Self::format_date(date)
+            ));
+        }
+
+        let use_pretest = config.use_pretest;
+        let mut columns = vec![
+            Label::ProblemName,
+            Label::Directory,
+            Label::ProblemType,
+            Label::TimeLimit,
+            Label::MemoryLimit,
+            Label::Testcases,
+        ];
+        if use_pretest {
+            columns.push(Label::Pretestcases);
+        }
+        body.push_str("<table class=\"problems\">\n<thead>\n<tr>");
+        for column in columns {
+            body.push_str(&format!("<th>{}</th>", locale.label(column)));
+        }
+        body.push_str("</tr>\n</thead>\n<tbody>\n");
+        for problem in &doc.problems {
+            let meta = &problem.meta;
+            body.push_str(&format!(
+                "<tr><td><a href=\"{name}.html\">{title}</a></td><td><code>{name}</code></td>\
+                 <td>{kind}</td><td>{time}</td><td>{memory}</td><td>{testcase}</td>",
+                name = escape_html(&meta.name),
+                title = escape_html(&meta.title),
+                kind = locale.problem_type(meta.problem_type),
+                time = Self::time_limit(meta, locale),
+                memory = Self::memory_limit(meta),
+                testcase = meta.testcase,
+            ));
+            if use_pretest {
+                body.push_str(&format!("<td>{}</td>", meta.pretestcase));
+            }
+            body.push_str("</tr>\n");
+        }
+        body.push_str("</tbody>\n</table>\n");
+
+        if !config.support_languages.is_empty() {
+            body.push_str(&format!(
+                "<h2>{}</h2>\n<ul>\n",
+                locale.label(Label::CompileOptions)
+            ));
+            for lang in &config.support_languages {
+                let mut multipliers = Vec::new();
+                if lang.time_multiplier != 1.0 {
+                    multipliers.push(format!(
+                        "{} ×{}",
+                        locale.label(Label::TimeLimit),
+                        lang.time_multiplier
+                    ));
+                }
+                if lang.memory_multiplier != 1.0 {
+                    multipliers.push(format!(
+                        "{} ×{}",
+                        locale.label(Label::MemoryLimit),
+                        lang.memory_multiplier
+                    ));
+                }
+                let note = if multipliers.is_empty() {
+                    String::new()
+                } else {
+                    locale.note(&multipliers)
+                };
+                body.push_str(&format!(
+                    "<li>{}{}<code>{}</code>{}</li>\n",
+                    escape_html(&lang.name),
+                    locale.colon(),
+                    escape_html(&lang.compile_options),
+                    note
+                ));
+            }
+            body.push_str("</ul>\n");
+        }
+
+        if let Some(precaution) = &doc.precaution {
+            body.push_str(&format!(
+                "<section class=\"precaution\">\n<h2>{}</h2>\n",
+                locale.label(Label::Precaution)
+            ));
+            body.push_str(&render_html(precaution));
+            body.push_str("</section>\n");
+        }
+
+        let title = if config.dayname.is_empty() {
+            config.title.clone()
+        } else {
+            format!("{} {}", config.title, config.dayname)
+        };
+        self.page(&config.lang, head, &title, &body)
+    }
+
+    /// 各语言版本题目页的链接，没有译文时为空
+    fn language_links(doc: &RenderDocument, problem: &Problem) -> String {
+        if problem.translations.is_empty() {
+            return String::new();
+        }
+        let name = escape_html(&problem.meta.name);
+        let mut links = format!(
+            " | <a href=\"{}.html\">{}</a>",
+            name,
+            escape_html(&doc.config.lang)
+        );
+        for translation in &problem.translations {
+            let lang = escape_html(&translation.lang);
+            links.push_str(&format!(" <a href=\"{name}.{lang}.html\">{lang}</a>"));
+        }
+        links
+    }
+
+    /// 题目页：题目元信息与题面
+    fn problem_page(
+        &self,
+        doc: &RenderDocument,
+        problem: &Problem,
+        title: &str,
+        statement: &str,
+        lang: &str,
+        head: &str,
+    ) -> String {
+        let meta = &problem.meta;
+        let locale = Locale::new(lang);
+        let mut body = format!(
+            "<nav><a href=\"index.html\">{}</a>{}</nav>\n<h1>{}</h1>\n",
+            escape_html(&doc.config.title),
+            Self::language_links(doc, problem),
+            escape_html(title)
+        );
+        body.push_str("<table class=\"meta\">\n");
+        let mut row = |key: Label, value: String| {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td></tr>\n",
+                locale.label(key),
+                value
+            ));
+        };
+        row(
+            Label::ProblemType,
+            locale.problem_type(meta.problem_type).to_string(),
+        );
+        if meta.problem_type != ProblemType::Output {
+            row(Label::TimeLimit, Self::time_limit(meta, locale));
+            row(Label::MemoryLimit, Self::memory_limit(meta));
+        }
+        if doc.config.use_pretest {
+            row(Label::Pretestcases, meta.pretestcase.to_string());
+        }
+        if doc.config.file_io && meta.problem_type == ProblemType::Program {
+            row(
+                Label::IoFiles,
+                format!(
+                    "<code>{name}.in</code> / <code>{name}.out</code>",
+                    name = escape_html(&meta.name)
+                ),
+            );
+        }
+        if !meta.submit_filename.is_empty() {
+            row(
+                Label::SubmitFiles,
+                meta.submit_filename
+                    .iter()
+                    .map(|f| format!("<code>{}</code>", escape_html(f)))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+        body.push_str("</table>\n<article>\n");
+        body.push_str(statement);
+        body.push_str("</article>\n");
+        self.page(
+            lang,
+            head,
+            &format!("{} - {}", title, doc.config.title),
+            &body,
+        )
+    }
+}
+
+#[async_trait]
+impl Renderer for HtmlRenderer {
+    async fn render(&self, doc: &RenderDocument) -> Result<(PathBuf, Vec<OutputFile>)> {
+        let day_key = &doc.config.day_key;
+        let mut files = Vec::new();
+
+        let head = if self.template_files.iter().any(|f| f == HEAD_FILE) {
+            fs::read_to_string(self.template_dir.join(HEAD_FILE))?
+        } else {
+            String::new()
+        };
+
+        for problem in &doc.problems {
+            let (ast, mut images) = rewrite_images(problem.ast.clone(), problem.idx)?;
+            let ast = render_math(ast)?;
+
+            let page = self.problem_page(
+                doc,
+                problem,
+                &problem.meta.title,
+                &render_html(&ast),
+                &doc.config.lang,
+                &head,
+            );
+            files.push(OutputFile::File {
+                path: PathBuf::from(format!("{}/{}.html", day_key, problem.meta.name)),
+                bytes: Box::new(std::io::Cursor::new(page.into_bytes())),
+            });
+
+            // 译文输出为 `<name>.<lang>.html`，图片与主语言共用
+            for translation in &problem.translations {
+                let (ast, map) = rewrite_images(translation.ast.clone(), problem.idx)?;
+                let ast = render_math(ast)?;
+                let page = self.problem_page(
+                    doc,
+                    problem,
+                    &translation.title,
+                    &render_html(&ast),
+                    &translation.lang,
+                    &head,
+                );
+                files.push(OutputFile::File {
+                    path: PathBuf::from(format!(
+                        "{}/{}.{}.html",
+                        day_key, problem.meta.name, translation.lang
+                    )),
+                    bytes: Box::new(std::io::Cursor::new(page.into_bytes())),
+                });
+                images.extend(map);
+            }
+
+            let mut seen = HashSet::new();
+            for (url, target) in &images {
+                if !seen.insert(target.clone()) {
+                    continue;
+                }
+                let stream = doc.assets.load(problem.idx, url).await?;
+                files.push(OutputFile::File {
+                    path: PathBuf::from(format!("{}/{}", day_key, target.display())),
+                    bytes: stream,
+                });
+            }
+        }
+
+        let index = self.index_page(doc, &head);
+        files.push(OutputFile::File {
+            path: PathBuf::from(format!("{}/index.html", day_key)),
+            bytes: Box::new(std::io::Cursor::new(index.into_bytes())),
+        });
+
+        // 模板静态文件（样式、图标等）原样输出
+        for rel in &self.template_files {
+            if rel == HEAD_FILE {
+                continue;
+            }
+            let file = tokio::fs::File::open(self.template_dir.join(rel)).await?;
+            files.push(OutputFile::File {
+                path: PathBuf::from(format!("{}/{}", day_key, rel)),
+                bytes: Box::new(file),
+            });
+        }
+        if !self.template_files.iter().any(|f| f == "style.css") {
+            files.push(OutputFile::File {
+                path: PathBuf::from(format!("{}/style.css", day_key)),
+                bytes: Box::new(std::io::Cursor::new(DEFAULT_STYLE.as_bytes())),
+            });
+        }
+
+        Ok((PathBuf::from(format!("{}/index.html", day_key)), files))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn math_is_rendered_to_mathml() {
+        let ast = tuack_ng_parser::parse("设 $a_i \\le 10^9$。\n\n$$\n\\sum a_i\n$$\n");
+        let html = render_html(&render_math(ast).unwrap());
+        assert!(html.contains("<span class=\"math math-inline\"><span class=\"katex\"><math"));
+        assert!(html.contains("<div class=\"math math-display\">"));
+        assert!(html.contains("display=\"block\""));
+        assert!(!html.contains("\\("));
+    }
+
+    #[test]
+    fn invalid_math_is_reported() {
+        let ast = tuack_ng_parser::parse("$\\frac{1}$ 与 $x$");
+        let err = render_math(ast).unwrap_err().to_string();
+        assert!(err.contains("\\frac{1}"), "{}", err);
+    }
+}
@@ -94,6 +94,21 @@ fn build_config(
 
             Value::Table(data_cases)
         }),
+        ("pretest_cases", {
+            let pretest_cases = lua
+                .to_value(&problem.runtime.inherited_pretest)?
+                .as_table()
+                .unwrap()
+                .to_owned();
+            pretest_cases.set(
+                "map",
+                lua.create_function(|lua, (tbl, func): (Table, Function)| {
+                    map_table(lua, tbl, func)
+                })?,
+            )?;
+
+            Value::Table(pretest_cases)
+        }),
     ])
 }
 
@@ -5,6 +5,7 @@ use crate::prelude::*;
 pub enum TargetType {
     Typst,
     Markdown,
+    Html,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -20,6 +21,10 @@ pub struct TemplateManifest {
     pub filelist: IndexMap<String, String>,
     #[serde(default)]
     pub processor: Vec<String>,
+    /// 渲染语言，第一个为主语言，其余作为译文一并交给模板（如双语 PDF）；
+    /// 可被 `ren --lang` 覆盖，为空时使用缺省语言
+    #[serde(default)]
+    pub languages: Vec<String>,
 }
 
 fn default_use_pretest() -> bool {
@@ -33,6 +33,20 @@ impl Renderer for MarkdownRenderer {
                 bytes: Box::new(std::io::Cursor::new(output.into_bytes())),
             });
 
+            // 译文输出为 `<name>.<lang>.md`，图片与主语言共用
+            let mut images = images;
+            for translation in &problem.translations {
+                let (ast, map) = rewrite_images(translation.ast.clone(), problem.idx)?;
+                files.push(OutputFile::File {
+                    path: PathBuf::from(format!(
+                        "{}/{}.{}.md",
+                        doc.config.day_key, problem.meta.name, translation.lang
+                    )),
+                    bytes: Box::new(std::io::Cursor::new(render_markdown(&ast).into_bytes())),
+                });
+                images.extend(map);
+            }
+
             let mut seen = HashSet::new();
             for (url, target) in &images {
                 if !seen.insert(target.clone()) {
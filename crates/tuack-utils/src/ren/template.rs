@@ -5,20 +5,129 @@ use anyhow::Result;
 use minijinja::{Environment, Value, context};
 use owo_colors::OwoColorize;
 use std::sync::{Arc, Mutex};
+use tuack_lib::ren::ProblemType;
 
-fn input_file(problem: &ProblemConfig, file_io: bool) -> Result<String, minijinja::Error> {
-    Ok(if file_io {
-        format!("从文件 _{}.in_ 中读入数据。", problem.name)
-    } else {
-        "从标准输入读入数据。".to_string()
+/// 模板内置文字与渲染器界面文字的语言，未知语言按中文处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Locale {
+    Zh,
+    En,
+}
+
+/// 渲染器界面中的固定文字
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Label {
+    ProblemName,
+    Directory,
+    ProblemType,
+    TimeLimit,
+    MemoryLimit,
+    Testcases,
+    Pretestcases,
+    IoFiles,
+    SubmitFiles,
+    CompileOptions,
+    Precaution,
+    Seconds,
+    Yes,
+    No,
+}
+
+impl Locale {
+    pub(crate) fn new(lang: &str) -> Self {
+        if lang == "en" || lang.starts_with("en-") {
+            Locale::En
+        } else {
+            Locale::Zh
+        }
+    }
+
+    pub(crate) fn label(self, label: Label) -> &'static str {
+        match (self, label) {
+            (Locale::Zh, Label::ProblemName) => "题目名称",
+            (Locale::Zh, Label::Directory) => "目录",
+            (Locale::Zh, Label::ProblemType) => "题目类型",
+            (Locale::Zh, Label::TimeLimit) => "时间限制",
+            (Locale::Zh, Label::MemoryLimit) => "内存限制",
+            (Locale::Zh, Label::Testcases) => "测试点数目",
+            (Locale::Zh, Label::Pretestcases) => "预测试点数目",
+            (Locale::Zh, Label::IoFiles) => "输入输出文件",
+            (Locale::Zh, Label::SubmitFiles) => "提交文件名",
+            (Locale::Zh, Label::CompileOptions) => "编译选项",
+            (Locale::Zh, Label::Precaution) => "注意事项",
+            (Locale::Zh, Label::Seconds) => "秒",
+            (Locale::Zh, Label::Yes) => "是",
+            (Locale::Zh, Label::No) => "否",
+            (Locale::En, Label::ProblemName) => "Problem",
+            (Locale::En, Label::Directory) => "Directory",
+            (Locale::En, Label::ProblemType) => "Problem type",
+            (Locale::En, Label::TimeLimit) => "Time limit",
+            (Locale::En, Label::MemoryLimit) => "Memory limit",
+            (Locale::En, Label::Testcases) => "Test cases",
+            (Locale::En, Label::Pretestcases) => "Pretest cases",
+            (Locale::En, Label::IoFiles) => "Input/output files",
+            (Locale::En, Label::SubmitFiles) => "Submission files",
+            (Locale::En, Label::CompileOptions) => "Compiler options",
+            (Locale::En, Label::Precaution) => "Notes",
+            (Locale::En, Label::Seconds) => "s",
+            (Locale::En, Label::Yes) => "Yes",
+            (Locale::En, Label::No) => "No",
+        }
+    }
+
+    pub(crate) fn problem_type(self, problem_type: ProblemType) -> &'static str {
+        match (self, problem_type) {
+            (Locale::Zh, ProblemType::Program) => "传统型",
+            (Locale::Zh, ProblemType::Output) => "提交答案型",
+            (Locale::Zh, ProblemType::Interactive) => "交互型",
+            (Locale::Zh, ProblemType::Communication) => "通信型",
+            (Locale::En, ProblemType::Program) => "Traditional",
+            (Locale::En, ProblemType::Output) => "Output only",
+            (Locale::En, ProblemType::Interactive) => "Interactive",
+            (Locale::En, ProblemType::Communication) => "Communication",
+        }
+    }
+
+    /// 冒号
+    pub(crate) fn colon(self) -> &'static str {
+        match self {
+            Locale::Zh => "：",
+            Locale::En => ": ",
+        }
+    }
+
+    /// 以括号附在其他文字后的说明，多项之间以逗号分隔
+    pub(crate) fn note(self, items: &[String]) -> String {
+        match self {
+            Locale::Zh => format!("（{}）", items.join("，")),
+            Locale::En => format!(" ({})", items.join(", ")),
+        }
+    }
+}
+
+fn input_file(
+    problem: &ProblemConfig,
+    file_io: bool,
+    locale: Locale,
+) -> Result<String, minijinja::Error> {
+    Ok(match (locale, file_io) {
+        (Locale::Zh, true) => format!("从文件 _{}.in_ 中读入数据。", problem.name),
+        (Locale::Zh, false) => "从标准输入读入数据。".to_string(),
+        (Locale::En, true) => format!("Read the input from the file _{}.in_.", problem.name),
+        (Locale::En, false) => "Read the input from standard input.".to_string(),
     })
 }
 
-fn output_file(problem: &ProblemConfig, file_io: bool) -> Result<String, minijinja::Error> {
-    Ok(if file_io {
-        format!("输出到文件 _{}.out_ 中。", problem.name)
-    } else {
-        "输出到标准输出。".to_string()
+fn output_file(
+    problem: &ProblemConfig,
+    file_io: bool,
+    locale: Locale,
+) -> Result<String, minijinja::Error> {
+    Ok(match (locale, file_io) {
+        (Locale::Zh, true) => format!("输出到文件 _{}.out_ 中。", problem.name),
+        (Locale::Zh, false) => "输出到标准输出。".to_string(),
+        (Locale::En, true) => format!("Write the output to the file _{}.out_.", problem.name),
+        (Locale::En, false) => "Write the output to standard output.".to_string(),
     })
 }
 
@@ -27,6 +136,7 @@ fn handle_sample(
     sample_id: u32,
     problem: &ProblemConfig,
     base_path: &Path,
+    locale: Locale,
     warnings: &Arc<Mutex<Vec<String>>>,
 ) -> Result<String, minijinja::Error> {
     debug!("处理 sample 函数：{}", sample_id);
@@ -51,7 +161,10 @@ fn handle_sample(
     let mut md = String::new();
 
     // 输入部分
-    md.push_str(&format!("## 样例 {} 输入\n\n", sample_id));
+    md.push_str(&match locale {
+        Locale::Zh => format!("## 样例 {} 输入\n\n", sample_id),
+        Locale::En => format!("## Sample Input {}\n\n", sample_id),
+    });
 
     let input_file = &sample_item.input_path();
 
@@ -85,7 +198,10 @@ fn handle_sample(
     }
 
     // 输出部分（修改这里）
-    md.push_str(&format!("## 样例 {} 输出\n\n", sample_id));
+    md.push_str(&match locale {
+        Locale::Zh => format!("## 样例 {} 输出\n\n", sample_id),
+        Locale::En => format!("## Sample Output {}\n\n", sample_id),
+    });
 
     let output_file = &sample_item.output_path();
 
@@ -126,6 +242,7 @@ fn handle_sample(
 fn handle_sample_file(
     sample_id: u32,
     problem: &ProblemConfig,
+    locale: Locale,
     warnings: &Arc<Mutex<Vec<String>>>,
 ) -> Result<String, minijinja::Error> {
     debug!("处理 sample_file 函数：{}", sample_id);
@@ -139,10 +256,16 @@ fn handle_sample_file(
         ));
     }
 
-    let text = format!(
-        "见选手目录下的 _{0}/{0}{1}.in_ 与 _{0}/{0}{1}.ans_。",
-        problem.name, sample_id
-    );
+    let text = match locale {
+        Locale::Zh => format!(
+            "见选手目录下的 _{0}/{0}{1}.in_ 与 _{0}/{0}{1}.ans_。",
+            problem.name, sample_id
+        ),
+        Locale::En => format!(
+            "See _{0}/{0}{1}.in_ and _{0}/{0}{1}.ans_ in the contestant directory.",
+            problem.name, sample_id
+        ),
+    };
 
     debug!("生成文件引用：sample_file({}) -> {}", sample_id, text);
     Ok(text)
@@ -164,6 +287,8 @@ fn handle_lua_table(
 }
 
 /// 使用模板渲染函数；返回渲染结果与渲染过程中的警告（已带颜色）。
+///
+/// `lang` 决定 `s.input_file()`、`sample.text()` 等内置文字的语言，也以 `lang` 变量暴露给题面。
 pub fn render_template(
     template: &str,
     problem: &ProblemConfig,
@@ -171,8 +296,10 @@ pub fn render_template(
     contest: &ContestConfig,
     base_path: PathBuf,
     manifest: TemplateManifest,
+    lang: &str,
 ) -> Result<(String, Vec<String>)> {
     let warnings: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+    let locale = Locale::new(lang);
 
     // 创建环境
     let env = Environment::new();
@@ -185,7 +312,7 @@ pub fn render_template(
                 let base_path = base_path.clone();
                 let warnings = warnings.clone();
                 move |sample_id: u32| -> Result<String, minijinja::Error> {
-                    handle_sample(sample_id, &problem, &base_path, &warnings)
+                    handle_sample(sample_id, &problem, &base_path, locale, &warnings)
                 }
             }),
         ),
@@ -195,7 +322,7 @@ pub fn render_template(
                 let problem = problem.clone();
                 let warnings = warnings.clone();
                 move |sample_id: u32| -> Result<String, minijinja::Error> {
-                    handle_sample_file(sample_id, &problem, &warnings)
+                    handle_sample_file(sample_id, &problem, locale, &warnings)
                 }
             }),
         ),
@@ -255,7 +382,11 @@ pub fn render_template(
             Value::from_function({
                 let problem = problem.clone();
                 move || -> Result<String, minijinja::Error> {
-                    input_file(&problem, problem.file_io.unwrap_or(manifest.file_io))
+                    input_file(
+                        &problem,
+                        problem.file_io.unwrap_or(manifest.file_io),
+                        locale,
+                    )
                 }
             }),
         ),
@@ -264,7 +395,11 @@ pub fn render_template(
             Value::from_function({
                 let problem = problem.clone();
                 move || -> Result<String, minijinja::Error> {
-                    output_file(&problem, problem.file_io.unwrap_or(manifest.file_io))
+                    output_file(
+                        &problem,
+                        problem.file_io.unwrap_or(manifest.file_io),
+                        locale,
+                    )
                 }
             }),
         ),
@@ -287,7 +422,9 @@ pub fn render_template(
         day => AsSerde::<ContestDayConfig, FullView>::new(day.clone()),
         contest => AsSerde::<ContestConfig, FullView>::new(contest.clone()),
         data_cases => problem.runtime.inherited_data,
+        pretest_cases => problem.runtime.inherited_pretest,
         sample_cases => problem.runtime.samples,
+        lang => lang,
 
         sample => sample,
         tools => tools,
@@ -1,13 +1,14 @@
 use crate::prelude::*;
 use crate::ren::manifest::TemplateManifest;
 use crate::ren::renderers::{rewrite_images, unwrap_template};
-use tuack_lib::ren::{ProblemType, RenderDocument, Renderer};
+use crate::ren::template::{Label, Locale};
+use tuack_lib::ren::{RenderDocument, Renderer};
 use tuack_lib::utils::output::OutputFile;
 use std::collections::HashSet;
 use tuack_ng_parser::printers::render_typst;
 
 mod datajson;
-use datajson::{DataJson, DateInfo, Problem, SupportLanguage};
+use datajson::{DataJson, DateInfo, Problem, SupportLanguage, Translation};
 
 /// Typst 渲染器
 pub struct TypstRenderer {
@@ -60,15 +61,17 @@ impl TypstRenderer {
     }
 
     fn generate_conf(&self, doc: &RenderDocument) -> DataJson {
+        let locale = Locale::new(&doc.config.lang);
+        let seconds = locale.label(Label::Seconds);
         let problems = doc
             .problems
             .iter()
             .map(|p| {
                 let meta = &p.meta;
-                let problem_type = match meta.problem_type {
-                    ProblemType::Program => "传统型",
-                    ProblemType::Output => "提交答案型",
-                    ProblemType::Interactive => "交互型",
+                let point_equal = if meta.point_equal {
+                    Label::Yes
+                } else {
+                    Label::No
                 };
                 Problem {
                     name: meta.name.clone(),
@@ -77,12 +80,34 @@ impl TypstRenderer {
                     exec: meta.name.clone(),
                     input: format!("{}.in", meta.name),
                     output: format!("{}.out", meta.name),
-                    problem_type: problem_type.to_string(),
-                    time_limit: format!("{:.1} 秒", meta.time_limit.as_secs_f64()),
-                    memory_limit: format!("{:.0}", meta.memory_limit),
+                    problem_type: locale.problem_type(meta.problem_type).to_string(),
+                    time_limit: if meta.max_time_limit > meta.time_limit {
+                        format!(
+                            "{:.1} ~ {:.1} {}",
+                            meta.time_limit.as_secs_f64(),
+                            meta.max_time_limit.as_secs_f64(),
+                            seconds
+                        )
+                    } else {
+                        format!("{:.1} {}", meta.time_limit.as_secs_f64(), seconds)
+                    },
+                    memory_limit: if meta.max_memory_limit > meta.memory_limit {
+                        format!("{:.0} ~ {:.0}", meta.memory_limit, meta.max_memory_limit)
+                    } else {
+                        format!("{:.0}", meta.memory_limit)
+                    },
                     testcase: meta.testcase.to_string(),
-                    point_equal: if meta.point_equal { "是" } else { "否" }.to_string(),
+                    pretestcase: meta.pretestcase.to_string(),
+                    point_equal: locale.label(point_equal).to_string(),
                     submit_filename: meta.submit_filename.clone(),
+                    translations: p
+                        .translations
+                        .iter()
+                        .map(|t| Translation {
+                            lang: t.lang.clone(),
+                            title: t.title.clone(),
+                        })
+                        .collect(),
                 }
             })
             .collect();
@@ -93,9 +118,12 @@ impl TypstRenderer {
             .map(|l| SupportLanguage {
                 name: l.name.clone(),
                 compile_options: l.compile_options.clone(),
+                time_multiplier: l.time_multiplier,
+                memory_multiplier: l.memory_multiplier,
             })
             .collect();
         DataJson {
+            lang: doc.config.lang.clone(),
             title: doc.config.title.clone(),
             subtitle: doc.config.short_title.clone(),
             dayname: doc.config.dayname.clone(),
@@ -159,6 +187,21 @@ impl Renderer for TypstRenderer {
             for (url, target) in &map {
                 images.push((problem.idx, url.clone(), target.clone()));
             }
+
+            // 译文写入 `problem-<idx>-<lang>.typ`，由模板按 data.json 中的 translations 引用
+            for translation in &problem.translations {
+                let (ast, map) = rewrite_images(translation.ast.clone(), problem.idx)?;
+                let typst_output = format!("#import \"utils.typ\": *\n{}", render_typst(&ast));
+                tokio::fs::write(
+                    self.template_dir
+                        .join(format!("problem-{}-{}.typ", problem.idx, translation.lang)),
+                    typst_output,
+                )
+                .await?;
+                for (url, target) in &map {
+                    images.push((problem.idx, url.clone(), target.clone()));
+                }
+            }
         }
 
         if let Some(precaution) = &doc.precaution {
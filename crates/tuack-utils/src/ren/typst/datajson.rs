@@ -2,6 +2,8 @@ use crate::prelude::*;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DataJson {
+    /// 主语言
+    pub lang: String,
     pub title: String,
     pub subtitle: String,
     pub dayname: String,
@@ -26,14 +28,25 @@ pub struct Problem {
     pub time_limit: String,
     pub memory_limit: String,
     pub testcase: String,
+    pub pretestcase: String,
     pub point_equal: String,
     pub submit_filename: Vec<String>,
+    /// 译文，题面位于 `problem-<idx>-<lang>.typ`
+    pub translations: Vec<Translation>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Translation {
+    pub lang: String,
+    pub title: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SupportLanguage {
     pub name: String,
     pub compile_options: String,
+    pub time_multiplier: f64,
+    pub memory_multiplier: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -0,0 +1,34 @@
+#[cfg(target_os = "linux")]
+mod linux;
+
+#[cfg(target_os = "linux")]
+pub use linux::{Sandbox, check_available};
+
+#[cfg(not(target_os = "linux"))]
+mod unsupported {
+    use std::process::Command as StdCommand;
+
+    use crate::prelude::*;
+    use crate::process::ProcessOutcome;
+    use tuack_lib::utils::compiler::ResourceLimits;
+
+    /// 检查当前环境能否使用沙箱
+    pub fn check_available() -> Result<()> {
+        bail!("沙箱仅支持 Linux")
+    }
+
+    pub struct Sandbox;
+
+    impl Sandbox {
+        pub fn new(_limits: ResourceLimits, _workdir: impl Into<PathBuf>) -> Self {
+            Sandbox
+        }
+
+        pub async fn run(self, _cmd: StdCommand) -> Result<ProcessOutcome> {
+            bail!("沙箱仅支持 Linux")
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub use unsupported::{Sandbox, check_available};
@@ -0,0 +1,1054 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::{CStr, CString};
+use std::fs::File;
+use std::io;
+use std::os::fd::AsRawFd;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::process::CommandExt;
+use std::process::Command as StdCommand;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use seccompiler::{
+    BpfProgram, SeccompAction, SeccompCmpArgLen, SeccompCmpOp, SeccompCondition, SeccompFilter,
+    SeccompRule,
+};
+use tempfile::TempDir;
+use tokio::process::Command as TokioCommand;
+use tokio::time::{Instant, sleep};
+
+use crate::prelude::*;
+use crate::process::ProcessOutcome;
+use tuack_lib::utils::compiler::{ResourceLimits, RunStatus};
+
+/// 指定沙箱 cgroup 目录的环境变量（需为已委派给当前用户的 cgroup v2 目录）
+const CGROUP_ENV: &str = "TUACK_NG_CGROUP";
+/// 输出文件大小上限（RLIMIT_FSIZE）
+const OUTPUT_LIMIT: u64 = 1 << 30;
+/// 沙箱内进程数上限（pids.max）
+const PIDS_LIMIT: u64 = 64;
+/// CPU 时间轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 沙箱内允许的系统调用，其余调用返回 EPERM
+const ALLOWED_SYSCALLS: &[i64] = &[
+    libc::SYS_read,
+    libc::SYS_write,
+    libc::SYS_readv,
+    libc::SYS_writev,
+    libc::SYS_pread64,
+    libc::SYS_pwrite64,
+    libc::SYS_preadv,
+    libc::SYS_pwritev,
+    libc::SYS_preadv2,
+    libc::SYS_pwritev2,
+    libc::SYS_openat,
+    libc::SYS_close,
+    libc::SYS_close_range,
+    libc::SYS_fstat,
+    libc::SYS_newfstatat,
+    libc::SYS_statx,
+    libc::SYS_statfs,
+    libc::SYS_fstatfs,
+    libc::SYS_lseek,
+    libc::SYS_ppoll,
+    libc::SYS_pselect6,
+    libc::SYS_ioctl,
+    libc::SYS_fcntl,
+    libc::SYS_flock,
+    libc::SYS_fsync,
+    libc::SYS_fdatasync,
+    libc::SYS_truncate,
+    libc::SYS_ftruncate,
+    libc::SYS_fadvise64,
+    libc::SYS_sendfile,
+    libc::SYS_copy_file_range,
+    libc::SYS_splice,
+    libc::SYS_tee,
+    libc::SYS_dup,
+    libc::SYS_dup3,
+    libc::SYS_pipe2,
+    libc::SYS_getdents64,
+    libc::SYS_getcwd,
+    libc::SYS_chdir,
+    libc::SYS_fchdir,
+    libc::SYS_faccessat,
+    libc::SYS_faccessat2,
+    libc::SYS_readlinkat,
+    libc::SYS_mkdirat,
+    libc::SYS_unlinkat,
+    libc::SYS_renameat,
+    libc::SYS_renameat2,
+    libc::SYS_fchmod,
+    libc::SYS_fchmodat,
+    libc::SYS_umask,
+    libc::SYS_utimensat,
+    libc::SYS_mmap,
+    libc::SYS_mprotect,
+    libc::SYS_munmap,
+    libc::SYS_mremap,
+    libc::SYS_madvise,
+    libc::SYS_mincore,
+    libc::SYS_msync,
+    libc::SYS_mlock,
+    libc::SYS_munlock,
+    libc::SYS_brk,
+    libc::SYS_memfd_create,
+    libc::SYS_rt_sigaction,
+    libc::SYS_rt_sigprocmask,
+    libc::SYS_rt_sigreturn,
+    libc::SYS_rt_sigsuspend,
+    libc::SYS_rt_sigtimedwait,
+    libc::SYS_sigaltstack,
+    libc::SYS_kill,
+    libc::SYS_tkill,
+    libc::SYS_tgkill,
+    libc::SYS_execve,
+    libc::SYS_execveat,
+    libc::SYS_exit,
+    libc::SYS_exit_group,
+    libc::SYS_wait4,
+    libc::SYS_waitid,
+    libc::SYS_set_tid_address,
+    libc::SYS_set_robust_list,
+    libc::SYS_get_robust_list,
+    libc::SYS_futex,
+    libc::SYS_rseq,
+    libc::SYS_membarrier,
+    libc::SYS_sched_yield,
+    libc::SYS_sched_getaffinity,
+    libc::SYS_sched_getparam,
+    libc::SYS_sched_getscheduler,
+    libc::SYS_getpid,
+    libc::SYS_getppid,
+    libc::SYS_gettid,
+    libc::SYS_getuid,
+    libc::SYS_geteuid,
+    libc::SYS_getgid,
+    libc::SYS_getegid,
+    libc::SYS_getresuid,
+    libc::SYS_getresgid,
+    libc::SYS_getgroups,
+    libc::SYS_getpgid,
+    libc::SYS_getsid,
+    libc::SYS_prctl,
+    libc::SYS_prlimit64,
+    libc::SYS_getrlimit,
+    libc::SYS_getrusage,
+    libc::SYS_getrandom,
+    libc::SYS_uname,
+    libc::SYS_sysinfo,
+    libc::SYS_times,
+    libc::SYS_clock_gettime,
+    libc::SYS_clock_getres,
+    libc::SYS_clock_nanosleep,
+    libc::SYS_gettimeofday,
+    libc::SYS_nanosleep,
+    libc::SYS_getitimer,
+    libc::SYS_setitimer,
+    libc::SYS_timer_create,
+    libc::SYS_timer_settime,
+    libc::SYS_timer_gettime,
+    libc::SYS_timer_delete,
+    libc::SYS_timerfd_create,
+    libc::SYS_timerfd_settime,
+    libc::SYS_timerfd_gettime,
+    libc::SYS_eventfd2,
+    libc::SYS_epoll_create1,
+    libc::SYS_epoll_ctl,
+    libc::SYS_epoll_pwait,
+];
+
+/// x86_64 上旧式的系统调用，其他架构只提供 `*at` 等新版本
+#[cfg(target_arch = "x86_64")]
+const ALLOWED_LEGACY_SYSCALLS: &[i64] = &[
+    libc::SYS_open,
+    libc::SYS_stat,
+    libc::SYS_lstat,
+    libc::SYS_access,
+    libc::SYS_poll,
+    libc::SYS_select,
+    libc::SYS_dup2,
+    libc::SYS_pipe,
+    libc::SYS_getdents,
+    libc::SYS_readlink,
+    libc::SYS_mkdir,
+    libc::SYS_rmdir,
+    libc::SYS_unlink,
+    libc::SYS_rename,
+    libc::SYS_chmod,
+    libc::SYS_fork,
+    libc::SYS_vfork,
+    libc::SYS_alarm,
+    libc::SYS_pause,
+    libc::SYS_time,
+    libc::SYS_getpgrp,
+    libc::SYS_arch_prctl,
+    libc::SYS_epoll_create,
+    libc::SYS_epoll_wait,
+    libc::SYS_eventfd,
+];
+#[cfg(not(target_arch = "x86_64"))]
+const ALLOWED_LEGACY_SYSCALLS: &[i64] = &[];
+
+/// `clone` 不允许携带的 flag：禁止在沙箱内创建新的 namespace
+const NAMESPACE_FLAGS: libc::c_int = libc::CLONE_NEWNS
+    | libc::CLONE_NEWUTS
+    | libc::CLONE_NEWIPC
+    | libc::CLONE_NEWUSER
+    | libc::CLONE_NEWPID
+    | libc::CLONE_NEWNET
+    | libc::CLONE_NEWCGROUP;
+
+/// 以只读方式绑定到沙箱根目录的系统路径，提供动态库与解释器；不存在的路径跳过
+const SYSTEM_PATHS: &[&str] = &[
+    "/usr",
+    "/bin",
+    "/sbin",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/libx32",
+    "/etc/ld.so.cache",
+    "/etc/ld.so.conf",
+    "/etc/ld.so.conf.d",
+    "/etc/alternatives",
+];
+
+/// 绑定到沙箱根目录的设备文件
+const DEVICE_PATHS: &[&str] = &["/dev/null", "/dev/zero", "/dev/random", "/dev/urandom"];
+
+static CGROUP_BASE: OnceLock<PathBuf> = OnceLock::new();
+static RUN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// 检查当前环境能否使用沙箱
+pub fn check_available() -> Result<()> {
+    cgroup_base().map(|_| ())
+}
+
+/// 在 `/proc/self/mountinfo` 中查找 cgroup v2 的挂载点
+fn cgroup2_mount() -> Result<PathBuf> {
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo")?;
+    mountinfo
+        .lines()
+        .find_map(|line| {
+            let (mount, fs) = line.split_once(" - ")?;
+            if fs.split_whitespace().next()? != "cgroup2" {
+                return None;
+            }
+            Some(PathBuf::from(unescape_mount_path(
+                mount.split_whitespace().nth(4)?,
+            )))
+        })
+        .context("未找到 cgroup v2 挂载点")
+}
+
+/// 定位沙箱使用的 cgroup 目录，并确保其子 cgroup 可使用 memory 与 pids 控制器
+fn cgroup_base() -> Result<&'static Path> {
+    if let Some(base) = CGROUP_BASE.get() {
+        return Ok(base);
+    }
+
+    let base = match std::env::var_os(CGROUP_ENV) {
+        Some(path) => PathBuf::from(path),
+        None => cgroup2_mount()?.join("tuack-ng"),
+    };
+    fs::create_dir_all(&base).with_context(|| format!("无法创建 cgroup：{}", base.display()))?;
+
+    let has_controllers = |file: &str| -> Result<bool> {
+        let content = fs::read_to_string(base.join(file))?;
+        let controllers: Vec<&str> = content.split_whitespace().collect();
+        Ok(controllers.contains(&"memory") && controllers.contains(&"pids"))
+    };
+
+    if !has_controllers("cgroup.controllers")?
+        && let Some(parent) = base.parent()
+    {
+        // 尝试在上级 cgroup 中启用控制器，失败时由下方检查报错
+        let _ = fs::write(parent.join("cgroup.subtree_control"), "+memory +pids");
+    }
+    if !has_controllers("cgroup.controllers")? {
+        bail!(
+            "cgroup {} 未启用 memory 或 pids 控制器，可通过环境变量 {} 指定已委派的 cgroup",
+            base.display(),
+            CGROUP_ENV
+        );
+    }
+    if !has_controllers("cgroup.subtree_control")? {
+        fs::write(base.join("cgroup.subtree_control"), "+memory +pids")
+            .with_context(|| format!("无法为 cgroup {} 启用控制器", base.display()))?;
+    }
+
+    Ok(CGROUP_BASE.get_or_init(|| base))
+}
+
+/// 还原 mountinfo 中的八进制转义（如 `\040` 表示空格）
+fn unescape_mount_path(path: &str) -> String {
+    let mut result = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let code: String = chars.by_ref().take(3).collect();
+            match u8::from_str_radix(&code, 8) {
+                Ok(byte) => result.push(byte as char),
+                Err(_) => {
+                    result.push(c);
+                    result.push_str(&code);
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// 单次运行使用的 cgroup，析构时杀死残留进程并删除目录
+struct Cgroup {
+    path: PathBuf,
+}
+
+impl Cgroup {
+    fn create(base: &Path) -> Result<Self> {
+        let path = base.join(format!(
+            "run-{}-{}",
+            std::process::id(),
+            RUN_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir(&path).with_context(|| format!("无法创建 cgroup：{}", path.display()))?;
+        Ok(Cgroup { path })
+    }
+
+    fn write(&self, file: &str, value: impl ToString) -> Result<()> {
+        fs::write(self.path.join(file), value.to_string())
+            .with_context(|| format!("无法写入 cgroup 文件 {}", file))
+    }
+
+    /// 读取形如 `key value` 的统计文件中的某一项
+    fn stat(&self, file: &str, key: &str) -> Result<u64> {
+        let content = fs::read_to_string(self.path.join(file))?;
+        content
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.trim().parse().ok())
+            .with_context(|| format!("cgroup 文件 {} 中没有 {}", file, key))
+    }
+
+    /// 已使用的 CPU 时间（用户态 + 内核态）
+    fn cpu_time(&self) -> Result<Duration> {
+        Ok(Duration::from_micros(self.stat("cpu.stat", "usage_usec")?))
+    }
+
+    /// cgroup 记录的峰值内存（字节），内核不支持 `memory.peak` 时为 `None`
+    fn memory_peak(&self) -> Option<u64> {
+        fs::read_to_string(self.path.join("memory.peak"))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// 是否发生过 OOM kill
+    fn oom_killed(&self) -> Result<bool> {
+        Ok(self.stat("memory.events", "oom_kill ")? > 0)
+    }
+
+    fn kill(&self) {
+        let _ = fs::write(self.path.join("cgroup.kill"), "1");
+    }
+}
+
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        self.kill();
+        // 进程被杀死后 cgroup 才能删除，最多等待约 1 秒
+        for _ in 0..100 {
+            if fs::remove_dir(&self.path).is_ok() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        warn!("无法删除 cgroup：{}", self.path.display());
+    }
+}
+
+/// 将当前进程加入 cgroup，在子进程 `exec` 前调用
+fn join_cgroup(cgroup_procs: &File) -> io::Result<()> {
+    check(unsafe { libc::write(cgroup_procs.as_raw_fd(), c"0".as_ptr().cast(), 1) } as i64)
+}
+
+/// 挂载表中各挂载点的挂载选项，用于只读重新挂载时保留原有的选项
+struct MountTable {
+    mounts: Vec<(PathBuf, libc::c_ulong)>,
+}
+
+impl MountTable {
+    fn read() -> Result<Self> {
+        let mut mounts = Vec::new();
+        for line in fs::read_to_string("/proc/self/mountinfo")?.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (Some(mount_point), Some(options)) = (fields.get(4), fields.get(5)) else {
+                continue;
+            };
+            let mut flags = 0;
+            for option in options.split(',') {
+                flags |= match option {
+                    "nosuid" => libc::MS_NOSUID,
+                    "nodev" => libc::MS_NODEV,
+                    "noexec" => libc::MS_NOEXEC,
+                    "noatime" => libc::MS_NOATIME,
+                    "nodiratime" => libc::MS_NODIRATIME,
+                    "relatime" => libc::MS_RELATIME,
+                    "strictatime" => libc::MS_STRICTATIME,
+                    _ => 0,
+                };
+            }
+            mounts.push((PathBuf::from(unescape_mount_path(mount_point)), flags));
+        }
+        Ok(MountTable { mounts })
+    }
+
+    /// `path` 所在挂载点的选项；user namespace 中重新挂载时不能去掉这些选项
+    fn flags(&self, path: &Path) -> libc::c_ulong {
+        self.mounts
+            .iter()
+            .filter(|(mount_point, _)| path.starts_with(mount_point))
+            .max_by_key(|(mount_point, _)| mount_point.components().count())
+            .map_or(0, |(_, flags)| *flags)
+    }
+}
+
+/// 绑定挂载到沙箱根目录中的路径
+struct Bind {
+    source: CString,
+    target: CString,
+    /// 只读重新挂载时的选项，`None` 表示保持可写
+    readonly: Option<libc::c_ulong>,
+}
+
+/// 沙箱根目录的布局：需在新根目录中创建的目录、空文件、符号链接与绑定挂载
+struct RootLayout {
+    root: PathBuf,
+    dirs: BTreeSet<PathBuf>,
+    files: Vec<PathBuf>,
+    /// （链接内容，链接路径）
+    symlinks: Vec<(PathBuf, PathBuf)>,
+    /// （宿主路径，挂载点，只读重新挂载的选项）
+    binds: Vec<(PathBuf, PathBuf, Option<libc::c_ulong>)>,
+    /// 已绑定路径的真实路径
+    bound: Vec<PathBuf>,
+}
+
+impl RootLayout {
+    fn new(root: &Path) -> Self {
+        RootLayout {
+            root: root.to_path_buf(),
+            dirs: BTreeSet::new(),
+            files: Vec::new(),
+            symlinks: Vec::new(),
+            binds: Vec::new(),
+            bound: Vec::new(),
+        }
+    }
+
+    /// 宿主路径在新根目录中对应的路径
+    fn target(&self, path: &Path) -> PathBuf {
+        self.root.join(path.strip_prefix("/").unwrap_or(path))
+    }
+
+    /// 将宿主路径加入新根目录：符号链接原样复制，其余绑定挂载，`readonly` 为 `None` 时保持可写。
+    /// 路径不存在时跳过。
+    fn add(&mut self, path: &Path, readonly: Option<libc::c_ulong>) -> Result<()> {
+        let Ok(meta) = fs::symlink_metadata(path) else {
+            return Ok(());
+        };
+        for ancestor in path.ancestors().skip(1) {
+            if ancestor.parent().is_none() {
+                break;
+            }
+            self.dirs.insert(self.target(ancestor));
+        }
+        let target = self.target(path);
+        if meta.file_type().is_symlink() {
+            self.symlinks.push((fs::read_link(path)?, target));
+            return Ok(());
+        }
+        if meta.is_dir() {
+            self.dirs.insert(target.clone());
+        } else {
+            self.files.push(target.clone());
+        }
+        self.binds.push((path.to_path_buf(), target, readonly));
+        self.bound.push(fs::canonicalize(path)?);
+        Ok(())
+    }
+
+    /// `path` 是否已在某个绑定的目录中
+    fn covers(&self, path: &Path) -> bool {
+        self.bound.iter().any(|bound| path.starts_with(bound))
+    }
+}
+
+/// 在 `PATH` 中查找命令，返回其所在的真实路径
+fn resolve_program(cmd: &StdCommand) -> Option<PathBuf> {
+    let program = Path::new(cmd.get_program());
+    if program.components().count() > 1 {
+        let program = match cmd.get_current_dir() {
+            Some(dir) if program.is_relative() => dir.join(program),
+            _ => program.to_path_buf(),
+        };
+        return fs::canonicalize(program).ok();
+    }
+    let path = cmd
+        .get_envs()
+        .find(|(key, _)| *key == "PATH")
+        .and_then(|(_, value)| value.map(|v| v.to_os_string()))
+        .or_else(|| std::env::var_os("PATH"))?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+        .and_then(|candidate| fs::canonicalize(candidate).ok())
+}
+
+/// 在子进程 `exec` 前进入沙箱所需的全部数据。
+///
+/// 所有分配都在 fork 前完成，`enter` 中只调用异步信号安全的系统调用。
+struct Isolation {
+    stack_limit: libc::rlim_t,
+    /// 进入 user namespace 后写入的 `/proc/self/{setgroups,uid_map,gid_map}`
+    id_maps: [(&'static CStr, CString); 3],
+    /// 新根目录（宿主上的空目录），挂载 tmpfs 后在其中搭建最小的文件系统
+    root: CString,
+    /// 需在新根目录中依次创建的目录、空文件（作为文件的挂载点）与符号链接
+    dirs: Vec<CString>,
+    files: Vec<CString>,
+    symlinks: Vec<(CString, CString)>,
+    binds: Vec<Bind>,
+    workdir: CString,
+    /// 依次安装的 seccomp 过滤器
+    filters: [BpfProgram; 2],
+}
+
+impl Isolation {
+    fn prepare(
+        limits: &ResourceLimits,
+        cmd: &StdCommand,
+        root: &Path,
+        workdir: &Path,
+    ) -> Result<Self> {
+        let workdir = fs::canonicalize(workdir)?;
+        let mounts = MountTable::read()?;
+        let mut layout = RootLayout::new(root);
+        for path in SYSTEM_PATHS {
+            let path = Path::new(path);
+            layout.add(path, Some(mounts.flags(path)))?;
+        }
+        for path in DEVICE_PATHS {
+            layout.add(Path::new(path), None)?;
+        }
+        // 程序不在系统目录与工作目录中时（如 /opt 下的解释器），只读绑定其所在目录
+        if let Some(program) = resolve_program(cmd)
+            && !program.starts_with(&workdir)
+            && !layout.covers(&program)
+            && let Some(dir) = program.parent()
+        {
+            layout.add(dir, Some(mounts.flags(dir)))?;
+        }
+        layout.add(&workdir, None)?;
+
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        let id_maps = [
+            (c"/proc/self/setgroups", CString::new("deny")?),
+            (
+                c"/proc/self/uid_map",
+                CString::new(format!("{uid} {uid} 1"))?,
+            ),
+            (
+                c"/proc/self/gid_map",
+                CString::new(format!("{gid} {gid} 1"))?,
+            ),
+        ];
+
+        let cstring = |path: &Path| CString::new(path.as_os_str().as_bytes());
+        Ok(Isolation {
+            stack_limit: limits.memory_limit.unwrap_or(libc::RLIM_INFINITY),
+            id_maps,
+            root: cstring(root)?,
+            dirs: layout
+                .dirs
+                .iter()
+                .map(|dir| cstring(dir))
+                .collect::<Result<_, _>>()?,
+            files: layout
+                .files
+                .iter()
+                .map(|file| cstring(file))
+                .collect::<Result<_, _>>()?,
+            symlinks: layout
+                .symlinks
+                .iter()
+                .map(|(target, link)| Ok((cstring(target)?, cstring(link)?)))
+                .collect::<Result<_, std::ffi::NulError>>()?,
+            binds: layout
+                .binds
+                .iter()
+                .map(|(source, target, readonly)| {
+                    Ok(Bind {
+                        source: cstring(source)?,
+                        target: cstring(target)?,
+                        readonly: *readonly,
+                    })
+                })
+                .collect::<Result<_, std::ffi::NulError>>()?,
+            workdir: cstring(&workdir)?,
+            filters: build_filters()?,
+        })
+    }
+
+    /// 在子进程中执行：设置 rlimit、进入 namespace 并写入 uid/gid 映射，
+    /// fork 出新 PID namespace 中的 1 号进程，在新根目录中搭建最小文件系统并切换根目录，
+    /// 最后安装 seccomp。
+    fn enter(&self) -> io::Result<()> {
+        set_rlimit(libc::RLIMIT_STACK, self.stack_limit)?;
+        set_rlimit(libc::RLIMIT_FSIZE, OUTPUT_LIMIT)?;
+        set_rlimit(libc::RLIMIT_CORE, 0)?;
+
+        // 总是进入 user namespace：即使以 root 运行，沙箱内的权限也只在该 namespace 中有效
+        check(unsafe {
+            libc::unshare(
+                libc::CLONE_NEWUSER
+                    | libc::CLONE_NEWNS
+                    | libc::CLONE_NEWPID
+                    | libc::CLONE_NEWNET
+                    | libc::CLONE_NEWIPC
+                    | libc::CLONE_NEWUTS,
+            )
+        } as i64)?;
+        for (path, content) in &self.id_maps {
+            write_file(path, content)?;
+        }
+
+        // 新 PID namespace 只对之后 fork 的进程生效：程序在其中作为 1 号进程运行，
+        // `kill(-1, ...)` 等只能影响沙箱内的进程
+        let pid = unsafe { libc::fork() };
+        check(pid as i64)?;
+        if pid > 0 {
+            wait_program(pid);
+        }
+        // 中间进程被杀死（如超时）时程序随之结束
+        check(unsafe { libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) } as i64)?;
+
+        // 挂载变更不传播到宿主
+        mount(None, c"/", None, libc::MS_REC | libc::MS_PRIVATE)?;
+        mount(
+            Some(c"tmpfs"),
+            &self.root,
+            Some(c"tmpfs"),
+            libc::MS_NOSUID | libc::MS_NODEV,
+        )?;
+        for dir in &self.dirs {
+            let ret = unsafe { libc::mkdir(dir.as_ptr(), 0o755) };
+            if ret < 0 && io::Error::last_os_error().raw_os_error() != Some(libc::EEXIST) {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        for file in &self.files {
+            let fd = unsafe {
+                libc::open(
+                    file.as_ptr(),
+                    libc::O_CREAT | libc::O_WRONLY | libc::O_CLOEXEC,
+                    0o644,
+                )
+            };
+            check(fd as i64)?;
+            unsafe { libc::close(fd) };
+        }
+        for (target, link) in &self.symlinks {
+            check(unsafe { libc::symlink(target.as_ptr(), link.as_ptr()) } as i64)?;
+        }
+        for bind in &self.binds {
+            mount(
+                Some(&bind.source),
+                &bind.target,
+                None,
+                libc::MS_BIND | libc::MS_REC,
+            )?;
+            if let Some(flags) = bind.readonly {
+                mount(
+                    None,
+                    &bind.target,
+                    None,
+                    libc::MS_REMOUNT | libc::MS_BIND | libc::MS_RDONLY | flags,
+                )?;
+            }
+        }
+
+        // 切换到新根目录并卸载原来的根目录，之后只能访问上面绑定的路径
+        check(unsafe { libc::chdir(self.root.as_ptr()) } as i64)?;
+        check(unsafe { libc::syscall(libc::SYS_pivot_root, c".".as_ptr(), c".".as_ptr()) })?;
+        check(unsafe { libc::umount2(c".".as_ptr(), libc::MNT_DETACH) } as i64)?;
+        mount(
+            None,
+            c"/",
+            None,
+            libc::MS_REMOUNT | libc::MS_RDONLY | libc::MS_NOSUID | libc::MS_NODEV,
+        )?;
+        check(unsafe { libc::chdir(self.workdir.as_ptr()) } as i64)?;
+
+        for filter in &self.filters {
+            seccompiler::apply_filter(filter)
+                .map_err(|_| io::Error::from_raw_os_error(libc::EPERM))?;
+        }
+        Ok(())
+    }
+}
+
+/// 构建 seccomp 过滤器：先安装的过滤器使 `clone3` 返回 ENOSYS，
+/// 让 libc 退回到可检查 flag 的 `clone`；后安装的过滤器只放行白名单中的系统调用，
+/// 并禁止 `clone` 创建新的 namespace。
+fn build_filters() -> Result<[BpfProgram; 2]> {
+    let arch = std::env::consts::ARCH
+        .try_into()
+        .map_err(|e| anyhow!("seccomp 不支持当前架构：{}", e))?;
+    let compile = |filter: SeccompFilter| -> Result<BpfProgram> {
+        filter
+            .try_into()
+            .map_err(|e| anyhow!("无法编译 seccomp 过滤器：{}", e))
+    };
+
+    let clone3 = SeccompFilter::new(
+        BTreeMap::from([(libc::SYS_clone3, vec![])]),
+        SeccompAction::Allow,
+        SeccompAction::Errno(libc::ENOSYS as u32),
+        arch,
+    )
+    .map_err(|e| anyhow!("无法构建 seccomp 过滤器：{}", e))?;
+
+    let mut rules: BTreeMap<i64, Vec<SeccompRule>> = ALLOWED_SYSCALLS
+        .iter()
+        .chain(ALLOWED_LEGACY_SYSCALLS)
+        .map(|&syscall| (syscall, vec![]))
+        .collect();
+    // 多个过滤器都返回错误码时以后安装的为准，`clone3` 在此放行，由前一个过滤器返回 ENOSYS
+    rules.insert(libc::SYS_clone3, vec![]);
+    rules.insert(
+        libc::SYS_clone,
+        vec![
+            SeccompRule::new(vec![
+                SeccompCondition::new(
+                    0,
+                    SeccompCmpArgLen::Qword,
+                    SeccompCmpOp::MaskedEq(NAMESPACE_FLAGS as u64),
+                    0,
+                )
+                .map_err(|e| anyhow!("无法构建 seccomp 规则：{}", e))?,
+            ])
+            .map_err(|e| anyhow!("无法构建 seccomp 规则：{}", e))?,
+        ],
+    );
+    let allowlist = SeccompFilter::new(
+        rules,
+        SeccompAction::Errno(libc::EPERM as u32),
+        SeccompAction::Allow,
+        arch,
+    )
+    .map_err(|e| anyhow!("无法构建 seccomp 过滤器：{}", e))?;
+
+    Ok([compile(clone3)?, compile(allowlist)?])
+}
+
+#[cfg(target_env = "gnu")]
+type RlimitResource = libc::__rlimit_resource_t;
+#[cfg(not(target_env = "gnu"))]
+type RlimitResource = libc::c_int;
+
+fn check(ret: i64) -> io::Result<()> {
+    if ret < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// 中间进程：留在原 PID namespace 中等待程序结束，以相同的退出码或信号结束自身。
+///
+/// 先关闭继承的文件描述符，使 spawn 用于报告 exec 错误的管道与程序的标准输入输出
+/// 只由程序持有。
+fn wait_program(pid: libc::pid_t) -> ! {
+    unsafe {
+        libc::syscall(libc::SYS_close_range, 0, libc::c_uint::MAX, 0);
+        let mut status = 0;
+        while libc::waitpid(pid, &mut status, 0) < 0 {
+            if io::Error::last_os_error().raw_os_error() != Some(libc::EINTR) {
+                libc::_exit(127);
+            }
+        }
+        if libc::WIFSIGNALED(status) {
+            let signal = libc::WTERMSIG(status);
+            let mut mask = std::mem::zeroed();
+            libc::sigemptyset(&mut mask);
+            libc::sigprocmask(libc::SIG_SETMASK, &mask, std::ptr::null_mut());
+            libc::signal(signal, libc::SIG_DFL);
+            libc::kill(libc::getpid(), signal);
+            libc::_exit(128 + signal);
+        }
+        libc::_exit(libc::WEXITSTATUS(status))
+    }
+}
+
+fn set_rlimit(resource: RlimitResource, limit: libc::rlim_t) -> io::Result<()> {
+    let rlimit = libc::rlimit {
+        rlim_cur: limit,
+        rlim_max: limit,
+    };
+    check(unsafe { libc::setrlimit(resource, &rlimit) } as i64)
+}
+
+fn mount(
+    source: Option<&CStr>,
+    target: &CStr,
+    fstype: Option<&CStr>,
+    flags: libc::c_ulong,
+) -> io::Result<()> {
+    check(unsafe {
+        libc::mount(
+            source.map_or(std::ptr::null(), CStr::as_ptr),
+            target.as_ptr(),
+            fstype.map_or(std::ptr::null(), CStr::as_ptr),
+            flags,
+            std::ptr::null(),
+        )
+    } as i64)
+}
+
+/// 将 `content` 写入 `path`，用于写入 `/proc` 中的映射文件
+fn write_file(path: &CStr, content: &CStr) -> io::Result<()> {
+    let fd = unsafe { libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC) };
+    check(fd as i64)?;
+    let bytes = content.to_bytes();
+    let written = unsafe { libc::write(fd, bytes.as_ptr().cast(), bytes.len()) };
+    unsafe { libc::close(fd) };
+    check(written as i64)
+}
+
+/// Linux 沙箱执行后端。
+///
+/// 以 cgroup v2 统计 CPU 时间与峰值内存。TLE 总按 CPU 时间判定，不受
+/// [`TimeMode`](tuack_lib::utils::compiler::TimeMode) 影响；
+/// 墙钟时间上限只用于防止程序长时间阻塞。
+///
+/// 程序在新的 user、mount、PID、network 等 namespace 中作为 1 号进程运行，
+/// 根目录切换为只含系统库（只读）、常用设备文件与工作目录（可写）的 tmpfs，
+/// 系统调用受 seccomp 白名单限制。
+pub struct Sandbox {
+    limits: ResourceLimits,
+    workdir: PathBuf,
+}
+
+impl Sandbox {
+    pub fn new(limits: ResourceLimits, workdir: impl Into<PathBuf>) -> Self {
+        Self {
+            limits,
+            workdir: workdir.into(),
+        }
+    }
+
+    /// CPU 时间超过时间限制，或墙钟时间超过上限
+    fn exceeds_time(&self, cpu_time: Duration, wall_time: Duration) -> bool {
+        self.limits.time_limit.is_some_and(|limit| cpu_time > limit)
+            || self
+                .limits
+                .wall_time_limit
+                .is_some_and(|limit| wall_time > limit)
+    }
+
+    /// 在沙箱中运行命令，返回结束状态、CPU 时间、墙钟时间和峰值内存。
+    pub async fn run(self, mut cmd: StdCommand) -> Result<ProcessOutcome> {
+        let cgroup = Cgroup::create(cgroup_base()?)?;
+        if let Some(memory_limit) = self.limits.memory_limit {
+            cgroup.write("memory.max", memory_limit)?;
+            // 未开启 swap 记账时不存在该文件
+            let _ = cgroup.write("memory.swap.max", 0);
+        }
+        cgroup.write("pids.max", PIDS_LIMIT)?;
+
+        let cgroup_procs = fs::OpenOptions::new()
+            .write(true)
+            .open(cgroup.path.join("cgroup.procs"))?;
+        let root = TempDir::with_prefix("tuack-ng-sandbox-")?;
+        let isolation = Isolation::prepare(&self.limits, &cmd, root.path(), &self.workdir)?;
+        // SAFETY: `join_cgroup` 与 `enter` 只调用异步信号安全的系统调用，不分配内存
+        unsafe {
+            cmd.pre_exec(move || {
+                join_cgroup(&cgroup_procs)?;
+                isolation.enter()
+            });
+        }
+
+        let mut child = TokioCommand::from(cmd)
+            .spawn()
+            .context("无法在沙箱中启动程序")?;
+
+        let start = Instant::now();
+        let memory_limit = self.limits.memory_limit.unwrap_or(u64::MAX);
+
+        let exit_status = loop {
+            tokio::select! {
+                status = child.wait() => break Some(status),
+                _ = sleep(POLL_INTERVAL) => {
+                    if self.exceeds_time(cgroup.cpu_time()?, start.elapsed()) {
+                        cgroup.kill();
+                        let _ = child.kill().await;
+                        break None;
+                    }
+                }
+            }
+        };
+
+        let wall_time = start.elapsed();
+        let cpu_time = cgroup.cpu_time()?;
+        let peak_memory = cgroup.memory_peak();
+
+        if exit_status.is_none() || self.exceeds_time(cpu_time, wall_time) {
+            info!(
+                "进程超时：CPU 时间 {}，墙钟时间 {}",
+                cpu_time.as_secs_f64(),
+                wall_time.as_secs_f64()
+            );
+            return Ok(ProcessOutcome {
+                status: RunStatus::TimeLimitExceeded,
+                cpu_time: None,
+                wall_time: None,
+                memory: peak_memory,
+            });
+        }
+
+        if cgroup.oom_killed()? || peak_memory.is_some_and(|peak| peak > memory_limit) {
+            info!("进程内存超限");
+            return Ok(ProcessOutcome {
+                status: RunStatus::MemoryLimitExceeded,
+                cpu_time: None,
+                wall_time: None,
+                memory: peak_memory,
+            });
+        }
+
+        let exit_status = exit_status.unwrap();
+        info!(
+            "进程结束，CPU 时间：{:?}，墙钟时间：{:?}, 峰值内存：{:?}, 退出码：{:?}",
+            cpu_time,
+            wall_time,
+            peak_memory,
+            exit_status.as_ref().ok().and_then(|s| s.code())
+        );
+        let status = match exit_status {
+            Ok(status) if status.success() => RunStatus::Success,
+            Ok(status) => RunStatus::NonZeroExit(status.code().unwrap_or(-1)),
+            Err(e) => {
+                return Ok(ProcessOutcome {
+                    status: RunStatus::InternalError(e.into()),
+                    cpu_time: None,
+                    wall_time: None,
+                    memory: None,
+                });
+            }
+        };
+        Ok(ProcessOutcome {
+            status,
+            cpu_time: Some(cpu_time),
+            wall_time: Some(wall_time),
+            memory: peak_memory,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 在沙箱中以 `sh -c` 运行脚本；当前环境不允许创建 user namespace 时返回 `None`
+    fn run_isolated(workdir: &Path, script: &str) -> Option<std::process::ExitStatus> {
+        let root = TempDir::with_prefix("tuack-ng-sandbox-test-").unwrap();
+        let mut cmd = StdCommand::new("sh");
+        cmd.arg("-c").arg(script).current_dir(workdir);
+        let isolation =
+            Isolation::prepare(&ResourceLimits::unlimited(), &cmd, root.path(), workdir).unwrap();
+        unsafe {
+            cmd.pre_exec(move || isolation.enter());
+        }
+        match cmd.status() {
+            Ok(status) => Some(status),
+            Err(e) if e.raw_os_error() == Some(libc::EPERM) => {
+                eprintln!("当前环境无法创建 namespace，跳过：{}", e);
+                None
+            }
+            Err(e) => panic!("无法在沙箱中启动程序：{}", e),
+        }
+    }
+
+    #[test]
+    fn only_workdir_and_system_paths_are_visible() {
+        let outside = TempDir::new().unwrap();
+        let secret = outside.path().join("secret");
+        fs::write(&secret, "secret").unwrap();
+        let workdir = TempDir::new().unwrap();
+
+        let script = format!(
+            "test ! -e {} && test ! -e /root && test -x /usr/bin/env && echo ok > out",
+            secret.display()
+        );
+        let Some(status) = run_isolated(workdir.path(), &script) else {
+            return;
+        };
+        assert!(status.success());
+        assert_eq!(
+            fs::read_to_string(workdir.path().join("out")).unwrap(),
+            "ok\n"
+        );
+    }
+
+    #[test]
+    fn system_paths_are_readonly() {
+        let workdir = TempDir::new().unwrap();
+        let script =
+            "! touch /usr/tuack-ng-probe 2>/dev/null && ! mkdir /tuack-ng-probe 2>/dev/null";
+        let Some(status) = run_isolated(workdir.path(), script) else {
+            return;
+        };
+        assert!(status.success());
+        assert!(!Path::new("/usr/tuack-ng-probe").exists());
+    }
+
+    #[test]
+    fn ids_are_mapped() {
+        let workdir = TempDir::new().unwrap();
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        let script = format!("test \"$(id -u)\" = {uid} && test \"$(id -g)\" = {gid}");
+        let Some(status) = run_isolated(workdir.path(), &script) else {
+            return;
+        };
+        assert!(status.success());
+    }
+
+    #[test]
+    fn namespaces_cannot_be_created() {
+        if !Path::new("/usr/bin/unshare").exists() {
+            return;
+        }
+        let workdir = TempDir::new().unwrap();
+        let script = "! unshare -U true 2>/dev/null && ! unshare -n true 2>/dev/null";
+        let Some(status) = run_isolated(workdir.path(), script) else {
+            return;
+        };
+        assert!(status.success());
+    }
+
+    #[test]
+    fn program_runs_as_pid_one() {
+        let workdir = TempDir::new().unwrap();
+        let Some(status) = run_isolated(workdir.path(), "test $$ = 1 && exit 3") else {
+            return;
+        };
+        assert_eq!(status.code(), Some(3));
+    }
+}
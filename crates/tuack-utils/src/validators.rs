@@ -1 +1,3 @@
 pub mod cpp;
+pub mod general;
+pub mod helper;
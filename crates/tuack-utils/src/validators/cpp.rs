@@ -1,8 +1,8 @@
-use std::fs::File;
 use std::process::{Command, Stdio};
-use tempfile::{NamedTempFile, TempDir};
+use tempfile::TempDir;
 
 use crate::prelude::*;
+use crate::validators::helper::run_validator;
 use tuack_lib::data::AsyncReader;
 use tuack_lib::utils::testlib::{Validator, ValidatorResult};
 use async_trait::async_trait;
@@ -92,28 +92,6 @@ impl Validator for CppValidator {
 
     async fn validate(&self, input: &mut dyn AsyncReader) -> Result<ValidatorResult> {
         let binary = self.binary_path.as_ref().context("Validator 未编译")?;
-
-        let input_file = NamedTempFile::with_prefix("tuack-ng-validator-in-")?;
-        let mut f = tokio::fs::File::create(input_file.path()).await?;
-        tokio::io::copy(input, &mut f).await?;
-        drop(f);
-        let stdin_file = File::open(input_file.path())?;
-
-        let stderr_file = NamedTempFile::with_prefix("tuack-ng-validator-err-")?;
-        let stderr_f = File::create(stderr_file.path())?;
-
-        let status = Command::new(binary)
-            .stdin(Stdio::from(stdin_file))
-            .stdout(Stdio::null())
-            .stderr(Stdio::from(stderr_f))
-            .status()?;
-
-        let message = fs::read_to_string(stderr_file.path()).unwrap_or_default();
-
-        Ok(if status.success() {
-            ValidatorResult::Ok
-        } else {
-            ValidatorResult::Invalid(message.trim().to_string())
-        })
+        run_validator(Command::new(binary), input).await
     }
 }
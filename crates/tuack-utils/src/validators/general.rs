@@ -0,0 +1,44 @@
+use crate::compilers::program::GeneralProgram;
+use crate::prelude::*;
+use crate::validators::helper::run_validator;
+use async_trait::async_trait;
+use tuack_config::lang::Language;
+use tuack_lib::data::AsyncReader;
+use tuack_lib::utils::testlib::{Validator, ValidatorResult};
+
+/// 按 `langs.json` 中的模板编译、运行的 Validator，用于 C++ 以外的语言
+pub struct GeneralValidator {
+    program: GeneralProgram,
+}
+
+impl GeneralValidator {
+    pub fn new(
+        source: impl Into<PathBuf>,
+        compile_args: &IndexMap<String, String>,
+        program_name: impl Into<String>,
+        dependencies: IndexMap<String, Vec<u8>>,
+        languages: &IndexMap<String, Language>,
+    ) -> Result<Self> {
+        Ok(GeneralValidator {
+            program: GeneralProgram::new(
+                source,
+                compile_args,
+                program_name,
+                dependencies,
+                languages,
+                "tuack-ng-validator-",
+            )?,
+        })
+    }
+}
+
+#[async_trait]
+impl Validator for GeneralValidator {
+    fn prepare(&mut self) -> Result<()> {
+        self.program.prepare("Validator")
+    }
+
+    async fn validate(&self, input: &mut dyn AsyncReader) -> Result<ValidatorResult> {
+        run_validator(self.program.command("Validator")?, input).await
+    }
+}
@@ -0,0 +1,40 @@
+use std::fs::File;
+use std::process::{Command as StdCommand, Stdio};
+use tempfile::NamedTempFile;
+use tokio::process::Command;
+
+use crate::prelude::*;
+use tuack_lib::data::AsyncReader;
+use tuack_lib::utils::testlib::ValidatorResult;
+
+/// 以输入数据为标准输入运行 Validator，退出码非零时以 stderr 作为失败原因
+pub async fn run_validator(
+    cmd: StdCommand,
+    input: &mut dyn AsyncReader,
+) -> Result<ValidatorResult> {
+    let input_file = NamedTempFile::with_prefix("tuack-ng-validator-in-")?;
+    let mut f = tokio::fs::File::create(input_file.path()).await?;
+    tokio::io::copy(input, &mut f).await?;
+    drop(f);
+    let stdin_file = File::open(input_file.path())?;
+
+    let stderr_file = NamedTempFile::with_prefix("tuack-ng-validator-err-")?;
+    let stderr_f = File::create(stderr_file.path())?;
+
+    let status = Command::from(cmd)
+        .stdin(Stdio::from(stdin_file))
+        .stdout(Stdio::null())
+        .stderr(Stdio::from(stderr_f))
+        .status()
+        .await?;
+
+    let message = tokio::fs::read_to_string(stderr_file.path())
+        .await
+        .unwrap_or_default();
+
+    Ok(if status.success() {
+        ValidatorResult::Ok
+    } else {
+        ValidatorResult::Invalid(message.trim().to_string())
+    })
+}